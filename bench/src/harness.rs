@@ -0,0 +1,622 @@
+use std::path::Path;
+
+use anchor_lang::{
+    solana_program::{hash::hashv, program_option::COption, program_pack::Pack},
+    AccountSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::{anyhow, Result};
+use bonk_paper_scissors::{
+    accounts,
+    constants::{
+        ADMIN, ALLOWED_COLLECTION, BPS_SETTINGS_V2, BPS_SETTINGS_V3, ESCROW, FEE_VAULT,
+        FIRST_PLAYER, GAME, METADATA, OPEN_GAMES, REVENUE_SPLIT, SECOND_PLAYER, SIDE_BET,
+        STAKE_LIMITS,
+    },
+    instruction,
+    state::{BpsSettingsV2, Choice, GameV3, SidePool},
+    utils::TOKEN_METADATA_PROGRAM_ID,
+};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    message::Message,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::Transaction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account;
+
+pub const BONK_MINT: Pubkey = pubkey!("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263");
+pub const TREASURY: Pubkey = pubkey!("bpstzWLPDetyjiD33HPGGE96MzkhEA7dhRFzhc8Ay5R");
+pub const TIME_FOR_PENALIZATION: i64 = 7 * 24 * 60 * 60;
+pub const PLAYER_FEE_LAMPORTS: u64 = LAMPORTS_PER_SOL / 40;
+pub const RELAYER_FEE_LAMPORTS: u64 = LAMPORTS_PER_SOL / 1_000;
+pub const STAKE: u64 = 1_000;
+// Same addresses as the `tests/fixtures` NFTs.
+pub const NFT_COLLECTION: Pubkey = pubkey!("FY4r1Zb3Eo8EfiAZ7RQjF8LLq9A6Kiz9n3kMybJ11LAL");
+pub const NFT_MINTS: [Pubkey; 2] = [
+    pubkey!("GQFjroRubu7wJqNAGtvpoMfjwpETtigSECM8E3z7gkBe"),
+    pubkey!("CBf7zsBJcCmjrd6NrhQgMx2qwydPE1Uo8WBMaBQ2oUam"),
+];
+const PLAYER_TOKENS: u64 = 1_000_000;
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &bonk_paper_scissors::ID).0
+}
+
+pub fn pda_bump(seeds: &[&[u8]]) -> u8 {
+    Pubkey::find_program_address(seeds, &bonk_paper_scissors::ID).1
+}
+
+pub fn game_address(first_player: &Pubkey, game_id: &str) -> Pubkey {
+    pda(&[GAME.as_ref(), first_player.as_ref(), game_id.as_bytes()])
+}
+
+pub fn escrow_address(owner: &Pubkey, player_seed: &[u8]) -> Pubkey {
+    pda(&[owner.as_ref(), ESCROW.as_ref(), player_seed])
+}
+
+pub fn bps_settings_v2_address() -> Pubkey {
+    pda(&[BPS_SETTINGS_V2.as_ref()])
+}
+
+pub fn bps_settings_address() -> Pubkey {
+    pda(&[BPS_SETTINGS_V3.as_ref()])
+}
+
+pub fn stake_limits_address(mint: &Pubkey) -> Pubkey {
+    pda(&[STAKE_LIMITS.as_ref(), mint.as_ref()])
+}
+
+pub fn open_games_address(player: &Pubkey) -> Pubkey {
+    pda(&[OPEN_GAMES.as_ref(), player.as_ref()])
+}
+
+pub fn fee_vault_address() -> Pubkey {
+    pda(&[FEE_VAULT.as_ref()])
+}
+
+pub fn revenue_split_address() -> Pubkey {
+    pda(&[REVENUE_SPLIT.as_ref()])
+}
+
+pub fn side_bet_address(side_pool: &Pubkey, bettor: &Pubkey) -> Pubkey {
+    pda(&[SIDE_BET.as_ref(), side_pool.as_ref(), bettor.as_ref()])
+}
+
+pub fn allowed_collection_address() -> Pubkey {
+    pda(&[ALLOWED_COLLECTION.as_ref(), NFT_COLLECTION.as_ref()])
+}
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            METADATA.as_ref(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Metaplex metadata with `mint` verified in `NFT_COLLECTION`, only the fields the program reads.
+fn metadata_account(mint: &Pubkey) -> Account {
+    let mut data = vec![4]; // MetadataV1
+    data.extend_from_slice(Pubkey::default().as_ref()); // update_authority
+    data.extend_from_slice(mint.as_ref());
+    for field in ["BPS", "BPS", ""] {
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    // seller_fee_basis_points, creators, primary_sale_happened, is_mutable, edition_nonce,
+    // token_standard
+    data.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+    data.extend_from_slice(&[1, 1]); // verified collection
+    data.extend_from_slice(NFT_COLLECTION.as_ref());
+    data.extend_from_slice(&[0, 0, 0]); // uses, collection_details, programmable_config
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: TOKEN_METADATA_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn mint_account(mint_authority: &Pubkey, decimals: u8) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply: 0,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: bonk_paper_scissors::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Serializes an Anchor account the way the program would have created it.
+pub fn program_account(account: &impl AccountSerialize, space: usize) -> Result<Account> {
+    let mut data = Vec::with_capacity(space);
+    account.try_serialize(&mut data)?;
+    data.resize(space, 0);
+    Ok(Account {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: bonk_paper_scissors::ID,
+        executable: false,
+        rent_epoch: 0,
+    })
+}
+
+pub struct Player {
+    pub keypair: Keypair,
+    pub token_account: Pubkey,
+}
+
+impl Player {
+    pub fn key(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+}
+
+/// A committed choice, revealed later with the same salt.
+pub struct Move {
+    pub choice: Choice,
+    pub salt: [u8; 32],
+}
+
+impl Move {
+    pub fn new(choice: Choice) -> Self {
+        Self {
+            choice,
+            salt: Pubkey::new_unique().to_bytes(),
+        }
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        hashv(&[&[self.choice as u8], &self.salt]).to_bytes()
+    }
+}
+
+pub struct Bench {
+    context: ProgramTestContext,
+    mint_authority: Keypair,
+    pub results: Vec<(String, u64)>,
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<u64> {
+    let blockhash = context.banks_client.get_latest_blockhash().await?;
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await?;
+    result.result?;
+    result
+        .metadata
+        .map(|metadata| metadata.compute_units_consumed)
+        .ok_or_else(|| anyhow!("Transaction metadata is missing"))
+}
+
+impl Bench {
+    /// Loads the program built by `anchor build`, the Bonk mint, the NFTs of a collection, the
+    /// funded admin and the settings it migrates. `accounts` are preloaded as well.
+    pub async fn new(accounts: Vec<(Pubkey, Account)>) -> Result<Self> {
+        let deploy_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/deploy");
+        if !deploy_dir.join("bonk_paper_scissors.so").exists() {
+            return Err(anyhow!("Program not found, run `anchor build` first"));
+        }
+        std::env::set_var("BPF_OUT_DIR", deploy_dir);
+
+        let mut program_test =
+            ProgramTest::new("bonk_paper_scissors", bonk_paper_scissors::ID, None);
+        program_test.prefer_bpf(true);
+
+        let mint_authority = Keypair::new();
+        program_test.add_account(BONK_MINT, mint_account(&mint_authority.pubkey(), 5));
+        for mint in NFT_MINTS {
+            program_test.add_account(mint, mint_account(&mint_authority.pubkey(), 0));
+            program_test.add_account(metadata_address(&mint), metadata_account(&mint));
+        }
+        // Pays for what the admin creates. As the treasury it also gets what a revenue split
+        // leaves over, which can be less than its rent.
+        program_test.add_account(
+            ADMIN,
+            Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+        // Settings from before `BpsSettingsV3`, the admin migrates them.
+        program_test.add_account(
+            bps_settings_v2_address(),
+            program_account(
+                &BpsSettingsV2::new(
+                    pda_bump(&[BPS_SETTINGS_V2.as_ref()]),
+                    TIME_FOR_PENALIZATION,
+                    TREASURY,
+                    PLAYER_FEE_LAMPORTS,
+                ),
+                BpsSettingsV2::size(),
+            )?,
+        );
+        for (address, account) in accounts {
+            program_test.add_account(address, account);
+        }
+
+        Ok(Self {
+            context: program_test.start_with_context().await,
+            mint_authority,
+            results: vec![],
+        })
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Runs a setup transaction, its compute units aren't recorded.
+    pub async fn execute(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<()> {
+        process(&mut self.context, instructions, signers).await?;
+        Ok(())
+    }
+
+    /// Runs `instruction` on its own and records the compute units it consumed as `name`.
+    pub async fn measure(
+        &mut self,
+        name: &str,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<()> {
+        let units = process(&mut self.context, &[instruction], signers)
+            .await
+            .map_err(|err| anyhow!("{} failed: {}", name, err))?;
+        self.results.push((name.to_string(), units));
+        Ok(())
+    }
+
+    /// Simulates `instruction` with the admin as a signer and records the compute units it
+    /// consumed as `name`. Simulations don't check signatures, so the admin key isn't needed, but
+    /// nothing the instruction writes is kept, `set_account` stores it instead.
+    pub async fn measure_as_admin(&mut self, name: &str, instruction: Instruction) -> Result<()> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let mut transaction = Transaction::new_unsigned(Message::new(
+            &[instruction],
+            Some(&self.context.payer.pubkey()),
+        ));
+        transaction.try_partial_sign(&[&self.context.payer], blockhash)?;
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await?;
+        simulation
+            .result
+            .ok_or_else(|| anyhow!("{} wasn't simulated", name))?
+            .map_err(|err| anyhow!("{} failed: {}", name, err))?;
+        let units = simulation
+            .simulation_details
+            .map(|details| details.units_consumed)
+            .ok_or_else(|| anyhow!("Simulation details are missing"))?;
+        self.results.push((name.to_string(), units));
+        Ok(())
+    }
+
+    /// Stores `account` as if the admin instruction measured before it had created it.
+    pub fn set_account(&mut self, address: &Pubkey, account: Account) {
+        self.context.set_account(address, &account.into());
+    }
+
+    pub async fn now(&mut self) -> Result<i64> {
+        let clock: Clock = self.context.banks_client.get_sysvar().await?;
+        Ok(clock.unix_timestamp)
+    }
+
+    /// When `game` was created, which seeds its side pool.
+    pub async fn game_created_at(&mut self, game: &Pubkey) -> Result<i64> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*game)
+            .await?
+            .ok_or_else(|| anyhow!("game {} doesn't exist", game))?;
+        let offset = GameV3::CREATED_AT_OFFSET;
+        Ok(i64::from_le_bytes(
+            account.data[offset..offset + 8].try_into()?,
+        ))
+    }
+
+    pub async fn advance_clock(&mut self, seconds: i64) -> Result<()> {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await?;
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+        Ok(())
+    }
+
+    /// A funded player holding Bonk in its associated token account.
+    pub async fn player(&mut self) -> Result<Player> {
+        self.player_with(Keypair::new()).await
+    }
+
+    /// Same as `player`, for a keypair preloaded accounts already refer to.
+    pub async fn player_with(&mut self, keypair: Keypair) -> Result<Player> {
+        let token_account = get_associated_token_address(&keypair.pubkey(), &BONK_MINT);
+        let payer = self.payer();
+        let instructions = [
+            system_instruction::transfer(&payer, &keypair.pubkey(), 10 * LAMPORTS_PER_SOL),
+            create_associated_token_account(&payer, &keypair.pubkey(), &BONK_MINT, &spl_token::ID),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &BONK_MINT,
+                &token_account,
+                &self.mint_authority.pubkey(),
+                &[],
+                PLAYER_TOKENS,
+            )?,
+        ];
+        process(&mut self.context, &instructions, &[&self.mint_authority]).await?;
+        Ok(Player {
+            keypair,
+            token_account,
+        })
+    }
+
+    /// Mints the NFT to `player`, returning its associated token account.
+    pub async fn mint_nft(&mut self, player: &Player, mint: &Pubkey) -> Result<Pubkey> {
+        let token_account = get_associated_token_address(&player.key(), mint);
+        let payer = self.payer();
+        let instructions = [
+            create_associated_token_account(&payer, &player.key(), mint, &spl_token::ID),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                mint,
+                &token_account,
+                &self.mint_authority.pubkey(),
+                &[],
+                1,
+            )?,
+        ];
+        process(&mut self.context, &instructions, &[&self.mint_authority]).await?;
+        Ok(token_account)
+    }
+
+    pub fn first_player_move(
+        &self,
+        player: &Player,
+        game_id: &str,
+        first_move: &Move,
+    ) -> Instruction {
+        self.first_player_move_with(player, game_id, first_move, None, false)
+    }
+
+    /// Creates a game played with `ruleset` instead of Bonk-Paper-Scissors.
+    pub fn first_player_move_with_ruleset(
+        &self,
+        player: &Player,
+        game_id: &str,
+        first_move: &Move,
+        ruleset: Option<Pubkey>,
+    ) -> Instruction {
+        self.first_player_move_with(player, game_id, first_move, ruleset, false)
+    }
+
+    /// Creates a game the first player opted in to relayed reveals for.
+    pub fn first_player_move_relayed(
+        &self,
+        player: &Player,
+        game_id: &str,
+        first_move: &Move,
+    ) -> Instruction {
+        self.first_player_move_with(player, game_id, first_move, None, true)
+    }
+
+    fn first_player_move_with(
+        &self,
+        player: &Player,
+        game_id: &str,
+        first_move: &Move,
+        ruleset: Option<Pubkey>,
+        use_relayer: bool,
+    ) -> Instruction {
+        let game = game_address(&player.key(), game_id);
+        ix(
+            accounts::FirstPlayerMove {
+                game,
+                first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+                first_player_token_account: player.token_account,
+                mint: BONK_MINT,
+                stake_limits: stake_limits_address(&BONK_MINT),
+                bps_settings_v3: bps_settings_address(),
+                ruleset,
+                first_player_open_games: open_games_address(&player.key()),
+                first_player: player.key(),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::FirstPlayerMove {
+                game_id: game_id.to_string(),
+                amount: STAKE,
+                first_player_hash: first_move.hash(),
+                use_relayer,
+            },
+        )
+    }
+
+    /// Side pool of `game` as it is now, whether or not it was opened.
+    pub async fn side_pool_address(&mut self, game: &Pubkey) -> Result<Pubkey> {
+        let game_created_at = self.game_created_at(game).await?;
+        Ok(SidePool::address(game, game_created_at))
+    }
+
+    pub async fn second_player_move(
+        &mut self,
+        game: &Pubkey,
+        player: &Player,
+        second_move: &Move,
+    ) -> Result<Instruction> {
+        let side_pool = self.side_pool_address(game).await?;
+        Ok(ix(
+            accounts::SecondPlayerMove {
+                game: *game,
+                second_player_escrow: escrow_address(game, SECOND_PLAYER.as_ref()),
+                second_player_token_account: player.token_account,
+                mint: BONK_MINT,
+                second_player: player.key(),
+                second_player_side_bet: side_bet_address(&side_pool, &player.key()),
+                bps_settings_v3: bps_settings_address(),
+                second_player_open_games: open_games_address(&player.key()),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::SecondPlayerMove {
+                second_player_hash: second_move.hash(),
+                use_relayer: false,
+            },
+        ))
+    }
+
+    pub fn reveal(&self, game: &Pubkey, player: &Pubkey, player_move: &Move) -> Instruction {
+        ix(
+            accounts::Reveal {
+                game: *game,
+                player: *player,
+                session_token: None,
+                system_program: system_program::ID,
+            },
+            instruction::Reveal {
+                choice: player_move.choice as u8,
+                salt: player_move.salt,
+            },
+        )
+    }
+
+    pub fn claim(
+        &self,
+        game: &Pubkey,
+        first_player: &Player,
+        second_player: &Player,
+    ) -> Instruction {
+        self.claim_in_season(game, first_player, second_player, None)
+    }
+
+    /// Claims `game`, crediting its winner on the leaderboard of `season`.
+    pub fn claim_in_season(
+        &self,
+        game: &Pubkey,
+        first_player: &Player,
+        second_player: &Player,
+        season: Option<Pubkey>,
+    ) -> Instruction {
+        ix(
+            accounts::Claim {
+                game: *game,
+                first_player_open_games: Some(open_games_address(&first_player.key())),
+                second_player_open_games: Some(open_games_address(&second_player.key())),
+                bps_settings_v3: bps_settings_address(),
+                fee_vault: fee_vault_address(),
+                season,
+                first_player_escrow: escrow_address(game, FIRST_PLAYER.as_ref()),
+                first_player_token_account: first_player.token_account,
+                second_player_escrow: escrow_address(game, SECOND_PLAYER.as_ref()),
+                second_player_token_account: second_player.token_account,
+                mint: BONK_MINT,
+                first_player: first_player.key(),
+                second_player: second_player.key(),
+                payer: self.payer(),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::Claim {},
+        )
+    }
+
+    /// Creates and joins a game, returning its address.
+    pub async fn start_game(
+        &mut self,
+        game_id: &str,
+        first_player: &Player,
+        second_player: &Player,
+        first_move: &Move,
+        second_move: &Move,
+    ) -> Result<Pubkey> {
+        let game = game_address(&first_player.key(), game_id);
+        let instruction = self.first_player_move(first_player, game_id, first_move);
+        self.execute(&[instruction], &[&first_player.keypair])
+            .await?;
+        let instruction = self
+            .second_player_move(&game, second_player, second_move)
+            .await?;
+        self.execute(&[instruction], &[&second_player.keypair])
+            .await?;
+        Ok(game)
+    }
+
+    /// Starts a game and reveals both moves, leaving it ready to be claimed.
+    pub async fn play_game(
+        &mut self,
+        game_id: &str,
+        first_player: &Player,
+        second_player: &Player,
+        first_move: &Move,
+        second_move: &Move,
+    ) -> Result<Pubkey> {
+        let game = self
+            .start_game(
+                game_id,
+                first_player,
+                second_player,
+                first_move,
+                second_move,
+            )
+            .await?;
+        let instructions = [
+            self.reveal(&game, &first_player.key(), first_move),
+            self.reveal(&game, &second_player.key(), second_move),
+        ];
+        self.execute(
+            &instructions,
+            &[&first_player.keypair, &second_player.keypair],
+        )
+        .await?;
+        Ok(game)
+    }
+}
@@ -0,0 +1,151 @@
+mod harness;
+mod scenarios;
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use harness::Bench;
+
+struct Config {
+    baseline_path: PathBuf,
+    threshold_percent: u64,
+    update: bool,
+    report_path: Option<PathBuf>,
+}
+
+impl Config {
+    fn from_args() -> Result<Self> {
+        let mut config = Config {
+            baseline_path: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("compute_units.json"),
+            threshold_percent: 5,
+            update: false,
+            report_path: None,
+        };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--baseline" => config.baseline_path = PathBuf::from(value()?),
+                "--threshold" => config.threshold_percent = value()?.parse()?,
+                "--update" => config.update = true,
+                "--report" => config.report_path = Some(PathBuf::from(value()?)),
+                _ => return Err(anyhow!("Unknown argument {}", arg)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Runs every scenario against the built program and compares the compute units each
+/// instruction consumed with the baseline, failing when one grew past the threshold or has no
+/// baseline yet.
+/// Markdown table of the units every instruction consumed before and after, instructions missing
+/// on one side are marked as added or removed.
+fn report(baseline: &BTreeMap<String, u64>, results: &[(String, u64)]) -> String {
+    let results: BTreeMap<&str, u64> = results
+        .iter()
+        .map(|(name, units)| (name.as_str(), *units))
+        .collect();
+    let mut names: Vec<&str> = baseline.keys().map(String::as_str).collect();
+    names.extend(results.keys().filter(|name| !baseline.contains_key(**name)));
+    names.sort_unstable();
+
+    let mut table =
+        String::from("| Instruction | Before | After | Change |\n| --- | ---: | ---: | ---: |\n");
+    for name in names {
+        let row = match (baseline.get(name), results.get(name)) {
+            (Some(before), Some(after)) => {
+                let change = (*after as f64 - *before as f64) / *before as f64 * 100.0;
+                format!("| `{}` | {} | {} | {:+.1}% |", name, before, after, change)
+            }
+            (Some(before), None) => format!("| `{}` | {} | - | removed |", name, before),
+            (None, Some(after)) => format!("| `{}` | - | {} | added |", name, after),
+            (None, None) => continue,
+        };
+        table.push_str(&row);
+        table.push('\n');
+    }
+    table
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let config = Config::from_args()?;
+    let baseline: BTreeMap<String, u64> = match fs::read_to_string(&config.baseline_path) {
+        Ok(contents) => serde_json::from_str(&contents)?,
+        Err(_) => BTreeMap::new(),
+    };
+
+    let mut accounts = vec![
+        scenarios::legacy_game_account()?,
+        scenarios::legacy_first_player_account(),
+    ];
+    accounts.extend(scenarios::closed_season_accounts()?);
+    let mut bench = Bench::new(accounts).await?;
+    scenarios::run_all(&mut bench).await?;
+
+    let mut regressions = vec![];
+    let mut unmeasured = vec![];
+    println!(
+        "{:<28} {:>10} {:>10} {:>8}",
+        "instruction", "baseline", "units", "change"
+    );
+    for (name, units) in &bench.results {
+        match baseline.get(name) {
+            Some(&baseline_units) => {
+                let change =
+                    (*units as f64 - baseline_units as f64) / baseline_units as f64 * 100.0;
+                println!(
+                    "{:<28} {:>10} {:>10} {:>7.1}%",
+                    name, baseline_units, units, change
+                );
+                if *units > baseline_units + baseline_units * config.threshold_percent / 100 {
+                    regressions.push(name.clone());
+                }
+            }
+            None => {
+                println!("{:<28} {:>10} {:>10} {:>8}", name, "-", units, "new");
+                unmeasured.push(name.clone());
+            }
+        }
+    }
+    for name in scenarios::RETIRED {
+        println!("{:<28} retired, always fails", name);
+    }
+
+    if let Some(report_path) = &config.report_path {
+        fs::write(report_path, report(&baseline, &bench.results))?;
+        println!("Report written to {}", report_path.display());
+    }
+    if config.update {
+        let results: BTreeMap<&String, &u64> = bench
+            .results
+            .iter()
+            .map(|(name, units)| (name, units))
+            .collect();
+        fs::write(
+            &config.baseline_path,
+            serde_json::to_string_pretty(&results)? + "\n",
+        )?;
+        println!("Baseline written to {}", config.baseline_path.display());
+        return Ok(());
+    }
+    if !unmeasured.is_empty() {
+        return Err(anyhow!(
+            "No baseline for {}, run with --update to record it",
+            unmeasured.join(", ")
+        ));
+    }
+    if !regressions.is_empty() {
+        return Err(anyhow!(
+            "Compute units grew more than {}% for {}",
+            config.threshold_percent,
+            regressions.join(", ")
+        ));
+    }
+    Ok(())
+}
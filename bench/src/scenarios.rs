@@ -0,0 +1,1844 @@
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::Result;
+use bonk_paper_scissors::{
+    accounts,
+    constants::{
+        ADMIN, ALLOWED_COLLECTION, BPS_SETTINGS_V3, ESCROW, FEE_VAULT, FIRST_PLAYER, GAME,
+        QUEUE_TICKET, REVENUE_SPLIT, RULESET, SEASON, SECOND_PLAYER, SESSION_TOKEN, SOL_VAULT,
+        STAKE_LIMITS, TABLE, TOURNAMENT,
+    },
+    instruction,
+    state::{
+        AllowedCollection, BpsSettingsV3, Choice, FeeVault, Game, GameState, LeaderboardEntry,
+        RevenueRecipient, RevenueSplit, Ruleset, Season, SeasonState, StakeLimits, MAX_CHOICES,
+        PAYOUT_BASIS_POINTS, REFUND_ON_CANCEL, REFUND_ON_FORFEIT, WIN_POINTS,
+    },
+};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{keypair_from_seed, Keypair, Signer},
+    system_program,
+};
+use spl_token::native_mint;
+
+use crate::harness::{
+    allowed_collection_address, bps_settings_address, bps_settings_v2_address, escrow_address,
+    fee_vault_address, game_address, ix, metadata_address, open_games_address, pda, pda_bump,
+    program_account, revenue_split_address, side_bet_address, stake_limits_address, Bench, Move,
+    Player, BONK_MINT, NFT_COLLECTION, NFT_MINTS, PLAYER_FEE_LAMPORTS, RELAYER_FEE_LAMPORTS, STAKE,
+    TIME_FOR_PENALIZATION, TREASURY,
+};
+
+/// Always fail with `InstructionRemoved`, there is nothing to measure.
+pub const RETIRED: [&str; 2] = ["init_bps_settings_v2", "update_bps_settings_v2"];
+
+const LEGACY_GAME_ID: &str = "bench-legacy";
+
+fn legacy_first_player() -> Pubkey {
+    Pubkey::new_from_array([7; 32])
+}
+
+/// The player of the legacy game, `migrate_game` returns the rent it freed to them.
+pub fn legacy_first_player_account() -> (Pubkey, Account) {
+    (
+        legacy_first_player(),
+        Account::new(LAMPORTS_PER_SOL, 0, &system_program::ID),
+    )
+}
+
+/// A game stored with the Borsh layout, as created before `GameV3`.
+pub fn legacy_game_account() -> Result<(Pubkey, Account)> {
+    let (address, bump) = Pubkey::find_program_address(
+        &[
+            GAME.as_ref(),
+            legacy_first_player().as_ref(),
+            LEGACY_GAME_ID.as_bytes(),
+        ],
+        &bonk_paper_scissors::ID,
+    );
+    let game = Game {
+        bump,
+        game_id: LEGACY_GAME_ID.to_string(),
+        mint: BONK_MINT,
+        amount_to_match: STAKE,
+        first_player: legacy_first_player(),
+        first_player_hash: Move::new(Choice::Bonk).hash(),
+        first_player_escrow_address: escrow_address(&address, FIRST_PLAYER.as_ref()),
+        first_player_choice: None,
+        first_player_revealed_at: None,
+        second_player: None,
+        second_player_hash: None,
+        second_player_escrow_address: None,
+        second_player_choice: None,
+        second_player_revealed_at: None,
+        winner: None,
+        loser: None,
+        amount_won: None,
+        amount_burned: None,
+        drawn_at: None,
+        game_state: GameState::CreatedAndWaitingForStart,
+        created_at: 0,
+        previous_game: None,
+        started_at: None,
+        version: 0,
+    };
+    Ok((address, program_account(&game, Game::size())?))
+}
+
+const RULESET_ID: &str = "lizard-spock";
+
+fn ruleset_address() -> Pubkey {
+    pda(&[RULESET.as_ref(), RULESET_ID.as_bytes()])
+}
+
+/// Bonk-Paper-Scissors-Lizard-Spock, each choice beats the two the bits are set for.
+const RULESET_BEATS: [u8; 5] = [0b01100, 0b10001, 0b01010, 0b10010, 0b00101];
+
+const SEASON_ID: &str = "bench-season";
+const ENDING_SEASON_ID: &str = "bench-season-ending";
+const CLOSED_SEASON_ID: &str = "bench-season-closed";
+
+fn season_winner() -> Keypair {
+    keypair_from_seed(&[9; 32]).expect("A 32 bytes seed is enough")
+}
+
+fn season_address(season_id: &str) -> Pubkey {
+    pda(&[SEASON.as_ref(), season_id.as_bytes()])
+}
+
+fn season_vault_address(season_id: &str) -> Pubkey {
+    pda(&[season_address(season_id).as_ref(), ESCROW.as_ref()])
+}
+
+fn season_account(season: &Season, vault_amount: u64) -> Result<Vec<(Pubkey, Account)>> {
+    let address = season_address(&season.season_id);
+    let mut vault_data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: season.mint,
+        owner: address,
+        amount: vault_amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut vault_data);
+    let vault = Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: vault_data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    Ok(vec![
+        (address, program_account(season, Season::size())?),
+        (season_vault_address(&season.season_id), vault),
+    ])
+}
+
+/// A Bonk season the admin already closed, with `season_winner` alone on its leaderboard.
+pub fn closed_season_accounts() -> Result<Vec<(Pubkey, Account)>> {
+    let bump = Pubkey::find_program_address(
+        &[SEASON.as_ref(), CLOSED_SEASON_ID.as_bytes()],
+        &bonk_paper_scissors::ID,
+    )
+    .1;
+    let mut closed = Season::new(
+        bump,
+        CLOSED_SEASON_ID.to_string(),
+        BONK_MINT,
+        0,
+        0,
+        STAKE,
+        vec![7_000, 3_000],
+    );
+    closed.state = SeasonState::Closed;
+    closed.reward_pool = STAKE;
+    closed.leaderboard.push(LeaderboardEntry {
+        player: season_winner().pubkey(),
+        points: WIN_POINTS,
+        wins: 1,
+        paid: false,
+    });
+    season_account(&closed, STAKE)
+}
+
+/// Every scenario, the admin sets the program up first and the ones moving the clock forward go
+/// last.
+pub async fn run_all(bench: &mut Bench) -> Result<()> {
+    settings(bench).await?;
+    moves_and_claims(bench).await?;
+    cancel(bench).await?;
+    unwind(bench).await?;
+    relayed_reveal(bench).await?;
+    sessions(bench).await?;
+    queue(bench).await?;
+    side_bets(bench).await?;
+    rematch(bench).await?;
+    migration(bench).await?;
+    expiries(bench).await?;
+    native_sol(bench).await?;
+    nft(bench).await?;
+    tables(bench).await?;
+    tournaments(bench).await?;
+    seasons(bench).await?;
+    rulesets(bench).await?;
+    fees(bench).await?;
+    Ok(())
+}
+
+fn revenue_recipients() -> Vec<RevenueRecipient> {
+    vec![
+        RevenueRecipient {
+            address: Pubkey::new_from_array([11; 32]),
+            basis_points: 7_000,
+        },
+        RevenueRecipient {
+            address: Pubkey::new_from_array([12; 32]),
+            basis_points: 3_000,
+        },
+    ]
+}
+
+fn create_season(season_id: &str, ends_at: i64) -> Instruction {
+    ix(
+        accounts::CreateSeason {
+            season: season_address(season_id),
+            season_vault: season_vault_address(season_id),
+            mint: BONK_MINT,
+            signer: ADMIN,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateSeason {
+            season_id: season_id.to_string(),
+            starts_at: 0,
+            ends_at,
+            stake_per_point: STAKE,
+            reward_split: vec![7_000, 3_000],
+        },
+    )
+}
+
+/// Measures every admin instruction that sets the program up, storing the accounts the other
+/// scenarios need as each one would have created them. Updates write the value already set.
+async fn settings(bench: &mut Bench) -> Result<()> {
+    let instruction = ix(
+        accounts::MigrateSettingsV2ToV3 {
+            bps_settings_v2: bps_settings_v2_address(),
+            bps_settings_v3: bps_settings_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateSettingsV2ToV3 {},
+    );
+    bench
+        .measure_as_admin("migrate_settings_v2_to_v3", instruction)
+        .await?;
+    bench.set_account(
+        &bps_settings_address(),
+        program_account(
+            &BpsSettingsV3::new(
+                pda_bump(&[BPS_SETTINGS_V3.as_ref()]),
+                TREASURY,
+                PLAYER_FEE_LAMPORTS,
+                TIME_FOR_PENALIZATION,
+            ),
+            BpsSettingsV3::size(),
+        )?,
+    );
+    // Players open far fewer games than this in any scenario, and cancels and forfeits refund,
+    // exercising every fee payout.
+    let instruction = ix(
+        accounts::UpdateBpsSettingsV3 {
+            bps_settings_v3: bps_settings_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateBpsSettingsV3 {
+            time_for_penalization: TIME_FOR_PENALIZATION,
+            player_fee_lamports: PLAYER_FEE_LAMPORTS,
+            payout_basis_points: PAYOUT_BASIS_POINTS,
+            relayer_fee_lamports: RELAYER_FEE_LAMPORTS,
+            max_open_games: 100,
+            flags: REFUND_ON_CANCEL | REFUND_ON_FORFEIT,
+        },
+    );
+    bench
+        .measure_as_admin("update_bps_settings_v3", instruction)
+        .await?;
+    let mut bps_settings = BpsSettingsV3::new(
+        pda_bump(&[BPS_SETTINGS_V3.as_ref()]),
+        ADMIN,
+        PLAYER_FEE_LAMPORTS,
+        TIME_FOR_PENALIZATION,
+    );
+    bps_settings.fees.relayer_fee_lamports = RELAYER_FEE_LAMPORTS;
+    bps_settings.limits.max_open_games = 100;
+    bench.set_account(
+        &bps_settings_address(),
+        program_account(&bps_settings, BpsSettingsV3::size())?,
+    );
+
+    let instruction = ix(
+        accounts::InitStakeLimits {
+            stake_limits: stake_limits_address(&BONK_MINT),
+            mint: BONK_MINT,
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::InitStakeLimits {
+            min_stake: 1,
+            max_stake: STAKE * 1_000,
+            tiers: vec![],
+        },
+    );
+    bench
+        .measure_as_admin("init_stake_limits", instruction)
+        .await?;
+    bench.set_account(
+        &stake_limits_address(&BONK_MINT),
+        program_account(
+            &StakeLimits::new(
+                pda_bump(&[STAKE_LIMITS.as_ref(), BONK_MINT.as_ref()]),
+                BONK_MINT,
+                1,
+                STAKE * 1_000,
+                vec![],
+            ),
+            StakeLimits::size(),
+        )?,
+    );
+    // Native SOL games are limited by the native mint's limits.
+    bench.set_account(
+        &stake_limits_address(&native_mint::ID),
+        program_account(
+            &StakeLimits::new(
+                pda_bump(&[STAKE_LIMITS.as_ref(), native_mint::ID.as_ref()]),
+                native_mint::ID,
+                1,
+                u64::MAX,
+                vec![],
+            ),
+            StakeLimits::size(),
+        )?,
+    );
+    let instruction = ix(
+        accounts::UpdateStakeLimits {
+            stake_limits: stake_limits_address(&BONK_MINT),
+            signer: ADMIN,
+        },
+        instruction::UpdateStakeLimits {
+            min_stake: 1,
+            max_stake: STAKE * 1_000,
+            tiers: vec![],
+        },
+    );
+    bench
+        .measure_as_admin("update_stake_limits", instruction)
+        .await?;
+
+    let instruction = ix(
+        accounts::InitFeeVault {
+            fee_vault: fee_vault_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::InitFeeVault {
+            destination: TREASURY,
+        },
+    );
+    bench
+        .measure_as_admin("init_fee_vault", instruction)
+        .await?;
+    bench.set_account(
+        &fee_vault_address(),
+        program_account(
+            &FeeVault::new(pda_bump(&[FEE_VAULT.as_ref()]), TREASURY),
+            FeeVault::size(),
+        )?,
+    );
+    let instruction = ix(
+        accounts::UpdateFeeVault {
+            fee_vault: fee_vault_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateFeeVault {
+            destination: TREASURY,
+        },
+    );
+    bench
+        .measure_as_admin("update_fee_vault", instruction)
+        .await?;
+
+    let instruction = ix(
+        accounts::InitRevenueSplit {
+            revenue_split: revenue_split_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::InitRevenueSplit {
+            recipients: revenue_recipients(),
+        },
+    );
+    bench
+        .measure_as_admin("init_revenue_split", instruction)
+        .await?;
+    bench.set_account(
+        &revenue_split_address(),
+        program_account(
+            &RevenueSplit::new(pda_bump(&[REVENUE_SPLIT.as_ref()]), revenue_recipients()),
+            RevenueSplit::size(),
+        )?,
+    );
+    let instruction = ix(
+        accounts::UpdateRevenueSplit {
+            revenue_split: revenue_split_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateRevenueSplit {
+            recipients: revenue_recipients(),
+        },
+    );
+    bench
+        .measure_as_admin("update_revenue_split", instruction)
+        .await?;
+
+    let instruction = ix(
+        accounts::AllowCollection {
+            allowed_collection: allowed_collection_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::AllowCollection {
+            collection: NFT_COLLECTION,
+        },
+    );
+    bench
+        .measure_as_admin("allow_collection", instruction)
+        .await?;
+    bench.set_account(
+        &allowed_collection_address(),
+        program_account(
+            &AllowedCollection::new(
+                pda_bump(&[ALLOWED_COLLECTION.as_ref(), NFT_COLLECTION.as_ref()]),
+                NFT_COLLECTION,
+            ),
+            AllowedCollection::size(),
+        )?,
+    );
+    // Nothing is kept, so the collection stays allowed for the NFT games.
+    let instruction = ix(
+        accounts::DisallowCollection {
+            allowed_collection: allowed_collection_address(),
+            signer: ADMIN,
+        },
+        instruction::DisallowCollection {},
+    );
+    bench
+        .measure_as_admin("disallow_collection", instruction)
+        .await?;
+
+    let instruction = ix(
+        accounts::CreateRuleset {
+            ruleset: ruleset_address(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::CreateRuleset {
+            ruleset_id: RULESET_ID.to_string(),
+            choice_count: RULESET_BEATS.len() as u8,
+            beats: RULESET_BEATS.to_vec(),
+        },
+    );
+    bench
+        .measure_as_admin("create_ruleset", instruction)
+        .await?;
+    let mut beats = [0; MAX_CHOICES as usize];
+    beats[..RULESET_BEATS.len()].copy_from_slice(&RULESET_BEATS);
+    bench.set_account(
+        &ruleset_address(),
+        program_account(
+            &Ruleset::new(
+                pda_bump(&[RULESET.as_ref(), RULESET_ID.as_bytes()]),
+                RULESET_ID.to_string(),
+                RULESET_BEATS.len() as u8,
+                beats,
+            ),
+            Ruleset::size(),
+        )?,
+    );
+
+    // Runs for as long as the bench does.
+    bench
+        .measure_as_admin("create_season", create_season(SEASON_ID, i64::MAX))
+        .await?;
+    // Over once the clock moves forward, `seasons` closes it.
+    let ends_at = bench.now().await? + 1;
+    for (season_id, ends_at) in [(SEASON_ID, i64::MAX), (ENDING_SEASON_ID, ends_at)] {
+        let season = Season::new(
+            pda_bump(&[SEASON.as_ref(), season_id.as_bytes()]),
+            season_id.to_string(),
+            BONK_MINT,
+            0,
+            ends_at,
+            STAKE,
+            vec![7_000, 3_000],
+        );
+        for (address, account) in season_account(&season, 0)? {
+            bench.set_account(&address, account);
+        }
+    }
+    Ok(())
+}
+
+async fn moves_and_claims(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+
+    let (first_move, second_move) = (Move::new(Choice::Bonk), Move::new(Choice::Scissors));
+    let game = game_address(&one.key(), "bench-first-won");
+    let instruction = bench.first_player_move(&one, "bench-first-won", &first_move);
+    bench
+        .measure("first_player_move", instruction, &[&one.keypair])
+        .await?;
+    let instruction = bench.second_player_move(&game, &two, &second_move).await?;
+    bench
+        .measure("second_player_move", instruction, &[&two.keypair])
+        .await?;
+    let instruction = bench.reveal(&game, &one.key(), &first_move);
+    bench
+        .measure("reveal/first_player", instruction, &[&one.keypair])
+        .await?;
+    let instruction = bench.reveal(&game, &two.key(), &second_move);
+    bench
+        .measure("reveal/second_player", instruction, &[&two.keypair])
+        .await?;
+    let instruction = ix(
+        accounts::AuditGame {
+            game,
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            second_player_escrow: Some(escrow_address(&game, SECOND_PLAYER.as_ref())),
+        },
+        instruction::AuditGame {},
+    );
+    bench.measure("audit_game", instruction, &[]).await?;
+    let instruction = ix(
+        accounts::GetGameStatus {
+            game,
+            bps_settings_v3: bps_settings_address(),
+        },
+        instruction::GetGameStatus {},
+    );
+    bench.measure("get_game_status", instruction, &[]).await?;
+    let instruction = ix(
+        accounts::PreviewSettlement {
+            game,
+            bps_settings_v3: bps_settings_address(),
+            mint: Some(BONK_MINT),
+        },
+        instruction::PreviewSettlement {},
+    );
+    bench
+        .measure("preview_settlement", instruction, &[])
+        .await?;
+    let instruction = bench.claim(&game, &one, &two);
+    bench
+        .measure("claim/first_player_won", instruction, &[])
+        .await?;
+
+    for (name, game_id, first_choice, second_choice) in [
+        (
+            "claim/second_player_won",
+            "bench-second-won",
+            Choice::Paper,
+            Choice::Scissors,
+        ),
+        ("claim/draw", "bench-draw", Choice::Bonk, Choice::Bonk),
+    ] {
+        let (first_move, second_move) = (Move::new(first_choice), Move::new(second_choice));
+        let game = bench
+            .play_game(game_id, &one, &two, &first_move, &second_move)
+            .await?;
+        let instruction = bench.claim(&game, &one, &two);
+        bench.measure(name, instruction, &[]).await?;
+    }
+    Ok(())
+}
+
+async fn cancel(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let game = game_address(&one.key(), "bench-cancel");
+    let instruction = bench.first_player_move(&one, "bench-cancel", &Move::new(Choice::Bonk));
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let instruction = ix(
+        accounts::CancelGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            first_player_token_account: one.token_account,
+            mint: BONK_MINT,
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            first_player: one.key(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CancelGame {},
+    );
+    bench
+        .measure("cancel_game", instruction, &[&one.keypair])
+        .await
+}
+
+async fn unwind(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let game = bench
+        .start_game(
+            "bench-unwind",
+            &one,
+            &two,
+            &Move::new(Choice::Bonk),
+            &Move::new(Choice::Paper),
+        )
+        .await?;
+    let instruction = ix(
+        accounts::AdminUnwindStaleGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: Some(open_games_address(&two.key())),
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            first_player_token_account: one.token_account,
+            second_player_escrow: escrow_address(&game, SECOND_PLAYER.as_ref()),
+            second_player_token_account: two.token_account,
+            mint: BONK_MINT,
+            first_player: one.key(),
+            second_player: two.key(),
+            signer: ADMIN,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::AdminUnwindStaleGame {},
+    );
+    bench
+        .measure_as_admin("admin_unwind_stale_game", instruction)
+        .await
+}
+
+async fn relayed_reveal(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let first_move = Move::new(Choice::Paper);
+    // The first player deposits the relayer fee, so the reveal pays it out.
+    let game = game_address(&one.key(), "bench-relayed");
+    let instruction = bench.first_player_move_relayed(&one, "bench-relayed", &first_move);
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let instruction = bench
+        .second_player_move(&game, &two, &Move::new(Choice::Bonk))
+        .await?;
+    bench.execute(&[instruction], &[&two.keypair]).await?;
+    let instruction = ix(
+        accounts::RevealFor {
+            game,
+            relayer: bench.payer(),
+            system_program: system_program::ID,
+        },
+        instruction::RevealFor {
+            player: one.key(),
+            choice: first_move.choice as u8,
+            salt: first_move.salt,
+        },
+    );
+    bench.measure("reveal_for", instruction, &[]).await
+}
+
+async fn sessions(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let session_signer = Keypair::new();
+    let session_token = pda(&[
+        SESSION_TOKEN.as_ref(),
+        one.key().as_ref(),
+        session_signer.pubkey().as_ref(),
+    ]);
+    let valid_until = bench.now().await? + 60 * 60;
+    let instruction = ix(
+        accounts::CreateSession {
+            session_token,
+            session_signer: session_signer.pubkey(),
+            authority: one.key(),
+            system_program: system_program::ID,
+        },
+        instruction::CreateSession {
+            session_signer: session_signer.pubkey(),
+            valid_until,
+            max_stake: STAKE,
+            top_up_lamports: LAMPORTS_PER_SOL / 100,
+        },
+    );
+    bench
+        .measure("create_session", instruction, &[&one.keypair])
+        .await?;
+
+    let first_move = Move::new(Choice::Scissors);
+    let game = bench
+        .start_game(
+            "bench-session",
+            &one,
+            &two,
+            &first_move,
+            &Move::new(Choice::Paper),
+        )
+        .await?;
+    let instruction = ix(
+        accounts::Reveal {
+            game,
+            player: session_signer.pubkey(),
+            session_token: Some(session_token),
+            system_program: system_program::ID,
+        },
+        instruction::Reveal {
+            choice: first_move.choice as u8,
+            salt: first_move.salt,
+        },
+    );
+    bench
+        .measure("reveal/session_signer", instruction, &[&session_signer])
+        .await?;
+
+    let instruction = ix(
+        accounts::RevokeSession {
+            session_token,
+            authority: one.key(),
+            signer: one.key(),
+        },
+        instruction::RevokeSession {},
+    );
+    bench
+        .measure("revoke_session", instruction, &[&one.keypair])
+        .await
+}
+
+fn queue_ticket_address(player: &Pubkey) -> Pubkey {
+    pda(&[
+        QUEUE_TICKET.as_ref(),
+        BONK_MINT.as_ref(),
+        STAKE.to_le_bytes().as_ref(),
+        player.as_ref(),
+    ])
+}
+
+fn enqueue(player: &Player) -> accounts::JoinQueue {
+    let queue_ticket = queue_ticket_address(&player.key());
+    accounts::JoinQueue {
+        player: player.key(),
+        player_token_account: player.token_account,
+        mint: BONK_MINT,
+        stake_limits: stake_limits_address(&BONK_MINT),
+        bps_settings_v3: bps_settings_address(),
+        queue_ticket: Some(queue_ticket),
+        queue_ticket_escrow: Some(escrow_address(&queue_ticket, FIRST_PLAYER.as_ref())),
+        queued_ticket: None,
+        queued_ticket_escrow: None,
+        queued_player: None,
+        game: None,
+        first_player_escrow: None,
+        second_player_escrow: None,
+        first_player_open_games: None,
+        second_player_open_games: None,
+        associated_token_program: anchor_spl::associated_token::ID,
+        token_program: spl_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+fn join_queue_args(player_move: &Move) -> instruction::JoinQueue {
+    instruction::JoinQueue {
+        game_id: "bench-queue".to_string(),
+        amount: STAKE,
+        player_hash: player_move.hash(),
+    }
+}
+
+async fn queue(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let three = bench.player().await?;
+
+    let instruction = ix(enqueue(&one), join_queue_args(&Move::new(Choice::Bonk)));
+    bench
+        .measure("join_queue/enqueue", instruction, &[&one.keypair])
+        .await?;
+
+    let queued_ticket = queue_ticket_address(&one.key());
+    let game = game_address(&one.key(), "bench-queue");
+    let instruction = ix(
+        accounts::JoinQueue {
+            queue_ticket: None,
+            queue_ticket_escrow: None,
+            queued_ticket: Some(queued_ticket),
+            queued_ticket_escrow: Some(escrow_address(&queued_ticket, FIRST_PLAYER.as_ref())),
+            queued_player: Some(one.key()),
+            game: Some(game),
+            first_player_escrow: Some(escrow_address(&game, FIRST_PLAYER.as_ref())),
+            second_player_escrow: Some(escrow_address(&game, SECOND_PLAYER.as_ref())),
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: Some(open_games_address(&two.key())),
+            ..enqueue(&two)
+        },
+        join_queue_args(&Move::new(Choice::Paper)),
+    );
+    bench
+        .measure("join_queue/match", instruction, &[&two.keypair])
+        .await?;
+
+    let instruction = ix(enqueue(&three), join_queue_args(&Move::new(Choice::Bonk)));
+    bench.execute(&[instruction], &[&three.keypair]).await?;
+    let queue_ticket = queue_ticket_address(&three.key());
+    let instruction = ix(
+        accounts::LeaveQueue {
+            queue_ticket,
+            queue_ticket_escrow: escrow_address(&queue_ticket, FIRST_PLAYER.as_ref()),
+            player_token_account: three.token_account,
+            mint: BONK_MINT,
+            player: three.key(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::LeaveQueue {},
+    );
+    bench
+        .measure("leave_queue", instruction, &[&three.keypair])
+        .await
+}
+
+async fn side_bets(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let bettor = bench.player().await?;
+    let (first_move, second_move) = (Move::new(Choice::Bonk), Move::new(Choice::Scissors));
+
+    let game = game_address(&one.key(), "bench-side");
+    let instruction = bench.first_player_move(&one, "bench-side", &first_move);
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+
+    let side_pool = bench.side_pool_address(&game).await?;
+    let side_pool_vault = pda(&[side_pool.as_ref(), ESCROW.as_ref()]);
+    let instruction = ix(
+        accounts::InitSidePool {
+            game,
+            side_pool,
+            side_pool_vault,
+            mint: BONK_MINT,
+            bps_settings_v3: bps_settings_address(),
+            payer: bench.payer(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitSidePool {},
+    );
+    bench.measure("init_side_pool", instruction, &[]).await?;
+
+    let side_bet = side_bet_address(&side_pool, &bettor.key());
+    let instruction = ix(
+        accounts::PlaceSideBet {
+            game,
+            side_pool,
+            side_pool_vault,
+            side_bet,
+            bettor_token_account: bettor.token_account,
+            mint: BONK_MINT,
+            bettor: bettor.key(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::PlaceSideBet {
+            outcome: GameState::FirstPlayerWon,
+            amount: STAKE,
+        },
+    );
+    bench
+        .measure("place_side_bet", instruction, &[&bettor.keypair])
+        .await?;
+
+    let instructions = [
+        bench.second_player_move(&game, &two, &second_move).await?,
+        bench.reveal(&game, &one.key(), &first_move),
+        bench.reveal(&game, &two.key(), &second_move),
+        bench.claim(&game, &one, &two),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair])
+        .await?;
+
+    let instruction = ix(
+        accounts::ClaimSideBet {
+            game,
+            side_pool,
+            side_pool_vault,
+            side_bet,
+            bettor_token_account: bettor.token_account,
+            mint: BONK_MINT,
+            bettor: bettor.key(),
+            token_program: spl_token::ID,
+        },
+        instruction::ClaimSideBet {},
+    );
+    bench
+        .measure("claim_side_bet", instruction, &[&bettor.keypair])
+        .await
+}
+
+async fn rematch(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let previous_game = bench
+        .play_game(
+            "bench-original",
+            &one,
+            &two,
+            &Move::new(Choice::Paper),
+            &Move::new(Choice::Bonk),
+        )
+        .await?;
+    let instruction = bench.claim(&previous_game, &one, &two);
+    bench.execute(&[instruction], &[]).await?;
+
+    // The loser asks for the rematch, so it moves first.
+    let game = game_address(&two.key(), "bench-rematch");
+    let instruction = ix(
+        accounts::ProposeRematch {
+            previous_game,
+            game,
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            first_player_token_account: two.token_account,
+            mint: BONK_MINT,
+            stake_limits: stake_limits_address(&BONK_MINT),
+            bps_settings_v3: bps_settings_address(),
+            first_player_open_games: open_games_address(&two.key()),
+            first_player: two.key(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ProposeRematch {
+            game_id: "bench-rematch".to_string(),
+            first_player_hash: Move::new(Choice::Scissors).hash(),
+        },
+    );
+    bench
+        .measure("propose_rematch", instruction, &[&two.keypair])
+        .await?;
+
+    let side_pool = bench.side_pool_address(&game).await?;
+    let instruction = ix(
+        accounts::AcceptRematch {
+            game,
+            previous_game,
+            second_player_escrow: escrow_address(&game, SECOND_PLAYER.as_ref()),
+            second_player_token_account: one.token_account,
+            mint: BONK_MINT,
+            second_player: one.key(),
+            second_player_side_bet: side_bet_address(&side_pool, &one.key()),
+            bps_settings_v3: bps_settings_address(),
+            second_player_open_games: open_games_address(&one.key()),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::AcceptRematch {
+            second_player_hash: Move::new(Choice::Paper).hash(),
+        },
+    );
+    bench
+        .measure("accept_rematch", instruction, &[&one.keypair])
+        .await
+}
+
+async fn migration(bench: &mut Bench) -> Result<()> {
+    let (game, _) = legacy_game_account()?;
+    let instruction = ix(
+        accounts::MigrateGame {
+            game,
+            first_player: legacy_first_player(),
+            payer: bench.payer(),
+            system_program: system_program::ID,
+        },
+        instruction::MigrateGame {},
+    );
+    bench.measure("migrate_game", instruction, &[]).await
+}
+
+async fn expiries(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+
+    let open_game = game_address(&one.key(), "bench-open");
+    let instruction = bench.first_player_move(&one, "bench-open", &Move::new(Choice::Bonk));
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let unrevealed_game = bench
+        .start_game(
+            "bench-unrevealed",
+            &one,
+            &two,
+            &Move::new(Choice::Bonk),
+            &Move::new(Choice::Paper),
+        )
+        .await?;
+    let first_move = Move::new(Choice::Scissors);
+    let forfeited_game = bench
+        .start_game(
+            "bench-forfeit",
+            &one,
+            &two,
+            &first_move,
+            &Move::new(Choice::Paper),
+        )
+        .await?;
+    let instruction = bench.reveal(&forfeited_game, &one.key(), &first_move);
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+
+    bench.advance_clock(TIME_FOR_PENALIZATION + 1).await?;
+
+    let instruction = ix(
+        accounts::ExpireOpenGame {
+            game: open_game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            first_player_escrow: escrow_address(&open_game, FIRST_PLAYER.as_ref()),
+            first_player_token_account: one.token_account,
+            mint: BONK_MINT,
+            first_player: one.key(),
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            payer: bench.payer(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ExpireOpenGame {},
+    );
+    bench.measure("expire_open_game", instruction, &[]).await?;
+
+    let instruction = ix(
+        accounts::ExpireUnrevealedGame {
+            game: unrevealed_game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: Some(open_games_address(&two.key())),
+            first_player_escrow: escrow_address(&unrevealed_game, FIRST_PLAYER.as_ref()),
+            first_player_token_account: one.token_account,
+            second_player_escrow: escrow_address(&unrevealed_game, SECOND_PLAYER.as_ref()),
+            second_player_token_account: two.token_account,
+            mint: BONK_MINT,
+            first_player: one.key(),
+            second_player: two.key(),
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            payer: bench.payer(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ExpireUnrevealedGame {},
+    );
+    bench
+        .measure("expire_unrevealed_game", instruction, &[])
+        .await?;
+
+    let instruction = bench.claim(&forfeited_game, &one, &two);
+    bench.measure("claim/forfeit", instruction, &[]).await
+}
+
+async fn native_sol(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let first_player_move_sol = |game_id: &str, first_move: &Move| {
+        let game = game_address(&one.key(), game_id);
+        let instruction = ix(
+            accounts::FirstPlayerMoveSol {
+                game,
+                sol_vault: pda(&[SOL_VAULT.as_ref(), game.as_ref()]),
+                stake_limits: stake_limits_address(&native_mint::ID),
+                bps_settings_v3: bps_settings_address(),
+                ruleset: None,
+                first_player_open_games: open_games_address(&one.key()),
+                first_player: one.key(),
+                system_program: system_program::ID,
+            },
+            instruction::FirstPlayerMoveSol {
+                game_id: game_id.to_string(),
+                amount: LAMPORTS_PER_SOL / 10,
+                first_player_hash: first_move.hash(),
+            },
+        );
+        (game, instruction)
+    };
+
+    let second_player_move_sol = |game: Pubkey, side_pool: Pubkey, second_move: &Move| {
+        ix(
+            accounts::SecondPlayerMoveSol {
+                game,
+                sol_vault: pda(&[SOL_VAULT.as_ref(), game.as_ref()]),
+                second_player: two.key(),
+                second_player_side_bet: side_bet_address(&side_pool, &two.key()),
+                bps_settings_v3: bps_settings_address(),
+                second_player_open_games: open_games_address(&two.key()),
+                system_program: system_program::ID,
+            },
+            instruction::SecondPlayerMoveSol {
+                second_player_hash: second_move.hash(),
+            },
+        )
+    };
+
+    let (first_move, second_move) = (Move::new(Choice::Paper), Move::new(Choice::Bonk));
+    let (game, instruction) = first_player_move_sol("bench-sol", &first_move);
+    let sol_vault = pda(&[SOL_VAULT.as_ref(), game.as_ref()]);
+    bench
+        .measure("first_player_move_sol", instruction, &[&one.keypair])
+        .await?;
+    let side_pool = bench.side_pool_address(&game).await?;
+    let instruction = second_player_move_sol(game, side_pool, &second_move);
+    bench
+        .measure("second_player_move_sol", instruction, &[&two.keypair])
+        .await?;
+    let instructions = [
+        bench.reveal(&game, &one.key(), &first_move),
+        bench.reveal(&game, &two.key(), &second_move),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair])
+        .await?;
+    let instruction = ix(
+        accounts::ClaimSol {
+            game,
+            sol_vault,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: Some(open_games_address(&two.key())),
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            first_player: one.key(),
+            second_player: two.key(),
+            payer: bench.payer(),
+            system_program: system_program::ID,
+        },
+        instruction::ClaimSol {},
+    );
+    bench.measure("claim_sol", instruction, &[]).await?;
+
+    let (game, instruction) = first_player_move_sol("bench-sol-cancel", &first_move);
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let instruction = ix(
+        accounts::CancelSolGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            sol_vault: pda(&[SOL_VAULT.as_ref(), game.as_ref()]),
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            first_player: one.key(),
+            system_program: system_program::ID,
+        },
+        instruction::CancelSolGame {},
+    );
+    bench
+        .measure("cancel_sol_game", instruction, &[&one.keypair])
+        .await?;
+
+    let (game, instruction) = first_player_move_sol("bench-sol-unwind", &first_move);
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let side_pool = bench.side_pool_address(&game).await?;
+    let instruction = second_player_move_sol(game, side_pool, &second_move);
+    bench.execute(&[instruction], &[&two.keypair]).await?;
+    let instruction = ix(
+        accounts::AdminUnwindStaleSolGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: Some(open_games_address(&two.key())),
+            sol_vault: pda(&[SOL_VAULT.as_ref(), game.as_ref()]),
+            first_player: one.key(),
+            second_player: two.key(),
+            signer: ADMIN,
+            system_program: system_program::ID,
+        },
+        instruction::AdminUnwindStaleSolGame {},
+    );
+    bench
+        .measure_as_admin("admin_unwind_stale_sol_game", instruction)
+        .await?;
+
+    let (game, instruction) = first_player_move_sol("bench-sol-open", &first_move);
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    bench.advance_clock(TIME_FOR_PENALIZATION + 1).await?;
+    let instruction = ix(
+        accounts::ExpireSolGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: None,
+            sol_vault: pda(&[SOL_VAULT.as_ref(), game.as_ref()]),
+            first_player: one.key(),
+            second_player: None,
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            payer: bench.payer(),
+            system_program: system_program::ID,
+        },
+        instruction::ExpireSolGame {},
+    );
+    bench.measure("expire_sol_game", instruction, &[]).await
+}
+
+async fn nft(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let [one_mint, two_mint] = NFT_MINTS;
+    let one_nft = bench.mint_nft(&one, &one_mint).await?;
+    let two_nft = bench.mint_nft(&two, &two_mint).await?;
+    let (first_move, second_move) = (Move::new(Choice::Bonk), Move::new(Choice::Scissors));
+    let first_player_move_nft = |game_id: &str| {
+        let game = game_address(&one.key(), game_id);
+        let instruction = ix(
+            accounts::FirstPlayerMoveNft {
+                game,
+                first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+                first_player_token_account: one_nft,
+                mint: one_mint,
+                metadata: metadata_address(&one_mint),
+                allowed_collection: allowed_collection_address(),
+                bps_settings_v3: bps_settings_address(),
+                ruleset: None,
+                first_player_open_games: open_games_address(&one.key()),
+                first_player: one.key(),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::FirstPlayerMoveNft {
+                game_id: game_id.to_string(),
+                first_player_hash: first_move.hash(),
+            },
+        );
+        (game, instruction)
+    };
+
+    let (game, instruction) = first_player_move_nft("bench-nft-cancel");
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let instruction = ix(
+        accounts::CancelNftGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            first_player_token_account: one_nft,
+            mint: one_mint,
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            first_player: one.key(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CancelNftGame {},
+    );
+    bench
+        .measure("cancel_nft_game", instruction, &[&one.keypair])
+        .await?;
+
+    let second_player_move_nft = |game: Pubkey, side_pool: Pubkey| {
+        ix(
+            accounts::SecondPlayerMoveNft {
+                game,
+                second_player_escrow: escrow_address(&game, SECOND_PLAYER.as_ref()),
+                second_player_token_account: two_nft,
+                mint: two_mint,
+                metadata: metadata_address(&two_mint),
+                second_player: two.key(),
+                second_player_side_bet: side_bet_address(&side_pool, &two.key()),
+                bps_settings_v3: bps_settings_address(),
+                second_player_open_games: open_games_address(&two.key()),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::SecondPlayerMoveNft {
+                second_player_hash: second_move.hash(),
+            },
+        )
+    };
+
+    // Both NFTs go back to their owners, ready for the next game.
+    let (game, instruction) = first_player_move_nft("bench-nft-unwind");
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let side_pool = bench.side_pool_address(&game).await?;
+    bench
+        .execute(&[second_player_move_nft(game, side_pool)], &[&two.keypair])
+        .await?;
+    let instruction = ix(
+        accounts::AdminUnwindStaleNftGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: Some(open_games_address(&two.key())),
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            first_player_mint: one_mint,
+            first_player_token_account: one_nft,
+            second_player_escrow: escrow_address(&game, SECOND_PLAYER.as_ref()),
+            second_player_mint: two_mint,
+            second_player_token_account: two_nft,
+            first_player: one.key(),
+            second_player: two.key(),
+            signer: ADMIN,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::AdminUnwindStaleNftGame {},
+    );
+    bench
+        .measure_as_admin("admin_unwind_stale_nft_game", instruction)
+        .await?;
+
+    let (game, instruction) = first_player_move_nft("bench-nft");
+    bench
+        .measure("first_player_move_nft", instruction, &[&one.keypair])
+        .await?;
+    let side_pool = bench.side_pool_address(&game).await?;
+    let instruction = second_player_move_nft(game, side_pool);
+    bench
+        .measure("second_player_move_nft", instruction, &[&two.keypair])
+        .await?;
+    let instructions = [
+        bench.reveal(&game, &one.key(), &first_move),
+        bench.reveal(&game, &two.key(), &second_move),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair])
+        .await?;
+    // The winner has no account for the opponent's NFT yet, so the claim creates it.
+    let instruction = ix(
+        accounts::ClaimNft {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: Some(open_games_address(&two.key())),
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            first_player_mint: one_mint,
+            first_player_token_account: one_nft,
+            first_player_prize_token_account: get_associated_token_address(&one.key(), &two_mint),
+            second_player_escrow: escrow_address(&game, SECOND_PLAYER.as_ref()),
+            second_player_mint: two_mint,
+            second_player_token_account: two_nft,
+            second_player_prize_token_account: get_associated_token_address(&two.key(), &one_mint),
+            first_player: one.key(),
+            second_player: two.key(),
+            payer: bench.payer(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimNft {},
+    );
+    bench.measure("claim_nft", instruction, &[]).await?;
+
+    let (game, instruction) = first_player_move_nft("bench-nft-open");
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    bench.advance_clock(TIME_FOR_PENALIZATION + 1).await?;
+    let instruction = ix(
+        accounts::ExpireNftGame {
+            game,
+            first_player_open_games: Some(open_games_address(&one.key())),
+            second_player_open_games: None,
+            first_player_escrow: escrow_address(&game, FIRST_PLAYER.as_ref()),
+            first_player_mint: one_mint,
+            first_player_token_account: one_nft,
+            first_player: one.key(),
+            second_player_escrow: None,
+            second_player_mint: None,
+            second_player_token_account: None,
+            second_player: None,
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            payer: bench.payer(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ExpireNftGame {},
+    );
+    bench.measure("expire_nft_game", instruction, &[]).await
+}
+
+fn table_address(creator: &Pubkey, table_id: &str) -> Pubkey {
+    pda(&[TABLE.as_ref(), creator.as_ref(), table_id.as_bytes()])
+}
+
+fn create_table(player: &Player, table_id: &str, max_rounds: u8) -> Instruction {
+    let table = table_address(&player.key(), table_id);
+    ix(
+        accounts::CreateTable {
+            table,
+            creator_escrow: escrow_address(&table, player.key().as_ref()),
+            creator_token_account: player.token_account,
+            mint: BONK_MINT,
+            stake_limits: stake_limits_address(&BONK_MINT),
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            creator: player.key(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateTable {
+            table_id: table_id.to_string(),
+            max_seats: 3,
+            max_rounds,
+            stake: STAKE,
+        },
+    )
+}
+
+fn join_table(table: &Pubkey, player: &Player) -> Instruction {
+    ix(
+        accounts::JoinTable {
+            table: *table,
+            player_escrow: escrow_address(table, player.key().as_ref()),
+            player_token_account: player.token_account,
+            mint: BONK_MINT,
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            player: player.key(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::JoinTable {},
+    )
+}
+
+fn commit_table_move(table: &Pubkey, player: &Player, player_move: &Move) -> Instruction {
+    ix(
+        accounts::CommitTableMove {
+            table: *table,
+            bps_settings_v3: bps_settings_address(),
+            player: player.key(),
+        },
+        instruction::CommitTableMove {
+            player_hash: player_move.hash(),
+        },
+    )
+}
+
+fn reveal_table_move(table: &Pubkey, player: &Player, player_move: &Move) -> Instruction {
+    ix(
+        accounts::RevealTableMove {
+            table: *table,
+            bps_settings_v3: bps_settings_address(),
+            player: player.key(),
+        },
+        instruction::RevealTableMove {
+            choice: player_move.choice,
+            salt: player_move.salt,
+        },
+    )
+}
+
+async fn tables(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let three = bench.player().await?;
+
+    let table = table_address(&one.key(), "bench-table-leave");
+    let instructions = [
+        create_table(&one, "bench-table-leave", 1),
+        join_table(&table, &two),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair])
+        .await?;
+    let instruction = ix(
+        accounts::LeaveTable {
+            table,
+            player_escrow: escrow_address(&table, two.key().as_ref()),
+            player_token_account: two.token_account,
+            mint: BONK_MINT,
+            creator: one.key(),
+            player: two.key(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::LeaveTable {},
+    );
+    bench
+        .measure("leave_table", instruction, &[&two.keypair])
+        .await?;
+
+    // Two bonks eliminate the scissors, leaving two survivors after the only round.
+    let table = table_address(&one.key(), "bench-table");
+    let moves = [
+        Move::new(Choice::Bonk),
+        Move::new(Choice::Bonk),
+        Move::new(Choice::Scissors),
+    ];
+    let instruction = create_table(&one, "bench-table", 1);
+    bench
+        .measure("create_table", instruction, &[&one.keypair])
+        .await?;
+    bench
+        .execute(&[join_table(&table, &two)], &[&two.keypair])
+        .await?;
+    bench
+        .measure("join_table", join_table(&table, &three), &[&three.keypair])
+        .await?;
+    let instructions = [
+        commit_table_move(&table, &one, &moves[0]),
+        commit_table_move(&table, &two, &moves[1]),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair])
+        .await?;
+    let instruction = commit_table_move(&table, &three, &moves[2]);
+    bench
+        .measure("commit_table_move", instruction, &[&three.keypair])
+        .await?;
+    let instructions = [
+        reveal_table_move(&table, &one, &moves[0]),
+        reveal_table_move(&table, &two, &moves[1]),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair])
+        .await?;
+    let instruction = reveal_table_move(&table, &three, &moves[2]);
+    bench
+        .measure("reveal_table_move/resolve", instruction, &[&three.keypair])
+        .await?;
+    let mut instruction = ix(
+        accounts::ClaimTable {
+            table,
+            mint: BONK_MINT,
+            payer: bench.payer(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimTable {},
+    );
+    for player in [&one, &two, &three] {
+        let escrow = escrow_address(&table, player.key().as_ref());
+        instruction.accounts.push(AccountMeta::new(escrow, false));
+    }
+    for player in [&one, &two] {
+        let token_account = player.token_account;
+        instruction
+            .accounts
+            .push(AccountMeta::new(token_account, false));
+    }
+    bench.measure("claim_table", instruction, &[]).await?;
+
+    // The third seat never commits and forfeits.
+    let table = table_address(&one.key(), "bench-table-forfeit");
+    let instructions = [
+        create_table(&one, "bench-table-forfeit", 3),
+        join_table(&table, &two),
+        join_table(&table, &three),
+        commit_table_move(&table, &one, &moves[0]),
+        commit_table_move(&table, &two, &moves[1]),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair, &three.keypair])
+        .await?;
+    bench.advance_clock(TIME_FOR_PENALIZATION + 1).await?;
+    let instruction = ix(
+        accounts::AdvanceTable {
+            table,
+            bps_settings_v3: bps_settings_address(),
+        },
+        instruction::AdvanceTable {},
+    );
+    bench.measure("advance_table", instruction, &[]).await
+}
+
+fn tournament_address(organizer: &Pubkey, tournament_id: &str) -> Pubkey {
+    pda(&[
+        TOURNAMENT.as_ref(),
+        organizer.as_ref(),
+        tournament_id.as_bytes(),
+    ])
+}
+
+fn register_for_tournament(tournament: &Pubkey, player: &Player) -> Instruction {
+    ix(
+        accounts::RegisterForTournament {
+            tournament: *tournament,
+            tournament_escrow: pda(&[tournament.as_ref(), ESCROW.as_ref()]),
+            player_token_account: player.token_account,
+            mint: BONK_MINT,
+            bps_settings_v3: bps_settings_address(),
+            fee_vault: fee_vault_address(),
+            player: player.key(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterForTournament {},
+    )
+}
+
+fn start_tournament(tournament: &Pubkey) -> Instruction {
+    ix(
+        accounts::StartTournament {
+            tournament: *tournament,
+            bps_settings_v3: bps_settings_address(),
+        },
+        instruction::StartTournament {},
+    )
+}
+
+async fn tournaments(bench: &mut Bench) -> Result<()> {
+    let organizer = bench.player().await?;
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+    let registration_ends_at = bench.now().await? + 60;
+    let create_tournament = |tournament_id: &str| {
+        let tournament = tournament_address(&organizer.key(), tournament_id);
+        let instruction = ix(
+            accounts::CreateTournament {
+                tournament,
+                tournament_escrow: pda(&[tournament.as_ref(), ESCROW.as_ref()]),
+                mint: BONK_MINT,
+                organizer: organizer.key(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateTournament {
+                tournament_id: tournament_id.to_string(),
+                entry_fee: STAKE,
+                max_entrants: 2,
+                registration_ends_at,
+                prize_split: vec![10_000],
+            },
+        );
+        (tournament, instruction)
+    };
+
+    let (tournament, instruction) = create_tournament("bench-tournament");
+    bench
+        .measure("create_tournament", instruction, &[&organizer.keypair])
+        .await?;
+    bench
+        .execute(
+            &[register_for_tournament(&tournament, &one)],
+            &[&one.keypair],
+        )
+        .await?;
+    let instruction = register_for_tournament(&tournament, &two);
+    bench
+        .measure("register_for_tournament", instruction, &[&two.keypair])
+        .await?;
+    bench
+        .measure("start_tournament", start_tournament(&tournament), &[])
+        .await?;
+
+    let (first_move, second_move) = (Move::new(Choice::Bonk), Move::new(Choice::Scissors));
+    let game = game_address(&one.key(), "bench-tournament-final");
+    let instruction = ix(
+        accounts::FirstPlayerMoveTournament {
+            game,
+            tournament,
+            first_player: one.key(),
+            system_program: system_program::ID,
+        },
+        instruction::FirstPlayerMoveTournament {
+            game_id: "bench-tournament-final".to_string(),
+            match_index: 0,
+            first_player_hash: first_move.hash(),
+        },
+    );
+    bench
+        .measure("first_player_move_tournament", instruction, &[&one.keypair])
+        .await?;
+    let side_pool = bench.side_pool_address(&game).await?;
+    let instruction = ix(
+        accounts::SecondPlayerMoveTournament {
+            game,
+            tournament,
+            second_player: two.key(),
+            second_player_side_bet: side_bet_address(&side_pool, &two.key()),
+        },
+        instruction::SecondPlayerMoveTournament {
+            second_player_hash: second_move.hash(),
+        },
+    );
+    bench
+        .measure(
+            "second_player_move_tournament",
+            instruction,
+            &[&two.keypair],
+        )
+        .await?;
+    let instructions = [
+        bench.reveal(&game, &one.key(), &first_move),
+        bench.reveal(&game, &two.key(), &second_move),
+    ];
+    bench
+        .execute(&instructions, &[&one.keypair, &two.keypair])
+        .await?;
+    let instruction = ix(
+        accounts::ClaimTournamentMatch {
+            game,
+            tournament,
+            bps_settings_v3: bps_settings_address(),
+        },
+        instruction::ClaimTournamentMatch {},
+    );
+    bench
+        .measure("claim_tournament_match", instruction, &[])
+        .await?;
+    let instruction = ix(
+        accounts::ClaimTournamentPrize {
+            tournament,
+            tournament_escrow: pda(&[tournament.as_ref(), ESCROW.as_ref()]),
+            entrant_token_account: one.token_account,
+            entrant: one.key(),
+            mint: BONK_MINT,
+            organizer: organizer.key(),
+            token_program: spl_token::ID,
+        },
+        instruction::ClaimTournamentPrize {},
+    );
+    bench
+        .measure("claim_tournament_prize", instruction, &[])
+        .await?;
+
+    // Nobody creates the game of the only match, so the away player advances.
+    let (tournament, instruction) = create_tournament("bench-tournament-forfeit");
+    let instructions = [
+        instruction,
+        register_for_tournament(&tournament, &one),
+        register_for_tournament(&tournament, &two),
+        start_tournament(&tournament),
+    ];
+    bench
+        .execute(
+            &instructions,
+            &[&organizer.keypair, &one.keypair, &two.keypair],
+        )
+        .await?;
+    bench.advance_clock(TIME_FOR_PENALIZATION + 1).await?;
+    let instruction = ix(
+        accounts::ForfeitTournamentMatch {
+            tournament,
+            game: None,
+            bps_settings_v3: bps_settings_address(),
+        },
+        instruction::ForfeitTournamentMatch { match_index: 0 },
+    );
+    bench
+        .measure("forfeit_tournament_match", instruction, &[])
+        .await
+}
+
+async fn seasons(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+
+    let instruction = ix(
+        accounts::FundSeason {
+            season: season_address(SEASON_ID),
+            season_vault: season_vault_address(SEASON_ID),
+            funder_token_account: one.token_account,
+            mint: BONK_MINT,
+            funder: one.key(),
+            token_program: spl_token::ID,
+        },
+        instruction::FundSeason { amount: STAKE },
+    );
+    bench
+        .measure("fund_season", instruction, &[&one.keypair])
+        .await?;
+
+    let game = bench
+        .play_game(
+            "bench-season-game",
+            &one,
+            &two,
+            &Move::new(Choice::Bonk),
+            &Move::new(Choice::Scissors),
+        )
+        .await?;
+    let instruction = bench.claim_in_season(&game, &one, &two, Some(season_address(SEASON_ID)));
+    bench.measure("claim/season", instruction, &[]).await?;
+
+    // Nobody played in it, so its empty vault goes back.
+    let instruction = ix(
+        accounts::CloseSeason {
+            season: season_address(ENDING_SEASON_ID),
+            season_vault: season_vault_address(ENDING_SEASON_ID),
+            refund_token_account: Some(one.token_account),
+            mint: BONK_MINT,
+            signer: ADMIN,
+            token_program: spl_token::ID,
+        },
+        instruction::CloseSeason {},
+    );
+    bench.measure_as_admin("close_season", instruction).await?;
+
+    let winner = bench.player_with(season_winner()).await?;
+    let instruction = ix(
+        accounts::ClaimSeasonReward {
+            season: season_address(CLOSED_SEASON_ID),
+            season_vault: season_vault_address(CLOSED_SEASON_ID),
+            player_token_account: winner.token_account,
+            player: winner.key(),
+            mint: BONK_MINT,
+            bps_treasury: TREASURY,
+            token_program: spl_token::ID,
+        },
+        instruction::ClaimSeasonReward {},
+    );
+    bench
+        .measure("claim_season_reward", instruction, &[&winner.keypair])
+        .await
+}
+
+async fn rulesets(bench: &mut Bench) -> Result<()> {
+    let one = bench.player().await?;
+    let two = bench.player().await?;
+
+    let (first_move, second_move) = (Move::new(Choice::Bonk), Move::new(Choice::Scissors));
+    let game = game_address(&one.key(), "bench-ruleset");
+    let instruction = bench.first_player_move_with_ruleset(
+        &one,
+        "bench-ruleset",
+        &first_move,
+        Some(ruleset_address()),
+    );
+    bench
+        .measure("first_player_move/ruleset", instruction, &[&one.keypair])
+        .await?;
+    let instruction = bench.second_player_move(&game, &two, &second_move).await?;
+    bench.execute(&[instruction], &[&two.keypair]).await?;
+    let instruction = bench.reveal(&game, &one.key(), &first_move);
+    bench.execute(&[instruction], &[&one.keypair]).await?;
+    let instruction = bench.reveal(&game, &two.key(), &second_move);
+    bench.execute(&[instruction], &[&two.keypair]).await?;
+    let instruction = bench.claim(&game, &one, &two);
+    bench.measure("claim/ruleset", instruction, &[]).await
+}
+
+/// Sweeps the fees every other scenario collected, shared by the revenue split.
+async fn fees(bench: &mut Bench) -> Result<()> {
+    let mut instruction = ix(
+        accounts::SweepFees {
+            fee_vault: fee_vault_address(),
+            destination: TREASURY,
+            revenue_split: revenue_split_address(),
+            signer: ADMIN,
+        },
+        instruction::SweepFees {},
+    );
+    instruction.accounts.extend(
+        revenue_recipients()
+            .iter()
+            .map(|recipient| AccountMeta::new(recipient.address, false)),
+    );
+    bench.measure_as_admin("sweep_fees", instruction).await
+}
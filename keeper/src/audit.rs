@@ -0,0 +1,89 @@
+use std::{collections::BTreeMap, rc::Rc};
+
+use anchor_client::{
+    anchor_lang::AnchorDeserialize,
+    solana_sdk::{pubkey::Pubkey, signature::Keypair},
+    Program,
+};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bonk_paper_scissors::{
+    accounts, instruction,
+    state::{GameAudit, GameV3},
+};
+
+/// `audit_game` only reads, so it is simulated and its return data decoded.
+pub fn audit(
+    program: &Program<Rc<Keypair>>,
+    game_address: Pubkey,
+    game: &GameV3,
+) -> Result<GameAudit> {
+    let transaction = program
+        .request()
+        .accounts(accounts::AuditGame {
+            game: game_address,
+            first_player_escrow: game.first_player_escrow_address,
+            second_player_escrow: game.second_player_escrow_address(),
+        })
+        .args(instruction::AuditGame {})
+        .signed_transaction()?;
+    let result = program.rpc().simulate_transaction(&transaction)?.value;
+    if let Some(err) = result.err {
+        return Err(anyhow!("{} {:?}", err, result.logs.unwrap_or_default()));
+    }
+    let (data, _) = result
+        .return_data
+        .ok_or_else(|| anyhow!("No return data"))?
+        .data;
+    Ok(GameAudit::deserialize(&mut &STANDARD.decode(data)?[..])?)
+}
+
+/// Audits every game, printing the escrows that don't hold what their game state implies and
+/// the surplus left behind for each mint, native SOL being the default pubkey. Returns how many
+/// games are off or couldn't be audited.
+pub fn reconcile(program: &Program<Rc<Keypair>>) -> Result<usize> {
+    let mut unbalanced = 0;
+    let mut surplus_by_mint = BTreeMap::<Pubkey, u64>::new();
+    for (game_address, game) in program.accounts::<GameV3>(vec![])? {
+        let audit = match audit(program, game_address, &game) {
+            Ok(audit) => audit,
+            Err(err) => {
+                eprintln!("AuditGame {} failed: {}", game_address, err);
+                unbalanced += 1;
+                continue;
+            }
+        };
+        if audit.is_balanced() {
+            continue;
+        }
+        unbalanced += 1;
+        for escrow in audit.escrows.iter() {
+            if escrow.shortfall() > 0 {
+                println!(
+                    "{} ({:?}) escrow {} is short {}: expected {}, holds {}",
+                    game_address,
+                    audit.game_state,
+                    escrow.address,
+                    escrow.shortfall(),
+                    escrow.expected,
+                    escrow.actual
+                );
+            } else if escrow.surplus() > 0 {
+                println!(
+                    "{} ({:?}) escrow {} has {} extra: expected {}, holds {}",
+                    game_address,
+                    audit.game_state,
+                    escrow.address,
+                    escrow.surplus(),
+                    escrow.expected,
+                    escrow.actual
+                );
+                *surplus_by_mint.entry(game.mint).or_default() += escrow.surplus();
+            }
+        }
+    }
+    for (mint, surplus) in surplus_by_mint {
+        println!("Surplus of {}: {}", mint, surplus);
+    }
+    Ok(unbalanced)
+}
@@ -0,0 +1,352 @@
+use std::rc::Rc;
+
+use anchor_client::{
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signature},
+        system_program,
+    },
+    Program,
+};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anyhow::{anyhow, Result};
+use bonk_paper_scissors::{
+    accounts,
+    constants::{ESCROW, FIRST_PLAYER, OPEN_GAMES, SECOND_PLAYER, SOL_VAULT},
+    instruction,
+    state::{
+        Game, GameState, GameV3, Season, SeasonState, Tournament, FIRST_PLAYER_SLOT,
+        SECOND_PLAYER_SLOT,
+    },
+};
+
+/// Season running for `mint` at `now`, if any.
+pub fn active_season(seasons: &[(Pubkey, Season)], mint: &Pubkey, now: i64) -> Option<Pubkey> {
+    seasons
+        .iter()
+        .find(|(_, season)| {
+            season.state == SeasonState::Active
+                && season.mint == *mint
+                && season.starts_at <= now
+                && now <= season.ends_at
+        })
+        .map(|(address, _)| *address)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Action {
+    Claim,
+    ExpireOpenGame,
+    ExpireUnrevealedGame,
+}
+
+/// Uses the same checks as the program, so the keeper only submits what would succeed.
+pub fn next_action(game: &GameV3, now: i64, time_for_penalization: i64) -> Option<Action> {
+    if game.is_claimable(now, time_for_penalization) {
+        return Some(Action::Claim);
+    }
+    if game.is_expired(now, time_for_penalization) {
+        return match game.game_state() {
+            GameState::CreatedAndWaitingForStart => Some(Action::ExpireOpenGame),
+            _ => Some(Action::ExpireUnrevealedGame),
+        };
+    }
+    None
+}
+
+fn escrow_address(game_address: &Pubkey, player_seed: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[game_address.as_ref(), ESCROW.as_ref(), player_seed],
+        &bonk_paper_scissors::ID,
+    )
+    .0
+}
+
+/// `OpenGames` of the players whose slot the game holds, released when it is over.
+fn open_games_addresses(game: &GameV3) -> (Option<Pubkey>, Option<Pubkey>) {
+    let address = |slot: u8, player: &Pubkey| {
+        game.holds_open_game_slot(slot).then(|| {
+            Pubkey::find_program_address(
+                &[OPEN_GAMES.as_ref(), player.as_ref()],
+                &bonk_paper_scissors::ID,
+            )
+            .0
+        })
+    };
+    (
+        address(FIRST_PLAYER_SLOT, &game.first_player),
+        address(SECOND_PLAYER_SLOT, &game.second_player),
+    )
+}
+
+/// Rewrites a Borsh game as a `GameV3`, which the other actions expect. The rent the smaller
+/// account frees goes back to the game's first player.
+pub fn migrate(
+    program: &Program<Rc<Keypair>>,
+    game_address: Pubkey,
+    game: &Game,
+) -> Result<Signature> {
+    Ok(program
+        .request()
+        .accounts(accounts::MigrateGame {
+            game: game_address,
+            first_player: game.first_player,
+            payer: program.payer(),
+            system_program: system_program::ID,
+        })
+        .args(instruction::MigrateGame {})
+        .send()?)
+}
+
+/// Players are paid to their associated token accounts, for whichever token program owns the mint.
+/// Claims credit the winner in `season` when one is running for the mint.
+pub fn submit(
+    program: &Program<Rc<Keypair>>,
+    bps_settings_v3: Pubkey,
+    fee_vault: Pubkey,
+    season: Option<Pubkey>,
+    game_address: Pubkey,
+    game: &GameV3,
+    action: Action,
+) -> Result<Signature> {
+    let token_program = program.rpc().get_account(&game.mint)?.owner;
+    let first_player_escrow = escrow_address(&game_address, FIRST_PLAYER.as_ref());
+    let first_player_token_account = get_associated_token_address_with_program_id(
+        &game.first_player,
+        &game.mint,
+        &token_program,
+    );
+    let (first_player_open_games, second_player_open_games) = open_games_addresses(game);
+    let request = program.request();
+    let request = match action {
+        Action::ExpireOpenGame => request
+            .accounts(accounts::ExpireOpenGame {
+                game: game_address,
+                first_player_open_games,
+                first_player_escrow,
+                first_player_token_account,
+                mint: game.mint,
+                first_player: game.first_player,
+                bps_settings_v3,
+                fee_vault,
+                payer: program.payer(),
+                token_program,
+                system_program: system_program::ID,
+            })
+            .args(instruction::ExpireOpenGame {}),
+        Action::Claim | Action::ExpireUnrevealedGame => {
+            let second_player = game
+                .second_player()
+                .ok_or_else(|| anyhow!("Game has no second player"))?;
+            let second_player_escrow = escrow_address(&game_address, SECOND_PLAYER.as_ref());
+            let second_player_token_account = get_associated_token_address_with_program_id(
+                &second_player,
+                &game.mint,
+                &token_program,
+            );
+            match action {
+                Action::Claim => request
+                    .accounts(accounts::Claim {
+                        game: game_address,
+                        first_player_open_games,
+                        second_player_open_games,
+                        bps_settings_v3,
+                        fee_vault,
+                        season,
+                        first_player_escrow,
+                        first_player_token_account,
+                        second_player_escrow,
+                        second_player_token_account,
+                        mint: game.mint,
+                        first_player: game.first_player,
+                        second_player,
+                        payer: program.payer(),
+                        associated_token_program: anchor_spl::associated_token::ID,
+                        token_program,
+                        system_program: system_program::ID,
+                    })
+                    .args(instruction::Claim {}),
+                _ => request
+                    .accounts(accounts::ExpireUnrevealedGame {
+                        game: game_address,
+                        first_player_open_games,
+                        second_player_open_games,
+                        first_player_escrow,
+                        first_player_token_account,
+                        second_player_escrow,
+                        second_player_token_account,
+                        mint: game.mint,
+                        first_player: game.first_player,
+                        second_player,
+                        bps_settings_v3,
+                        fee_vault,
+                        payer: program.payer(),
+                        token_program,
+                        system_program: system_program::ID,
+                    })
+                    .args(instruction::ExpireUnrevealedGame {}),
+            }
+        }
+    };
+    Ok(request.send()?)
+}
+
+/// Native SOL games pay out of the game vault, both expiries go through `expire_sol_game`.
+pub fn submit_sol(
+    program: &Program<Rc<Keypair>>,
+    bps_settings_v3: Pubkey,
+    fee_vault: Pubkey,
+    game_address: Pubkey,
+    game: &GameV3,
+    action: Action,
+) -> Result<Signature> {
+    let (sol_vault, _) = Pubkey::find_program_address(
+        &[SOL_VAULT.as_ref(), game_address.as_ref()],
+        &bonk_paper_scissors::ID,
+    );
+    let (first_player_open_games, second_player_open_games) = open_games_addresses(game);
+    let request = program.request();
+    let request = match action {
+        Action::Claim => request
+            .accounts(accounts::ClaimSol {
+                game: game_address,
+                first_player_open_games,
+                second_player_open_games,
+                sol_vault,
+                bps_settings_v3,
+                fee_vault,
+                first_player: game.first_player,
+                second_player: game
+                    .second_player()
+                    .ok_or_else(|| anyhow!("Game has no second player"))?,
+                payer: program.payer(),
+                system_program: system_program::ID,
+            })
+            .args(instruction::ClaimSol {}),
+        Action::ExpireOpenGame | Action::ExpireUnrevealedGame => request
+            .accounts(accounts::ExpireSolGame {
+                game: game_address,
+                first_player_open_games,
+                second_player_open_games,
+                sol_vault,
+                first_player: game.first_player,
+                second_player: game.second_player(),
+                bps_settings_v3,
+                fee_vault,
+                payer: program.payer(),
+                system_program: system_program::ID,
+            })
+            .args(instruction::ExpireSolGame {}),
+    };
+    Ok(request.send()?)
+}
+
+/// NFT games pay to associated token accounts, which the instructions create when missing.
+pub fn submit_nft(
+    program: &Program<Rc<Keypair>>,
+    bps_settings_v3: Pubkey,
+    fee_vault: Pubkey,
+    game_address: Pubkey,
+    game: &GameV3,
+    action: Action,
+) -> Result<Signature> {
+    let token_program = program.rpc().get_account(&game.mint)?.owner;
+    let ata = |owner: &Pubkey, mint: &Pubkey| {
+        get_associated_token_address_with_program_id(owner, mint, &token_program)
+    };
+    let second_player = game.second_player();
+    let second_player_mint = game.second_player_mint();
+    let (first_player_open_games, second_player_open_games) = open_games_addresses(game);
+    let request = program.request();
+    let request = match action {
+        Action::Claim => {
+            let second_player =
+                second_player.ok_or_else(|| anyhow!("Game has no second player"))?;
+            let second_player_mint =
+                second_player_mint.ok_or_else(|| anyhow!("Game has no second NFT"))?;
+            request
+                .accounts(accounts::ClaimNft {
+                    game: game_address,
+                    first_player_open_games,
+                    second_player_open_games,
+                    bps_settings_v3,
+                    fee_vault,
+                    first_player_escrow: game.first_player_escrow_address,
+                    first_player_mint: game.mint,
+                    first_player_token_account: ata(&game.first_player, &game.mint),
+                    first_player_prize_token_account: ata(&game.first_player, &second_player_mint),
+                    second_player_escrow: game.second_player_escrow_address,
+                    second_player_mint,
+                    second_player_token_account: ata(&second_player, &second_player_mint),
+                    second_player_prize_token_account: ata(&second_player, &game.mint),
+                    first_player: game.first_player,
+                    second_player,
+                    payer: program.payer(),
+                    associated_token_program: anchor_spl::associated_token::ID,
+                    token_program,
+                    system_program: system_program::ID,
+                })
+                .args(instruction::ClaimNft {})
+        }
+        Action::ExpireOpenGame | Action::ExpireUnrevealedGame => request
+            .accounts(accounts::ExpireNftGame {
+                game: game_address,
+                first_player_open_games,
+                second_player_open_games,
+                first_player_escrow: game.first_player_escrow_address,
+                first_player_mint: game.mint,
+                first_player_token_account: ata(&game.first_player, &game.mint),
+                first_player: game.first_player,
+                second_player_escrow: game.second_player_escrow_address(),
+                second_player_mint,
+                second_player_token_account: second_player
+                    .zip(second_player_mint)
+                    .map(|(second_player, mint)| ata(&second_player, &mint)),
+                second_player,
+                bps_settings_v3,
+                fee_vault,
+                payer: program.payer(),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program,
+                system_program: system_program::ID,
+            })
+            .args(instruction::ExpireNftGame {}),
+    };
+    Ok(request.send()?)
+}
+
+/// Tournament games stake nothing, expiries forfeit the match of the player who didn't show up.
+pub fn submit_tournament(
+    program: &Program<Rc<Keypair>>,
+    bps_settings_v3: Pubkey,
+    game_address: Pubkey,
+    game: &GameV3,
+    action: Action,
+) -> Result<Signature> {
+    let request = program.request();
+    let request = match action {
+        Action::Claim => request
+            .accounts(accounts::ClaimTournamentMatch {
+                game: game_address,
+                tournament: game.tournament,
+                bps_settings_v3,
+            })
+            .args(instruction::ClaimTournamentMatch {}),
+        Action::ExpireOpenGame | Action::ExpireUnrevealedGame => {
+            let tournament: Tournament = program.account(game.tournament)?;
+            let match_index = tournament
+                .match_index(&game_address)
+                .ok_or_else(|| anyhow!("Game is not a match of the current round"))?;
+            request
+                .accounts(accounts::ForfeitTournamentMatch {
+                    tournament: game.tournament,
+                    game: Some(game_address),
+                    bps_settings_v3,
+                })
+                .args(instruction::ForfeitTournamentMatch {
+                    match_index: match_index as u8,
+                })
+        }
+    };
+    Ok(request.send()?)
+}
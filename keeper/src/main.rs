@@ -0,0 +1,162 @@
+mod audit;
+mod crank;
+
+use std::{rc::Rc, str::FromStr, thread, time::Duration};
+
+use anchor_client::{
+    solana_client::rpc_filter::{Memcmp, RpcFilterType},
+    solana_sdk::{
+        commitment_config::CommitmentConfig, pubkey::Pubkey, signature::read_keypair_file,
+    },
+    Client, Cluster,
+};
+use anyhow::{anyhow, Result};
+use bonk_paper_scissors::{
+    constants::{BPS_SETTINGS_V3, FEE_VAULT},
+    state::{BpsSettingsV3, Game, GameState, GameV3, Season},
+};
+
+use crank::{active_season, next_action};
+
+struct Config {
+    rpc_url: String,
+    keypair_path: String,
+    interval: Duration,
+    once: bool,
+    audit: bool,
+}
+
+impl Config {
+    fn from_args() -> Result<Self> {
+        let mut config = Config {
+            rpc_url: "http://127.0.0.1:8899".to_string(),
+            keypair_path: format!(
+                "{}/.config/solana/id.json",
+                std::env::var("HOME").unwrap_or_default()
+            ),
+            interval: Duration::from_secs(30),
+            once: false,
+            audit: false,
+        };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--rpc" => config.rpc_url = value()?,
+                "--keypair" => config.keypair_path = value()?,
+                "--interval" => config.interval = Duration::from_secs(value()?.parse()?),
+                "--once" => config.once = true,
+                "--audit" => config.audit = true,
+                _ => return Err(anyhow!("Unknown argument {}", arg)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Watches every game of the program and cranks the ones that can be settled. The payer keeps
+/// the rent of the escrows closed along the way. With `--audit`, reconciles the escrows of every
+/// game with its state instead and exits.
+fn main() -> Result<()> {
+    let config = Config::from_args()?;
+    let payer = read_keypair_file(&config.keypair_path)
+        .map_err(|err| anyhow!("Can't read keypair {}: {}", config.keypair_path, err))?;
+    let client = Client::new_with_options(
+        Cluster::from_str(&config.rpc_url).map_err(|err| anyhow!("{}", err))?,
+        Rc::new(payer),
+        CommitmentConfig::confirmed(),
+    );
+    let program = client.program(bonk_paper_scissors::ID)?;
+    if config.audit {
+        return match audit::reconcile(&program)? {
+            0 => Ok(()),
+            unbalanced => Err(anyhow!("{} games don't balance", unbalanced)),
+        };
+    }
+    let (bps_settings_v3, _) =
+        Pubkey::find_program_address(&[BPS_SETTINGS_V3.as_ref()], &bonk_paper_scissors::ID);
+    let (fee_vault, _) =
+        Pubkey::find_program_address(&[FEE_VAULT.as_ref()], &bonk_paper_scissors::ID);
+
+    loop {
+        let settings: BpsSettingsV3 = program.account(bps_settings_v3)?;
+        let rpc = program.rpc();
+        let now = rpc.get_block_time(rpc.get_slot()?)?;
+        let seasons = program.accounts::<Season>(vec![])?;
+
+        for (game_address, game) in program.accounts::<Game>(vec![])? {
+            match crank::migrate(&program, game_address, &game) {
+                Ok(signature) => println!("MigrateGame {}: {}", game_address, signature),
+                Err(err) => eprintln!("MigrateGame {} failed: {}", game_address, err),
+            }
+        }
+
+        // Settled games are never cranked, so only the two open states are fetched.
+        for game_state in [
+            GameState::CreatedAndWaitingForStart,
+            GameState::StartedAndWaitingForReveal,
+        ] {
+            let filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                GameV3::GAME_STATE_OFFSET,
+                vec![game_state as u8],
+            ))];
+            for (game_address, game) in program.accounts::<GameV3>(filters)? {
+                if let Some(action) = next_action(
+                    &game,
+                    now,
+                    game.time_for_penalization_or(settings.timers.time_for_penalization),
+                ) {
+                    let result = if game.is_native_sol() {
+                        crank::submit_sol(
+                            &program,
+                            bps_settings_v3,
+                            fee_vault,
+                            game_address,
+                            &game,
+                            action,
+                        )
+                    } else if game.is_nft() {
+                        crank::submit_nft(
+                            &program,
+                            bps_settings_v3,
+                            fee_vault,
+                            game_address,
+                            &game,
+                            action,
+                        )
+                    } else if game.is_tournament() {
+                        crank::submit_tournament(
+                            &program,
+                            bps_settings_v3,
+                            game_address,
+                            &game,
+                            action,
+                        )
+                    } else {
+                        crank::submit(
+                            &program,
+                            bps_settings_v3,
+                            fee_vault,
+                            active_season(&seasons, &game.mint, now),
+                            game_address,
+                            &game,
+                            action,
+                        )
+                    };
+                    match result {
+                        Ok(signature) => println!("{:?} {}: {}", action, game_address, signature),
+                        Err(err) => eprintln!("{:?} {} failed: {}", action, game_address, err),
+                    }
+                }
+            }
+        }
+
+        if config.once {
+            return Ok(());
+        }
+        thread::sleep(config.interval);
+    }
+}
@@ -1,7 +1,29 @@
+use anchor_lang::prelude::*;
+use solana_program::pubkey;
+
+/// Signs settings and unwinds.
+pub const ADMIN: Pubkey = pubkey!("bpstzWLPDetyjiD33HPGGE96MzkhEA7dhRFzhc8Ay5R");
+
 pub const BPS: [u8; 3] = *b"bps";
 pub const GAME: [u8; 4] = *b"game";
 pub const ESCROW: [u8; 6] = *b"escrow";
 
 pub const BPS_SETTINGS_V2: [u8; 15] = *b"bps_settings_v2";
+pub const BPS_SETTINGS_V3: [u8; 15] = *b"bps_settings_v3";
 pub const FIRST_PLAYER: [u8; 12] = *b"first_player";
 pub const SECOND_PLAYER: [u8; 13] = *b"second_player";
+pub const QUEUE_TICKET: [u8; 12] = *b"queue_ticket";
+pub const SIDE_POOL: [u8; 9] = *b"side_pool";
+pub const SIDE_BET: [u8; 8] = *b"side_bet";
+pub const SESSION_TOKEN: [u8; 13] = *b"session_token";
+pub const SOL_VAULT: [u8; 9] = *b"sol_vault";
+pub const ALLOWED_COLLECTION: [u8; 18] = *b"allowed_collection";
+pub const METADATA: [u8; 8] = *b"metadata";
+pub const TABLE: [u8; 5] = *b"table";
+pub const TOURNAMENT: [u8; 10] = *b"tournament";
+pub const SEASON: [u8; 6] = *b"season";
+pub const RULESET: [u8; 7] = *b"ruleset";
+pub const STAKE_LIMITS: [u8; 12] = *b"stake_limits";
+pub const OPEN_GAMES: [u8; 10] = *b"open_games";
+pub const FEE_VAULT: [u8; 9] = *b"fee_vault";
+pub const REVENUE_SPLIT: [u8; 13] = *b"revenue_split";
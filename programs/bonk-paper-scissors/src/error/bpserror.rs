@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum BPSError {
+    #[msg("Invalid Game State")]
+    InvalidGameState,
+    #[msg("Player already moved")]
+    PlayerAlreadyMoved,
+    #[msg("Invalid Player")]
+    InvalidPlayer,
+    #[msg("Invalid Hash")]
+    InvalidHash,
+    #[msg("First Player Can't Join as Second Player")]
+    FirstPlayerCantJoinAsSecondPlayer,
+    #[msg("Amount exceeds balance")]
+    AmountExceedsBalance,
+    #[msg("Instruction was removed")]
+    InstructionRemoved,
+    #[msg("Player can't match its own queue ticket")]
+    CantMatchOwnQueueTicket,
+    #[msg("Missing accounts to match the queue ticket")]
+    MissingQueueAccounts,
+    #[msg("Betting is closed for this game")]
+    BettingClosed,
+    #[msg("Side bets can only be placed on a final game state")]
+    InvalidSideBetOutcome,
+    #[msg("Players can't bet on their own games")]
+    PlayerCantBetOnOwnGame,
+    #[msg("Game is not settled yet")]
+    GameNotSettled,
+    #[msg("Rematch is reserved for the previous opponent")]
+    RematchReservedForOpponent,
+    #[msg("Session token expired")]
+    SessionExpired,
+    #[msg("Game stake exceeds the session token limit")]
+    SessionStakeExceeded,
+    #[msg("Game is not expired yet")]
+    GameNotExpired,
+    #[msg("Game was already migrated")]
+    GameAlreadyMigrated,
+    #[msg("Escrowed amount doesn't match the amount to match")]
+    EscrowAmountMismatch,
+    #[msg("Transfer fee overflow")]
+    TransferFeeOverflow,
+    #[msg("Game is not a native SOL game")]
+    NotNativeSolGame,
+    #[msg("Game is not an NFT game")]
+    NotNftGame,
+    #[msg("Invalid metadata account")]
+    InvalidMetadata,
+    #[msg("NFT is not a verified member of the collection")]
+    CollectionMismatch,
+    #[msg("Mint is not an NFT")]
+    NotAnNft,
+    #[msg("Tables seat 3 to 8 players")]
+    InvalidSeatCount,
+    #[msg("Table is full")]
+    TableFull,
+    #[msg("Player is already seated")]
+    AlreadySeated,
+    #[msg("Player is eliminated")]
+    PlayerEliminated,
+    #[msg("No seat missed its deadline")]
+    NoExpiredSeats,
+    #[msg("Missing or invalid seat accounts")]
+    InvalidSeatAccounts,
+    #[msg("Tournaments take 2 to 32 entrants")]
+    InvalidEntrantCount,
+    #[msg("Prize split must cover 1 to 8 places and add up to 10000 basis points")]
+    InvalidPrizeSplit,
+    #[msg("Registration is closed")]
+    RegistrationClosed,
+    #[msg("Registration is still open")]
+    RegistrationOpen,
+    #[msg("Tournament is full")]
+    TournamentFull,
+    #[msg("Player is already registered")]
+    AlreadyRegistered,
+    #[msg("Game is not a tournament game")]
+    NotTournamentGame,
+    #[msg("Match is already decided")]
+    MatchDecided,
+    #[msg("Match deadline hasn't passed")]
+    MatchNotExpired,
+    #[msg("Entrant has no prize to claim")]
+    NoPrize,
+    #[msg("Season must end after it starts and award at least a point per stake")]
+    InvalidSeason,
+    #[msg("Reward split must cover 1 to 10 ranks and add up to 10000 basis points")]
+    InvalidRewardSplit,
+    #[msg("Game mint doesn't match the season")]
+    SeasonMismatch,
+    #[msg("Season is not active")]
+    SeasonNotActive,
+    #[msg("Season is not over yet")]
+    SeasonNotOver,
+    #[msg("Player has no reward to claim")]
+    NoReward,
+    #[msg("Ruleset must have 2 to 8 choices, none beating itself and no two beating each other")]
+    InvalidRuleset,
+    #[msg("Choice doesn't exist in the game's ruleset")]
+    InvalidChoice,
+    #[msg("Stake limits need a minimum above 0, a maximum above it and up to 8 ascending tiers between them")]
+    InvalidStakeLimits,
+    #[msg("Stake is below the minimum for this mint")]
+    StakeBelowMinimum,
+    #[msg("Stake is above the maximum for this mint")]
+    StakeAboveMaximum,
+    #[msg("Stake isn't one of the tiers of this mint")]
+    StakeNotInTiers,
+    #[msg("Mint has no stake limits, the admin hasn't allowed it")]
+    MintNotAllowed,
+    #[msg("Mint has a Token-2022 extension other than the transfer fee")]
+    UnsupportedMintExtension,
+    #[msg("Player has too many games going at once")]
+    TooManyOpenGames,
+    #[msg("Open games account of a player holding a slot in this game is missing")]
+    MissingOpenGames,
+    #[msg("Escrow of a player who joined the game is missing")]
+    MissingEscrow,
+    #[msg("Mint of the game is missing")]
+    MissingMint,
+    #[msg("Fee vault holds nothing above its rent")]
+    NothingToSweep,
+    #[msg("Revenue split must have 1 to 5 distinct recipients and add up to 10000 basis points")]
+    InvalidRevenueSplit,
+    #[msg("Recipient accounts don't match the revenue split")]
+    InvalidRevenueRecipients,
+    #[msg("Only token games can be rematched, not native SOL, NFT or tournament ones")]
+    RematchNotSupported,
+    #[msg("Player has no open game to give back")]
+    NoOpenGames,
+    #[msg("Penalization time must be between 1 and 4294967295 seconds")]
+    InvalidTimeForPenalization,
+    #[msg("Payout must be between 1 and 10000 basis points")]
+    InvalidPayoutBasisPoints,
+    #[msg("Settings flag is not defined")]
+    UnknownSettingsFlags,
+}
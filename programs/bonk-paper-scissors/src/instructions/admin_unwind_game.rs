@@ -1,14 +1,14 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use solana_program::pubkey;
 
 use crate::{
-    constants::GAME,
+    constants::{ADMIN, GAME, OPEN_GAMES},
     error::BPSError,
-    state::{Game, GameState},
+    state::{GameState, GameV3, OpenGames},
+    utils::refund_fees,
 };
 
 #[derive(Accounts)]
@@ -19,107 +19,140 @@ pub struct AdminUnwindStaleGame<'info> {
         seeds = [
             GAME.as_ref(),
             first_player.key().as_ref(),
-            game.game_id.as_bytes()
+            game.load()?.game_id()
         ],
-        constraint = game.game_state == GameState::StartedAndWaitingForReveal,
-        bump = game.bump
+        constraint = game.load()?.game_state() == GameState::StartedAndWaitingForReveal,
+        bump = game.load()?.bump
     )]
-    pub game: Account<'info, Game>,
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
 
     #[account(
         mut,
         // TODO: Figure out a way to close this, maybe in a different instruction?
         // close = first_player,
-        token::mint = game.mint,
+        token::mint = game.load()?.mint,
         token::authority = game,
     )]
-    pub first_player_escrow: Box<Account<'info, TokenAccount>>,
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut,
-        constraint = first_player_token_account.mint == game.mint,
+        constraint = first_player_token_account.mint == game.load()?.mint,
         constraint = first_player_token_account.owner == first_player.key(),
-        constraint = first_player_token_account.amount >= game.amount_to_match
+        constraint = first_player_token_account.amount >= game.load()?.amount_to_match
     )]
-    pub first_player_token_account: Box<Account<'info, TokenAccount>>,
+    pub first_player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         // TODO: Figure out a way to close this, maybe in a different instruction?
         // close = second_player,
-        token::mint = game.mint,
+        token::mint = game.load()?.mint,
         token::authority = game,
     )]
-    pub second_player_escrow: Box<Account<'info, TokenAccount>>,
+    pub second_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut,
-        constraint = second_player_token_account.mint == game.mint,
+        constraint = second_player_token_account.mint == game.load()?.mint,
         constraint = second_player_token_account.owner == second_player.key(),
-        constraint = second_player_token_account.amount >= game.amount_to_match
+        constraint = second_player_token_account.amount >= game.load()?.amount_to_match
+    )]
+    pub second_player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
     )]
-    pub second_player_token_account: Box<Account<'info, TokenAccount>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// CHECK: No use to check this. (Checked by CPI)
     #[account(
         mut,
-        address = game.first_player,
+        address = game.load()?.first_player,
     )]
     pub first_player: AccountInfo<'info>,
     /// CHECK: No use to check this. (Checked by CPI)
     #[account(
         mut,
-        address = game.second_player.unwrap(),
+        address = game.load()?.second_player().unwrap(),
     )]
     pub second_player: AccountInfo<'info>,
 
     // Program upgrade authority
-    #[account(mut, address = pubkey!("bpstzWLPDetyjiD33HPGGE96MzkhEA7dhRFzhc8Ay5R"))]
+    #[account(mut, address = ADMIN)]
     pub signer: Signer<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn admin_unwind_stale_game(ctx: Context<AdminUnwindStaleGame>) -> Result<()> {
-    let game = &mut ctx.accounts.game;
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    let game_info = ctx.accounts.game.to_account_info();
+    refund_fees(
+        &mut game,
+        &game_info,
+        &ctx.accounts.first_player,
+        &ctx.accounts.second_player,
+    )?;
     let first_player_escrow = &mut ctx.accounts.first_player_escrow;
     let first_player_token_account = &mut ctx.accounts.first_player_token_account;
     let second_player_escrow = &mut ctx.accounts.second_player_escrow;
     let second_player_token_account = &mut ctx.accounts.second_player_token_account;
+    let mint = &ctx.accounts.mint;
     require!(
-        game.game_state == GameState::StartedAndWaitingForReveal,
+        game.game_state() == GameState::StartedAndWaitingForReveal,
         BPSError::InvalidGameState
     );
     let game_seeds = &[
         b"game",
         game.first_player.as_ref(),
-        game.game_id.as_bytes(),
+        game.game_id(),
         &[game.bump],
     ];
     let game_signer = &[&game_seeds[..]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: first_player_escrow.to_account_info(),
+                mint: mint.to_account_info(),
                 to: first_player_token_account.to_account_info(),
-                authority: game.to_account_info(),
+                authority: game_info.clone(),
             },
         )
         .with_signer(game_signer),
         game.amount_to_match,
+        mint.decimals,
     )?;
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: second_player_escrow.to_account_info(),
+                mint: mint.to_account_info(),
                 to: second_player_token_account.to_account_info(),
-                authority: game.to_account_info(),
+                authority: game_info.clone(),
             },
         )
         .with_signer(game_signer),
         game.amount_to_match,
+        mint.decimals,
     )?;
     Ok(())
 }
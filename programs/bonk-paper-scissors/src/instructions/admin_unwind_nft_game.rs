@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{ADMIN, GAME, OPEN_GAMES},
+    error::BPSError,
+    state::{GameState, GameV3, OpenGames},
+    utils::{refund_fees, NftRelease},
+};
+
+#[derive(Accounts)]
+pub struct AdminUnwindStaleNftGame<'info> {
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game.load()?.game_id()
+        ],
+        constraint = game.load()?.is_nft() @ BPSError::NotNftGame,
+        constraint = game.load()?.game_state() == GameState::StartedAndWaitingForReveal,
+        bump = game.load()?.bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+
+    #[account(
+        mut,
+        address = game.load()?.first_player_escrow_address,
+    )]
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub first_player_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub first_player_token_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = game.load()?.second_player_escrow_address().unwrap(),
+    )]
+    pub second_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.second_player_mint().unwrap(),
+        mint::token_program = token_program,
+    )]
+    pub second_player_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub second_player_token_account: AccountInfo<'info>,
+
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.first_player,
+    )]
+    pub first_player: AccountInfo<'info>,
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.second_player().unwrap(),
+    )]
+    pub second_player: AccountInfo<'info>,
+
+    // Program upgrade authority
+    #[account(mut, address = ADMIN)]
+    pub signer: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn admin_unwind_stale_nft_game(ctx: Context<AdminUnwindStaleNftGame>) -> Result<()> {
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    let game_info = ctx.accounts.game.to_account_info();
+    refund_fees(
+        &mut game,
+        &game_info,
+        &ctx.accounts.first_player,
+        &ctx.accounts.second_player,
+    )?;
+    let accounts = &ctx.accounts;
+    let game_seeds = &[
+        b"game",
+        game.first_player.as_ref(),
+        game.game_id(),
+        &[game.bump],
+    ];
+    let nft_release = NftRelease {
+        game: accounts.game.to_account_info(),
+        game_signer: &[&game_seeds[..]],
+        payer: accounts.signer.to_account_info(),
+        token_program: accounts.token_program.to_account_info(),
+        associated_token_program: accounts.associated_token_program.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+    };
+    // Each escrow's rent goes back to the player who paid for it.
+    nft_release.release(
+        &accounts.first_player_escrow.to_account_info(),
+        &accounts.first_player_mint.to_account_info(),
+        &accounts.first_player_token_account,
+        &accounts.first_player,
+        &accounts.first_player,
+    )?;
+    nft_release.release(
+        &accounts.second_player_escrow.to_account_info(),
+        &accounts.second_player_mint.to_account_info(),
+        &accounts.second_player_token_account,
+        &accounts.second_player,
+        &accounts.second_player,
+    )
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, GAME, OPEN_GAMES, SOL_VAULT},
+    error::BPSError,
+    state::{GameState, GameV3, OpenGames, SolVault},
+    utils::{refund_fees, transfer_lamports},
+};
+
+#[derive(Accounts)]
+pub struct AdminUnwindStaleSolGame<'info> {
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game.load()?.game_id()
+        ],
+        constraint = game.load()?.is_native_sol() @ BPSError::NotNativeSolGame,
+        constraint = game.load()?.game_state() == GameState::StartedAndWaitingForReveal,
+        bump = game.load()?.bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [SOL_VAULT.as_ref(), game.key().as_ref()],
+        bump = sol_vault.bump,
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.first_player,
+    )]
+    pub first_player: AccountInfo<'info>,
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.second_player().unwrap(),
+    )]
+    pub second_player: AccountInfo<'info>,
+
+    // Program upgrade authority
+    #[account(mut, address = ADMIN)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn admin_unwind_stale_sol_game(ctx: Context<AdminUnwindStaleSolGame>) -> Result<()> {
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    let game_info = ctx.accounts.game.to_account_info();
+    refund_fees(
+        &mut game,
+        &game_info,
+        &ctx.accounts.first_player,
+        &ctx.accounts.second_player,
+    )?;
+    let sol_vault = &ctx.accounts.sol_vault.to_account_info();
+    transfer_lamports(sol_vault, &ctx.accounts.first_player, game.amount_to_match)?;
+    transfer_lamports(sol_vault, &ctx.accounts.second_player, game.amount_to_match)?;
+    Ok(())
+}
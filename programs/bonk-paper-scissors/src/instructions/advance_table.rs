@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, TABLE},
+    error::BPSError,
+    state::{BpsSettingsV3, Table, TableState},
+};
+
+#[derive(Accounts)]
+pub struct AdvanceTable<'info> {
+    #[account(
+        mut,
+        seeds = [TABLE.as_ref(), table.creator.as_ref(), table.table_id.as_bytes()],
+        bump = table.bump,
+        constraint = matches!(table.state, TableState::Committing | TableState::Revealing) @ BPSError::InvalidGameState,
+    )]
+    pub table: Box<Account<'info, Table>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+}
+
+/// Eliminates the seats that missed their deadline, anyone can crank it.
+pub fn advance_table(ctx: Context<AdvanceTable>) -> Result<()> {
+    let clock = Clock::get()?;
+    let forfeits = ctx.accounts.table.forfeit_expired_seats(
+        clock.unix_timestamp,
+        ctx.accounts.bps_settings_v3.timers.time_for_penalization,
+    );
+    require!(forfeits > 0, BPSError::NoExpiredSeats);
+    Ok(())
+}
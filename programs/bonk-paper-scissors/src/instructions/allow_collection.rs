@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, ALLOWED_COLLECTION},
+    state::AllowedCollection,
+};
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct AllowCollection<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = AllowedCollection::size(),
+        seeds = [ALLOWED_COLLECTION.as_ref(), collection.as_ref()],
+        bump
+    )]
+    pub allowed_collection: Account<'info, AllowedCollection>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn allow_collection(ctx: Context<AllowCollection>, collection: Pubkey) -> Result<()> {
+    let bump = ctx.bumps.allowed_collection;
+    ctx.accounts
+        .allowed_collection
+        .set_inner(AllowedCollection::new(bump, collection));
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    constants::GAME,
+    error::BPSError,
+    state::{EscrowAudit, GameAudit, GameV3},
+};
+
+#[derive(Accounts)]
+pub struct AuditGame<'info> {
+    #[account(
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    /// CHECK: Only its balance is read, a closed escrow holds nothing.
+    #[account(address = game.load()?.first_player_escrow_address)]
+    pub first_player_escrow: AccountInfo<'info>,
+    /// CHECK: Same as above, required once the second player joined a token or NFT game.
+    #[account(address = game.load()?.second_player_escrow_address)]
+    pub second_player_escrow: Option<AccountInfo<'info>>,
+}
+
+fn token_balance(escrow: &AccountInfo) -> Result<u64> {
+    if escrow.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(TokenAccount::try_deserialize(&mut &escrow.try_borrow_data()?[..])?.amount)
+}
+
+/// Read-only, compares the escrow balances with what the game state implies and returns the
+/// result. Stakes are held until the game is settled, and the escrows are emptied and closed by
+/// the settlement.
+pub fn audit_game(ctx: Context<AuditGame>) -> Result<GameAudit> {
+    let game = *ctx.accounts.game.load()?;
+    let first_player_escrow = &ctx.accounts.first_player_escrow;
+    let second_player_joined = game.second_player().is_some();
+    let stake = |joined: bool| {
+        if joined && !game.is_settled() {
+            game.amount_to_match
+        } else {
+            0
+        }
+    };
+
+    let mut escrows = vec![];
+    if game.is_native_sol() {
+        // Both stakes go to the same vault.
+        let rent = Rent::get()?.minimum_balance(first_player_escrow.data_len());
+        escrows.push(EscrowAudit {
+            address: first_player_escrow.key(),
+            expected: stake(true) + stake(second_player_joined),
+            actual: first_player_escrow.lamports().saturating_sub(rent),
+        });
+    } else if !game.is_tournament() {
+        escrows.push(EscrowAudit {
+            address: first_player_escrow.key(),
+            expected: stake(true),
+            actual: token_balance(first_player_escrow)?,
+        });
+        if second_player_joined {
+            let second_player_escrow = ctx
+                .accounts
+                .second_player_escrow
+                .as_ref()
+                .ok_or_else(|| error!(BPSError::MissingEscrow))?;
+            escrows.push(EscrowAudit {
+                address: second_player_escrow.key(),
+                expected: stake(true),
+                actual: token_balance(second_player_escrow)?,
+            });
+        }
+    }
+
+    Ok(GameAudit {
+        game_state: game.game_state(),
+        escrows,
+    })
+}
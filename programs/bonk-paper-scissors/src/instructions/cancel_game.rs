@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 use crate::{
-    constants::{ESCROW, FIRST_PLAYER, GAME},
+    constants::{BPS_SETTINGS_V3, ESCROW, FEE_VAULT, FIRST_PLAYER, GAME, OPEN_GAMES},
     error::BPSError,
-    state::{Game, GameState},
+    state::{BpsSettingsV3, FeeVault, GameState, GameV3, OpenGames},
+    utils::release_fees,
 };
 
 #[derive(Accounts)]
@@ -15,17 +18,24 @@ pub struct CancelGame<'info> {
         seeds = [
             GAME.as_ref(),
             first_player.key().as_ref(),
-            game.game_id.as_bytes()
+            game.load()?.game_id()
         ],
-        bump = game.bump,
-        constraint = game.game_state == GameState::CreatedAndWaitingForStart @ BPSError::InvalidGameState,
+        bump = game.load()?.bump,
+        constraint = game.load()?.game_state() == GameState::CreatedAndWaitingForStart @ BPSError::InvalidGameState,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    // Required when the game counts toward the first player's open games.
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
     )]
-    pub game: Account<'info, Game>,
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
     #[account(
         mut,
         // TODO: Figure out a way to close this, maybe in a different instruction?
         // close = first_player,
-        token::mint = game.mint.key(),
+        token::mint = game.load()?.mint,
         token::authority = game,
         seeds = [
             game.key().as_ref(),
@@ -34,44 +44,77 @@ pub struct CancelGame<'info> {
         ],
         bump,
     )]
-    pub first_player_escrow: Account<'info, TokenAccount>,
+    pub first_player_escrow: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = first_player_token_account.mint == game.mint,
+        constraint = first_player_token_account.mint == game.load()?.mint,
         constraint = first_player_token_account.owner == first_player.key(),
     )]
-    pub first_player_token_account: Account<'info, TokenAccount>,
+    pub first_player_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
     #[account(
         mut,
-        address = game.first_player,
+        address = game.load()?.first_player,
     )]
     pub first_player: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn cancel_game(ctx: Context<CancelGame>) -> Result<()> {
-    let game = &ctx.accounts.game;
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(ctx.accounts.first_player_open_games.as_deref_mut(), None)?;
+    let refunds = game.fee_refunds(
+        clock.unix_timestamp,
+        game.time_for_penalization_or(ctx.accounts.bps_settings_v3.timers.time_for_penalization),
+    );
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player.to_account_info(),
+        None,
+        &mut ctx.accounts.fee_vault,
+    )?;
     let first_player_escrow = &ctx.accounts.first_player_escrow;
     let first_player_token_account = &ctx.accounts.first_player_token_account;
+    let mint = &ctx.accounts.mint;
     let game_seeds = &[
         b"game",
         game.first_player.as_ref(),
-        game.game_id.as_bytes(),
+        game.game_id(),
         &[game.bump],
     ];
     let game_signer = &[&game_seeds[..]];
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: first_player_escrow.to_account_info(),
+                mint: mint.to_account_info(),
                 to: first_player_token_account.to_account_info(),
-                authority: game.to_account_info(),
+                authority: ctx.accounts.game.to_account_info(),
             },
         )
         .with_signer(game_signer),
         game.amount_to_match,
+        mint.decimals,
     )?;
     Ok(())
 }
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, FEE_VAULT, GAME, OPEN_GAMES},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, GameState, GameV3, OpenGames},
+    utils::{release_fees, NftRelease},
+};
+
+#[derive(Accounts)]
+pub struct CancelNftGame<'info> {
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_nft() @ BPSError::NotNftGame,
+        constraint = game.load()?.game_state() == GameState::CreatedAndWaitingForStart @ BPSError::InvalidGameState,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    // Required when the game counts toward the first player's open games.
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        address = game.load()?.first_player_escrow_address,
+    )]
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub first_player_token_account: AccountInfo<'info>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    #[account(
+        mut,
+        address = game.load()?.first_player,
+    )]
+    pub first_player: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancels an NFT game, returning the NFT and the escrow rent to the first player.
+pub fn cancel_nft_game(ctx: Context<CancelNftGame>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(ctx.accounts.first_player_open_games.as_deref_mut(), None)?;
+    let refunds = game.fee_refunds(
+        clock.unix_timestamp,
+        game.time_for_penalization_or(ctx.accounts.bps_settings_v3.timers.time_for_penalization),
+    );
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player.to_account_info(),
+        None,
+        &mut ctx.accounts.fee_vault,
+    )?;
+    let accounts = &ctx.accounts;
+    let game_seeds = &[
+        b"game",
+        game.first_player.as_ref(),
+        game.game_id(),
+        &[game.bump],
+    ];
+    let first_player = accounts.first_player.to_account_info();
+    NftRelease {
+        game: accounts.game.to_account_info(),
+        game_signer: &[&game_seeds[..]],
+        payer: first_player.clone(),
+        token_program: accounts.token_program.to_account_info(),
+        associated_token_program: accounts.associated_token_program.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+    }
+    .release(
+        &accounts.first_player_escrow.to_account_info(),
+        &accounts.mint.to_account_info(),
+        &accounts.first_player_token_account,
+        &first_player,
+        &first_player,
+    )
+}
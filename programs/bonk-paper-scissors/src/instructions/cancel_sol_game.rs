@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, FEE_VAULT, GAME, OPEN_GAMES, SOL_VAULT},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, GameState, GameV3, OpenGames, SolVault},
+    utils::release_fees,
+};
+
+#[derive(Accounts)]
+pub struct CancelSolGame<'info> {
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_native_sol() @ BPSError::NotNativeSolGame,
+        constraint = game.load()?.game_state() == GameState::CreatedAndWaitingForStart @ BPSError::InvalidGameState,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    // Required when the game counts toward the first player's open games.
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [SOL_VAULT.as_ref(), game.key().as_ref()],
+        bump = sol_vault.bump,
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    #[account(
+        mut,
+        address = game.load()?.first_player,
+    )]
+    pub first_player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Closing the vault returns the stake along with its rent.
+pub fn cancel_sol_game(ctx: Context<CancelSolGame>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(ctx.accounts.first_player_open_games.as_deref_mut(), None)?;
+    let refunds = game.fee_refunds(
+        clock.unix_timestamp,
+        game.time_for_penalization_or(ctx.accounts.bps_settings_v3.timers.time_for_penalization),
+    );
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player.to_account_info(),
+        None,
+        &mut ctx.accounts.fee_vault,
+    )?;
+    Ok(())
+}
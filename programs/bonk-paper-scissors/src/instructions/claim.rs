@@ -1,13 +1,17 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer},
+    token_interface::{
+        burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount,
+        TokenInterface, TransferChecked,
+    },
 };
 
 use crate::{
-    constants::{BPS_SETTINGS_V2, GAME},
+    constants::{BPS_SETTINGS_V3, FEE_VAULT, GAME, OPEN_GAMES, SEASON},
     error::BPSError,
-    state::{BpsSettingsV2, Choice, Game, GameState},
+    state::{BpsSettingsV3, FeeVault, GameState, GameV3, OpenGames, Season},
+    utils::{harvest_withheld_fees, release_fees, transfer_fee},
 };
 
 #[derive(Accounts)]
@@ -16,153 +20,169 @@ pub struct Claim<'info> {
         mut,
         seeds = [
             GAME.as_ref(),
-            game.first_player.as_ref(),
-            game.game_id.as_bytes()
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
         ],
-        bump = game.bump
+        bump = game.load()?.bump
     )]
-    pub game: Box<Account<'info, Game>>,
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
 
     #[account(
-        seeds = [BPS_SETTINGS_V2.as_ref()],
-        bump = bps_settings_v2.bump
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
     )]
-    pub bps_settings_v2: Account<'info, BpsSettingsV2>,
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    // Credits the winner on the leaderboard when passed.
+    #[account(
+        mut,
+        seeds = [SEASON.as_ref(), season.season_id.as_bytes()],
+        bump = season.bump,
+    )]
+    pub season: Option<Box<Account<'info, Season>>>,
 
     #[account(
         mut,
-        // TODO: Figure out a way to close this, maybe in a different instruction?
-        // close = first_player,
-        token::mint = game.mint,
+        token::mint = game.load()?.mint,
         token::authority = game,
     )]
-    pub first_player_escrow: Box<Account<'info, TokenAccount>>,
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut,
-        constraint = first_player_token_account.mint == game.mint,
+        constraint = first_player_token_account.mint == game.load()?.mint,
         constraint = first_player_token_account.owner == first_player.key(),
     )]
-    pub first_player_token_account: Box<Account<'info, TokenAccount>>,
+    pub first_player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        // TODO: Figure out a way to close this, maybe in a different instruction?
-        // close = second_player,
-        token::mint = game.mint,
+        token::mint = game.load()?.mint,
         token::authority = game,
     )]
-    pub second_player_escrow: Box<Account<'info, TokenAccount>>,
+    pub second_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut,
-        constraint = second_player_token_account.mint == game.mint,
+        constraint = second_player_token_account.mint == game.load()?.mint,
         constraint = second_player_token_account.owner == second_player.key(),
     )]
-    pub second_player_token_account: Box<Account<'info, TokenAccount>>,
+    pub second_player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        address = game.mint
+        address = game.load()?.mint,
+        mint::token_program = token_program,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// CHECK: No use to check this. (Checked by CPI)
     #[account(
         mut,
-        address = game.first_player
+        address = game.load()?.first_player
     )]
     pub first_player: AccountInfo<'info>,
     /// CHECK: No use to check this. (Checked by CPI)
     #[account(
         mut,
-        address = game.second_player.unwrap()
+        address = game.load()?.second_player().unwrap()
     )]
     pub second_player: AccountInfo<'info>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn claim(ctx: Context<Claim>) -> Result<()> {
     let clock = Clock::get()?;
-    let game = &mut ctx.accounts.game;
+    let mut game = *ctx.accounts.game.load()?;
+    let game_info = ctx.accounts.game.to_account_info();
     let mint = &ctx.accounts.mint;
-    let bps_settings_v2 = &ctx.accounts.bps_settings_v2;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let time_for_penalization =
+        game.time_for_penalization_or(bps_settings_v3.timers.time_for_penalization);
     let first_player = &ctx.accounts.first_player;
     let first_player_escrow = &mut ctx.accounts.first_player_escrow;
     let first_player_token_account = &mut ctx.accounts.first_player_token_account;
     let second_player = &ctx.accounts.second_player;
     let second_player_escrow = &mut ctx.accounts.second_player_escrow;
     let second_player_token_account = &mut ctx.accounts.second_player_token_account;
+    let payer = &ctx.accounts.payer;
 
     require!(
-        game.game_state == GameState::StartedAndWaitingForReveal,
+        game.game_state() == GameState::StartedAndWaitingForReveal,
+        BPSError::InvalidGameState
+    );
+    require!(
+        game.is_claimable(clock.unix_timestamp, time_for_penalization),
         BPSError::InvalidGameState
     );
-    // require!(
-    //     game.first_player_revealed_at.is_some() && game.second_player_revealed_at.is_some(),
-    //     BPSError::InvalidGameState
-    // );
 
+    // Owned copies, the game is updated while the signer is still needed to close the escrows.
+    let game_first_player = game.first_player;
+    let game_id = game.game_id;
     let game_seeds = &[
         b"game",
-        game.first_player.as_ref(),
-        game.game_id.as_bytes(),
+        game_first_player.as_ref(),
+        &game_id[..game.game_id_len as usize],
         &[game.bump],
     ];
     let game_signer = &[&game_seeds[..]];
 
-    let first_player_choice = game.first_player_choice.as_ref().unwrap();
-    let second_player_choice = game.second_player_choice.as_ref().unwrap();
-
-    let first_player_wins = game
-        .did_second_player_forfeit(clock.unix_timestamp, bps_settings_v2.time_for_penalization)
-        || match (first_player_choice, second_player_choice) {
-            (Choice::Bonk, Choice::Scissors) => true,
-            (Choice::Paper, Choice::Bonk) => true,
-            (Choice::Scissors, Choice::Paper) => true,
-            _ => false,
-        };
+    let outcome = game.outcome(clock.unix_timestamp, time_for_penalization);
 
-    let second_player_wins = game
-        .did_first_player_forfeit(clock.unix_timestamp, bps_settings_v2.time_for_penalization)
-        || match (second_player_choice, first_player_choice) {
-            (Choice::Bonk, Choice::Scissors) => true,
-            (Choice::Paper, Choice::Bonk) => true,
-            (Choice::Scissors, Choice::Paper) => true,
-            _ => false,
-        };
+    // Burn the remainder so no dust is left behind and the escrows can be closed.
+    let (amount_to_pay, amount_to_burn) = game.payout_split();
+    // Transfer-fee mints withhold part of each payout from the winner.
+    let amount_won = (amount_to_pay - transfer_fee(&mint.to_account_info(), amount_to_pay)?) * 2;
 
-    let amount_to_pay = (game.amount_to_match as f64 * 0.9) as u64;
-    let amount_to_burn = (game.amount_to_match as f64 * 0.1) as u64;
-
-    if first_player_wins {
+    if outcome == GameState::FirstPlayerWon {
         // ----- Transfer 90% of the tokens to the first player -----
-        transfer(
+        transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: first_player_escrow.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: first_player_token_account.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                 },
             )
             .with_signer(game_signer),
             amount_to_pay,
+            mint.decimals,
         )?;
-        transfer(
+        transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: second_player_escrow.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: first_player_token_account.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                 },
             )
             .with_signer(game_signer),
             amount_to_pay,
+            mint.decimals,
         )?;
         // ----- Transfer 90% of the tokens to the first player -----
 
@@ -172,7 +192,7 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
                 ctx.accounts.token_program.to_account_info(),
                 Burn {
                     mint: mint.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                     from: first_player_escrow.to_account_info(),
                 },
             )
@@ -184,7 +204,7 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
                 ctx.accounts.token_program.to_account_info(),
                 Burn {
                     mint: mint.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                     from: second_player_escrow.to_account_info(),
                 },
             )
@@ -193,40 +213,44 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
         )?; // Burn 10%
             // ----- Burn 10% of each player's funds -----
 
-        game.game_state = GameState::FirstPlayerWon;
+        game.set_game_state(GameState::FirstPlayerWon);
         game.set_claim_fields(
             first_player.key(),
             second_player.key(),
-            amount_to_pay * 2,
+            amount_won,
             amount_to_burn * 2,
             clock.unix_timestamp,
         );
-    } else if second_player_wins {
+    } else if outcome == GameState::SecondPlayerWon {
         msg!("Second player wins");
         // ----- Transfer 90% of the tokens to the second player -----
-        transfer(
+        transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: second_player_escrow.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: second_player_token_account.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                 },
             )
             .with_signer(game_signer),
             amount_to_pay,
+            mint.decimals,
         )?;
-        transfer(
+        transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: first_player_escrow.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: second_player_token_account.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                 },
             )
             .with_signer(game_signer),
             amount_to_pay,
+            mint.decimals,
         )?;
         // ----- Transfer 90% of the tokens to the second player -----
 
@@ -236,7 +260,7 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
                 ctx.accounts.token_program.to_account_info(),
                 Burn {
                     mint: mint.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                     from: first_player_escrow.to_account_info(),
                 },
             )
@@ -248,7 +272,7 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
                 ctx.accounts.token_program.to_account_info(),
                 Burn {
                     mint: mint.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                     from: second_player_escrow.to_account_info(),
                 },
             )
@@ -257,42 +281,46 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
         )?; // Burn 10%
             // ----- Burn 10% of each player's funds -----
 
-        game.game_state = GameState::SecondPlayerWon;
+        game.set_game_state(GameState::SecondPlayerWon);
         game.set_claim_fields(
             second_player.key(),
             first_player.key(),
-            amount_to_pay * 2,
+            amount_won,
             amount_to_burn * 2,
             clock.unix_timestamp,
         );
     } else {
         // ----- Return all funds to the original accounts -----
-        transfer(
+        transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: first_player_escrow.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: first_player_token_account.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                 },
             )
             .with_signer(game_signer),
             game.amount_to_match,
+            mint.decimals,
         )?;
-        transfer(
+        transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: second_player_escrow.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: second_player_token_account.to_account_info(),
-                    authority: game.to_account_info(),
+                    authority: game_info.clone(),
                 },
             )
             .with_signer(game_signer),
             game.amount_to_match,
+            mint.decimals,
         )?;
         // ----- Return all funds to the original accounts -----
-        game.game_state = GameState::Draw;
+        game.set_game_state(GameState::Draw);
         game.set_claim_fields(
             second_player.key(),
             first_player.key(),
@@ -302,5 +330,55 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
         );
     }
 
+    // ----- Close the escrows, their rent is the reward for whoever cranks the claim -----
+    harvest_withheld_fees(
+        &ctx.accounts.token_program.to_account_info(),
+        &mint.to_account_info(),
+        &[
+            first_player_escrow.to_account_info(),
+            second_player_escrow.to_account_info(),
+        ],
+    )?;
+    close_account(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: first_player_escrow.to_account_info(),
+                destination: payer.to_account_info(),
+                authority: game_info.clone(),
+            },
+        )
+        .with_signer(game_signer),
+    )?;
+    close_account(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: second_player_escrow.to_account_info(),
+                destination: payer.to_account_info(),
+                authority: game_info.clone(),
+            },
+        )
+        .with_signer(game_signer),
+    )?;
+    // ----- Close the escrows, their rent is the reward for whoever cranks the claim -----
+
+    if let Some(season) = ctx.accounts.season.as_mut() {
+        season.record_game(&game, clock.unix_timestamp)?;
+    }
+    let refunds = game.fee_refunds(clock.unix_timestamp, time_for_penalization);
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player,
+        Some(&ctx.accounts.second_player),
+        &mut ctx.accounts.fee_vault,
+    )?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    *ctx.accounts.game.load_mut()? = game;
     Ok(())
 }
@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, FEE_VAULT, GAME, OPEN_GAMES},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, GameState, GameV3, OpenGames},
+    utils::{release_fees, NftRelease},
+};
+
+#[derive(Accounts)]
+pub struct ClaimNft<'info> {
+    #[account(
+        mut,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_nft() @ BPSError::NotNftGame,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        mut,
+        address = game.load()?.first_player_escrow_address,
+    )]
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub first_player_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub first_player_token_account: AccountInfo<'info>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub first_player_prize_token_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = game.load()?.second_player_escrow_address().unwrap(),
+    )]
+    pub second_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.second_player_mint().unwrap(),
+        mint::token_program = token_program,
+    )]
+    pub second_player_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub second_player_token_account: AccountInfo<'info>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub second_player_prize_token_account: AccountInfo<'info>,
+
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.first_player
+    )]
+    pub first_player: AccountInfo<'info>,
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.second_player().unwrap()
+    )]
+    pub second_player: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settles an NFT game, the winner gets both NFTs and a draw returns each to its owner. The
+/// `*_token_account`s hold a player's own NFT, the `*_prize_token_account`s the opponent's.
+pub fn claim_nft(ctx: Context<ClaimNft>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let time_for_penalization =
+        game.time_for_penalization_or(bps_settings_v3.timers.time_for_penalization);
+    let accounts = &ctx.accounts;
+
+    require!(
+        game.game_state() == GameState::StartedAndWaitingForReveal,
+        BPSError::InvalidGameState
+    );
+    require!(
+        game.is_claimable(clock.unix_timestamp, time_for_penalization),
+        BPSError::InvalidGameState
+    );
+
+    let game_seeds = &[
+        b"game",
+        game.first_player.as_ref(),
+        game.game_id(),
+        &[game.bump],
+    ];
+    let game_signer = &[&game_seeds[..]];
+    let nft_release = NftRelease {
+        game: accounts.game.to_account_info(),
+        game_signer,
+        payer: accounts.payer.to_account_info(),
+        token_program: accounts.token_program.to_account_info(),
+        associated_token_program: accounts.associated_token_program.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+    };
+    let first_player_escrow = accounts.first_player_escrow.to_account_info();
+    let first_player_mint = accounts.first_player_mint.to_account_info();
+    let second_player_escrow = accounts.second_player_escrow.to_account_info();
+    let second_player_mint = accounts.second_player_mint.to_account_info();
+    let first_player = &accounts.first_player;
+    let second_player = &accounts.second_player;
+    let payer = accounts.payer.to_account_info();
+
+    // The escrow rent is the reward for whoever cranks the claim.
+    let outcome = game.outcome(clock.unix_timestamp, time_for_penalization);
+    let (first_player_nft_to, second_player_nft_to) = match outcome {
+        GameState::FirstPlayerWon => (
+            (&accounts.first_player_token_account, first_player),
+            (&accounts.first_player_prize_token_account, first_player),
+        ),
+        GameState::SecondPlayerWon => (
+            (&accounts.second_player_prize_token_account, second_player),
+            (&accounts.second_player_token_account, second_player),
+        ),
+        _ => (
+            (&accounts.first_player_token_account, first_player),
+            (&accounts.second_player_token_account, second_player),
+        ),
+    };
+    nft_release.release(
+        &first_player_escrow,
+        &first_player_mint,
+        first_player_nft_to.0,
+        first_player_nft_to.1,
+        &payer,
+    )?;
+    nft_release.release(
+        &second_player_escrow,
+        &second_player_mint,
+        second_player_nft_to.0,
+        second_player_nft_to.1,
+        &payer,
+    )?;
+
+    // Draws are recorded like `claim` does, with the second player as the winner.
+    let (winner, loser) = match outcome {
+        GameState::FirstPlayerWon => (first_player.key(), second_player.key()),
+        _ => (second_player.key(), first_player.key()),
+    };
+    game.set_game_state(outcome);
+    game.set_claim_fields(winner, loser, 2, 0, clock.unix_timestamp);
+
+    let refunds = game.fee_refunds(clock.unix_timestamp, time_for_penalization);
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player,
+        Some(&ctx.accounts.second_player),
+        &mut ctx.accounts.fee_vault,
+    )?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    *ctx.accounts.game.load_mut()? = game;
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+use solana_program::pubkey;
+
+use crate::{
+    constants::{ESCROW, SEASON},
+    error::BPSError,
+    state::{Season, SeasonState},
+    utils::harvest_withheld_fees,
+};
+
+#[derive(Accounts)]
+pub struct ClaimSeasonReward<'info> {
+    #[account(
+        mut,
+        seeds = [SEASON.as_ref(), season.season_id.as_bytes()],
+        bump = season.bump,
+        constraint = season.state == SeasonState::Closed @ BPSError::SeasonNotOver,
+    )]
+    pub season: Box<Account<'info, Season>>,
+    #[account(
+        mut,
+        seeds = [season.key().as_ref(), ESCROW.as_ref()],
+        bump,
+    )]
+    pub season_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = player_token_account.mint == season.mint,
+        constraint = player_token_account.owner == player.key(),
+    )]
+    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        address = season.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Address check is enough, gets the vault rent back after the last reward.
+    #[account(
+        mut,
+        address = pubkey!("bpstzWLPDetyjiD33HPGGE96MzkhEA7dhRFzhc8Ay5R")
+    )]
+    pub bps_treasury: AccountInfo<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pays a ranked player its share of the reward pool, the last reward burns the rounding dust and
+/// closes the vault.
+pub fn claim_season_reward(ctx: Context<ClaimSeasonReward>) -> Result<()> {
+    let season = &mut ctx.accounts.season;
+    let season_vault = &mut ctx.accounts.season_vault;
+    let mint = &ctx.accounts.mint;
+    let rank = season
+        .leaderboard
+        .iter()
+        .position(|entry| entry.player == ctx.accounts.player.key())
+        .ok_or_else(|| error!(BPSError::NoReward))?;
+    let reward = season.reward_of(rank);
+    require!(
+        !season.leaderboard[rank].paid && reward > 0,
+        BPSError::NoReward
+    );
+    season.leaderboard[rank].paid = true;
+
+    let season_seeds = &[SEASON.as_ref(), season.season_id.as_bytes(), &[season.bump]];
+    let season_signer = &[&season_seeds[..]];
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: season_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: ctx.accounts.player_token_account.to_account_info(),
+                authority: season.to_account_info(),
+            },
+        )
+        .with_signer(season_signer),
+        reward,
+        mint.decimals,
+    )?;
+
+    if season.is_paid_out() {
+        season_vault.reload()?;
+        burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: mint.to_account_info(),
+                    from: season_vault.to_account_info(),
+                    authority: season.to_account_info(),
+                },
+            )
+            .with_signer(season_signer),
+            season_vault.amount,
+        )?;
+        harvest_withheld_fees(
+            &ctx.accounts.token_program.to_account_info(),
+            &mint.to_account_info(),
+            &[season_vault.to_account_info()],
+        )?;
+        close_account(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: season_vault.to_account_info(),
+                    destination: ctx.accounts.bps_treasury.to_account_info(),
+                    authority: season.to_account_info(),
+                },
+            )
+            .with_signer(season_signer),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::{ESCROW, SIDE_BET, SIDE_POOL},
+    error::BPSError,
+    state::{GameV3, SideBet, SidePool},
+};
+
+#[derive(Accounts)]
+pub struct ClaimSideBet<'info> {
+    /// CHECK: Deserialized by hand, the game is closed when it gets cancelled or unwound.
+    #[account(address = side_pool.game)]
+    pub game: AccountInfo<'info>,
+    #[account(
+        seeds = [
+            SIDE_POOL.as_ref(),
+            game.key().as_ref(),
+            side_pool.game_created_at.to_le_bytes().as_ref()
+        ],
+        bump = side_pool.bump,
+    )]
+    pub side_pool: Box<Account<'info, SidePool>>,
+    #[account(
+        mut,
+        seeds = [side_pool.key().as_ref(), ESCROW.as_ref()],
+        bump,
+    )]
+    pub side_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [SIDE_BET.as_ref(), side_pool.key().as_ref(), bettor.key().as_ref()],
+        bump = side_bet.bump,
+        has_one = side_pool,
+    )]
+    pub side_bet: Box<Account<'info, SideBet>>,
+    #[account(
+        mut,
+        constraint = bettor_token_account.mint == side_pool.mint,
+        constraint = bettor_token_account.owner == bettor.key(),
+    )]
+    pub bettor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = side_pool.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Settles a side bet once the game is claimed, refunding it if the game never got played.
+pub fn claim_side_bet(ctx: Context<ClaimSideBet>) -> Result<()> {
+    let game_info = &ctx.accounts.game;
+    let side_pool = &ctx.accounts.side_pool;
+    let side_pool_vault = &ctx.accounts.side_pool_vault;
+    let side_bet = &ctx.accounts.side_bet;
+    let bettor_token_account = &ctx.accounts.bettor_token_account;
+    let mint = &ctx.accounts.mint;
+
+    let game_was_closed = game_info.owner != &crate::ID || game_info.data_is_empty();
+    let amount_to_pay = if game_was_closed {
+        side_bet.amount
+    } else {
+        let game = GameV3::try_deserialize(&mut &game_info.data.borrow()[..])?;
+        if game.created_at != side_pool.game_created_at {
+            // The original game was cancelled and the id reused.
+            side_bet.amount
+        } else {
+            require!(game.is_settled(), BPSError::GameNotSettled);
+            if side_pool.total_for(&game.game_state()) == 0 {
+                // Nobody picked the right outcome, everyone gets their stake back.
+                side_bet.amount
+            } else if side_bet.outcome == game.game_state() {
+                side_pool.payout_for(&side_bet.outcome, side_bet.amount)
+            } else {
+                0
+            }
+        }
+    };
+
+    if amount_to_pay > 0 {
+        let game_created_at = side_pool.game_created_at.to_le_bytes();
+        let side_pool_seeds = &[
+            SIDE_POOL.as_ref(),
+            side_pool.game.as_ref(),
+            game_created_at.as_ref(),
+            &[side_pool.bump],
+        ];
+        let side_pool_signer = &[&side_pool_seeds[..]];
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: side_pool_vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: bettor_token_account.to_account_info(),
+                    authority: side_pool.to_account_info(),
+                },
+            )
+            .with_signer(side_pool_signer),
+            amount_to_pay,
+            mint.decimals,
+        )?;
+    }
+    Ok(())
+}
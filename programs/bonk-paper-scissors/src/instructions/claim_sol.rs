@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, FEE_VAULT, GAME, OPEN_GAMES, SOL_VAULT},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, GameState, GameV3, OpenGames, SolVault},
+    utils::{release_fees, transfer_lamports},
+};
+
+#[derive(Accounts)]
+pub struct ClaimSol<'info> {
+    #[account(
+        mut,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_native_sol() @ BPSError::NotNativeSolGame,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [SOL_VAULT.as_ref(), game.key().as_ref()],
+        bump = sol_vault.bump,
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.first_player
+    )]
+    pub first_player: AccountInfo<'info>,
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.second_player().unwrap()
+    )]
+    pub second_player: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settles a native SOL game. The 10% that token games burn goes to the fee vault instead, the
+/// vault rent is the reward for whoever cranks the claim.
+pub fn claim_sol(ctx: Context<ClaimSol>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    let sol_vault = &ctx.accounts.sol_vault.to_account_info();
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let time_for_penalization =
+        game.time_for_penalization_or(bps_settings_v3.timers.time_for_penalization);
+    let first_player = &ctx.accounts.first_player;
+    let second_player = &ctx.accounts.second_player;
+
+    require!(
+        game.game_state() == GameState::StartedAndWaitingForReveal,
+        BPSError::InvalidGameState
+    );
+    require!(
+        game.is_claimable(clock.unix_timestamp, time_for_penalization),
+        BPSError::InvalidGameState
+    );
+
+    let outcome = game.outcome(clock.unix_timestamp, time_for_penalization);
+
+    let (amount_to_pay, amount_to_treasury) = game.payout_split();
+
+    if outcome == GameState::Draw {
+        transfer_lamports(sol_vault, first_player, game.amount_to_match)?;
+        transfer_lamports(sol_vault, second_player, game.amount_to_match)?;
+        game.set_game_state(GameState::Draw);
+        game.set_claim_fields(
+            second_player.key(),
+            first_player.key(),
+            amount_to_pay * 2,
+            amount_to_treasury * 2,
+            clock.unix_timestamp,
+        );
+    } else {
+        let (winner, loser) = if outcome == GameState::FirstPlayerWon {
+            (first_player, second_player)
+        } else {
+            (second_player, first_player)
+        };
+        transfer_lamports(sol_vault, winner, amount_to_pay * 2)?;
+        transfer_lamports(
+            sol_vault,
+            &ctx.accounts.fee_vault.to_account_info(),
+            amount_to_treasury * 2,
+        )?;
+        ctx.accounts.fee_vault.collect(amount_to_treasury * 2);
+        game.set_game_state(outcome);
+        game.set_claim_fields(
+            winner.key(),
+            loser.key(),
+            amount_to_pay * 2,
+            amount_to_treasury * 2,
+            clock.unix_timestamp,
+        );
+    }
+
+    let refunds = game.fee_refunds(clock.unix_timestamp, time_for_penalization);
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player,
+        Some(&ctx.accounts.second_player),
+        &mut ctx.accounts.fee_vault,
+    )?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    *ctx.accounts.game.load_mut()? = game;
+    Ok(())
+}
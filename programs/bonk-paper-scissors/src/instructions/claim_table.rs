@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::{
+    constants::TABLE,
+    error::BPSError,
+    state::{Table, TableState},
+    utils::harvest_withheld_fees,
+};
+
+#[derive(Accounts)]
+pub struct ClaimTable<'info> {
+    #[account(
+        mut,
+        seeds = [TABLE.as_ref(), table.creator.as_ref(), table.table_id.as_bytes()],
+        bump = table.bump,
+        constraint = table.state == TableState::Settled @ BPSError::InvalidGameState,
+    )]
+    pub table: Box<Account<'info, Table>>,
+    #[account(
+        mut,
+        address = table.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    // Gets the escrow rent for cranking the claim.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: every seat's escrow in seat order, then the token account of every
+    // survivor in seat order.
+}
+
+/// Splits the pot of a settled table between its survivors, burning 10% like a regular game.
+pub fn claim_table<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ClaimTable<'info>>,
+) -> Result<()> {
+    let table = &ctx.accounts.table;
+    let mint = &ctx.accounts.mint;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let table_info = table.to_account_info();
+    let seats = table.seats.len();
+    let survivors: Vec<Pubkey> = table.survivors().map(|seat| seat.player).collect();
+    require!(
+        ctx.remaining_accounts.len() == seats + survivors.len(),
+        BPSError::InvalidSeatAccounts
+    );
+    let (escrow_infos, prize_infos) = ctx.remaining_accounts.split_at(seats);
+    for (seat, escrow_info) in table.seats.iter().zip(escrow_infos) {
+        require_keys_eq!(
+            seat.escrow_address,
+            escrow_info.key(),
+            BPSError::InvalidSeatAccounts
+        );
+    }
+    for (player, prize_info) in survivors.iter().zip(prize_infos) {
+        let prize_token_account = InterfaceAccount::<TokenAccount>::try_from(prize_info)?;
+        require!(
+            prize_token_account.owner == *player && prize_token_account.mint == table.mint,
+            BPSError::InvalidSeatAccounts
+        );
+    }
+
+    let table_seeds = &[
+        TABLE.as_ref(),
+        table.creator.as_ref(),
+        table.table_id.as_bytes(),
+        &[table.bump],
+    ];
+    let table_signer = &[&table_seeds[..]];
+    let transfer = |from: &AccountInfo<'info>, to: &AccountInfo<'info>, amount: u64| {
+        transfer_checked(
+            CpiContext::new(
+                token_program.clone(),
+                TransferChecked {
+                    from: from.clone(),
+                    mint: mint.to_account_info(),
+                    to: to.clone(),
+                    authority: table_info.clone(),
+                },
+            )
+            .with_signer(table_signer),
+            amount,
+            mint.decimals,
+        )
+    };
+
+    // Gather the pot in the first escrow, transfer-fee mints withhold part of every move.
+    let pot_info = &escrow_infos[0];
+    for escrow_info in &escrow_infos[1..] {
+        let escrow = InterfaceAccount::<TokenAccount>::try_from(escrow_info)?;
+        transfer(escrow_info, pot_info, escrow.amount)?;
+    }
+    let pot = InterfaceAccount::<TokenAccount>::try_from(pot_info)?.amount;
+    let amount_to_pay = pot - pot / 10;
+    let payout_per_survivor = amount_to_pay / survivors.len() as u64;
+    // Burn the remainder so no dust is left behind and the escrows can be closed.
+    let amount_to_burn = pot - payout_per_survivor * survivors.len() as u64;
+
+    for prize_info in prize_infos {
+        transfer(pot_info, prize_info, payout_per_survivor)?;
+    }
+    burn(
+        CpiContext::new(
+            token_program.clone(),
+            Burn {
+                mint: mint.to_account_info(),
+                from: pot_info.clone(),
+                authority: table_info.clone(),
+            },
+        )
+        .with_signer(table_signer),
+        amount_to_burn,
+    )?;
+
+    // ----- Close the escrows, their rent is the reward for whoever cranks the claim -----
+    harvest_withheld_fees(&token_program, &mint.to_account_info(), escrow_infos)?;
+    for escrow_info in escrow_infos {
+        close_account(
+            CpiContext::new(
+                token_program.clone(),
+                CloseAccount {
+                    account: escrow_info.clone(),
+                    destination: ctx.accounts.payer.to_account_info(),
+                    authority: table_info.clone(),
+                },
+            )
+            .with_signer(table_signer),
+        )?;
+    }
+    // ----- Close the escrows, their rent is the reward for whoever cranks the claim -----
+
+    let table = &mut ctx.accounts.table;
+    table.state = TableState::Claimed;
+    table.payout_per_survivor = payout_per_survivor;
+    table.amount_burned = amount_to_burn;
+    Ok(())
+}
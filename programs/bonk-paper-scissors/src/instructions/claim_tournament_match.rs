@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, GAME, TOURNAMENT},
+    error::BPSError,
+    state::{BpsSettingsV3, GameState, GameV3, Tournament},
+};
+
+#[derive(Accounts)]
+pub struct ClaimTournamentMatch<'info> {
+    #[account(
+        mut,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_tournament() @ BPSError::NotTournamentGame,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [TOURNAMENT.as_ref(), tournament.organizer.as_ref(), tournament.tournament_id.as_bytes()],
+        bump = tournament.bump,
+        address = game.load()?.tournament,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+}
+
+/// Settles a bracket game and advances its winner, a draw is replayed with a new game.
+pub fn claim_tournament_match(ctx: Context<ClaimTournamentMatch>) -> Result<()> {
+    let clock = Clock::get()?;
+    let time_for_penalization = ctx.accounts.bps_settings_v3.timers.time_for_penalization;
+    let tournament = &mut ctx.accounts.tournament;
+    let mut game = *ctx.accounts.game.load()?;
+    require!(
+        game.is_claimable(clock.unix_timestamp, time_for_penalization),
+        BPSError::InvalidGameState
+    );
+    let index = tournament
+        .match_index(&ctx.accounts.game.key())
+        .ok_or_else(|| error!(BPSError::NotTournamentGame))?;
+    require!(
+        tournament.matches[index].winner.is_none(),
+        BPSError::MatchDecided
+    );
+    // Only set once the second player joined, which `is_claimable` implies.
+    let second_player = game
+        .second_player()
+        .ok_or_else(|| error!(BPSError::InvalidGameState))?;
+
+    let outcome = game.outcome(clock.unix_timestamp, time_for_penalization);
+    game.set_game_state(outcome);
+    match outcome {
+        GameState::FirstPlayerWon => {
+            game.set_claim_fields(game.first_player, second_player, 0, 0, 0);
+            tournament.record_winner(
+                index,
+                game.first_player,
+                clock.unix_timestamp,
+                time_for_penalization,
+            );
+        }
+        GameState::SecondPlayerWon => {
+            game.set_claim_fields(second_player, game.first_player, 0, 0, 0);
+            tournament.record_winner(
+                index,
+                second_player,
+                clock.unix_timestamp,
+                time_for_penalization,
+            );
+        }
+        _ => {
+            game.set_claim_fields(second_player, game.first_player, 0, 0, clock.unix_timestamp);
+            tournament.replay_match(index, clock.unix_timestamp, time_for_penalization);
+        }
+    }
+
+    *ctx.accounts.game.load_mut()? = game;
+    Ok(())
+}
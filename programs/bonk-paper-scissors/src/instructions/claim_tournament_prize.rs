@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::{
+    constants::{ESCROW, TOURNAMENT},
+    error::BPSError,
+    state::{Tournament, TournamentState},
+    utils::harvest_withheld_fees,
+};
+
+#[derive(Accounts)]
+pub struct ClaimTournamentPrize<'info> {
+    #[account(
+        mut,
+        seeds = [TOURNAMENT.as_ref(), tournament.organizer.as_ref(), tournament.tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = matches!(tournament.state, TournamentState::Finished | TournamentState::Cancelled) @ BPSError::InvalidGameState,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        mut,
+        seeds = [tournament.key().as_ref(), ESCROW.as_ref()],
+        bump,
+    )]
+    pub tournament_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = entrant_token_account.mint == tournament.mint,
+        constraint = entrant_token_account.owner == entrant.key(),
+    )]
+    pub entrant_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Must be registered, the prize goes to its token account.
+    pub entrant: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = tournament.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Address check is enough, gets the escrow rent back after the last prize.
+    #[account(
+        mut,
+        address = tournament.organizer,
+    )]
+    pub organizer: AccountInfo<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pays an entrant its share of the prize pool, or refunds its entry fee when the tournament was
+/// cancelled. Anyone can pay it out, the last payout burns the rounding dust and closes the escrow.
+pub fn claim_tournament_prize(ctx: Context<ClaimTournamentPrize>) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let tournament_escrow = &mut ctx.accounts.tournament_escrow;
+    let mint = &ctx.accounts.mint;
+    let index = tournament
+        .entrant_index(&ctx.accounts.entrant.key())
+        .ok_or_else(|| error!(BPSError::InvalidPlayer))?;
+    let prize = tournament.prize_of(index);
+    require!(
+        !tournament.entrants[index].paid && prize > 0,
+        BPSError::NoPrize
+    );
+    tournament.entrants[index].paid = true;
+
+    let organizer_key = tournament.organizer;
+    let tournament_seeds = &[
+        TOURNAMENT.as_ref(),
+        organizer_key.as_ref(),
+        tournament.tournament_id.as_bytes(),
+        &[tournament.bump],
+    ];
+    let tournament_signer = &[&tournament_seeds[..]];
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: tournament_escrow.to_account_info(),
+                mint: mint.to_account_info(),
+                to: ctx.accounts.entrant_token_account.to_account_info(),
+                authority: tournament.to_account_info(),
+            },
+        )
+        .with_signer(tournament_signer),
+        prize,
+        mint.decimals,
+    )?;
+
+    if tournament.is_paid_out() {
+        tournament_escrow.reload()?;
+        burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: mint.to_account_info(),
+                    from: tournament_escrow.to_account_info(),
+                    authority: tournament.to_account_info(),
+                },
+            )
+            .with_signer(tournament_signer),
+            tournament_escrow.amount,
+        )?;
+        harvest_withheld_fees(
+            &ctx.accounts.token_program.to_account_info(),
+            &mint.to_account_info(),
+            &[tournament_escrow.to_account_info()],
+        )?;
+        close_account(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: tournament_escrow.to_account_info(),
+                    destination: ctx.accounts.organizer.to_account_info(),
+                    authority: tournament.to_account_info(),
+                },
+            )
+            .with_signer(tournament_signer),
+        )?;
+    }
+    Ok(())
+}
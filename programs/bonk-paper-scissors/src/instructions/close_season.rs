@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::{
+    constants::{ADMIN, ESCROW, SEASON},
+    error::BPSError,
+    state::{Season, SeasonState},
+    utils::harvest_withheld_fees,
+};
+
+#[derive(Accounts)]
+pub struct CloseSeason<'info> {
+    #[account(
+        mut,
+        seeds = [SEASON.as_ref(), season.season_id.as_bytes()],
+        bump = season.bump,
+        constraint = season.state == SeasonState::Active @ BPSError::SeasonNotActive,
+    )]
+    pub season: Box<Account<'info, Season>>,
+    #[account(
+        mut,
+        seeds = [season.key().as_ref(), ESCROW.as_ref()],
+        bump,
+    )]
+    pub season_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Gets the vault back when nobody won a game this season.
+    #[account(
+        mut,
+        constraint = refund_token_account.mint == season.mint,
+    )]
+    pub refund_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        address = season.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Freezes the leaderboard and the reward pool once the season ended.
+pub fn close_season(ctx: Context<CloseSeason>) -> Result<()> {
+    let clock = Clock::get()?;
+    let season = &mut ctx.accounts.season;
+    let season_vault = &ctx.accounts.season_vault;
+    let mint = &ctx.accounts.mint;
+    require!(
+        clock.unix_timestamp > season.ends_at,
+        BPSError::SeasonNotOver
+    );
+    season.state = SeasonState::Closed;
+    season.reward_pool = season_vault.amount;
+    if !season.leaderboard.is_empty() {
+        return Ok(());
+    }
+
+    let refund_token_account = ctx
+        .accounts
+        .refund_token_account
+        .as_ref()
+        .ok_or_else(|| error!(BPSError::NoReward))?;
+    let season_seeds = &[SEASON.as_ref(), season.season_id.as_bytes(), &[season.bump]];
+    let season_signer = &[&season_seeds[..]];
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: season_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: refund_token_account.to_account_info(),
+                authority: season.to_account_info(),
+            },
+        )
+        .with_signer(season_signer),
+        season_vault.amount,
+        mint.decimals,
+    )?;
+    harvest_withheld_fees(
+        &ctx.accounts.token_program.to_account_info(),
+        &mint.to_account_info(),
+        &[season_vault.to_account_info()],
+    )?;
+    close_account(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: season_vault.to_account_info(),
+                destination: ctx.accounts.signer.to_account_info(),
+                authority: season.to_account_info(),
+            },
+        )
+        .with_signer(season_signer),
+    )?;
+    season.reward_pool = 0;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, TABLE},
+    error::BPSError,
+    state::{BpsSettingsV3, Table, TableState},
+};
+
+#[derive(Accounts)]
+pub struct CommitTableMove<'info> {
+    #[account(
+        mut,
+        seeds = [TABLE.as_ref(), table.creator.as_ref(), table.table_id.as_bytes()],
+        bump = table.bump,
+        constraint = table.state == TableState::Committing @ BPSError::InvalidGameState,
+    )]
+    pub table: Box<Account<'info, Table>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    pub player: Signer<'info>,
+}
+
+/// Commits the player's hash (choice + salt) for the current round.
+pub fn commit_table_move(ctx: Context<CommitTableMove>, player_hash: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let table = &mut ctx.accounts.table;
+    let index = table
+        .seat_index(&ctx.accounts.player.key())
+        .ok_or_else(|| error!(BPSError::InvalidPlayer))?;
+    let seat = &mut table.seats[index];
+    require!(!seat.eliminated, BPSError::PlayerEliminated);
+    require!(seat.hash.is_none(), BPSError::PlayerAlreadyMoved);
+    seat.hash = Some(player_hash);
+
+    table.advance(
+        clock.unix_timestamp,
+        ctx.accounts.bps_settings_v3.timers.time_for_penalization,
+    );
+    Ok(())
+}
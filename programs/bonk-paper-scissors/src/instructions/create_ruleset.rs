@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, RULESET},
+    error::BPSError,
+    state::{Ruleset, MAX_CHOICES},
+};
+
+#[derive(Accounts)]
+#[instruction(ruleset_id: String)]
+pub struct CreateRuleset<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = Ruleset::size(),
+        seeds = [RULESET.as_ref(), ruleset_id.as_bytes()],
+        bump
+    )]
+    pub ruleset: Account<'info, Ruleset>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Rulesets can't be changed once created, games copy theirs when they are created anyway.
+pub fn create_ruleset(
+    ctx: Context<CreateRuleset>,
+    ruleset_id: String,
+    choice_count: u8,
+    beats: Vec<u8>,
+) -> Result<()> {
+    require!(
+        Ruleset::is_valid(choice_count, &beats),
+        BPSError::InvalidRuleset
+    );
+    let mut matrix = [0u8; MAX_CHOICES as usize];
+    matrix[..beats.len()].copy_from_slice(&beats);
+    let bump = ctx.bumps.ruleset;
+    ctx.accounts
+        .ruleset
+        .set_inner(Ruleset::new(bump, ruleset_id, choice_count, matrix));
+    Ok(())
+}
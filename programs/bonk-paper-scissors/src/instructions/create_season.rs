@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{ADMIN, ESCROW, SEASON},
+    error::BPSError,
+    state::{Season, MAX_LEADERBOARD_LEN, PRIZE_SPLIT_TOTAL},
+};
+
+#[derive(Accounts)]
+#[instruction(season_id: String)]
+pub struct CreateSeason<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = Season::size(),
+        seeds = [SEASON.as_ref(), season_id.as_bytes()],
+        bump
+    )]
+    pub season: Box<Account<'info, Season>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = season,
+        seeds = [season.key().as_ref(), ESCROW.as_ref()],
+        bump,
+        payer = signer
+    )]
+    pub season_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_season(
+    ctx: Context<CreateSeason>,
+    season_id: String,
+    starts_at: i64,
+    ends_at: i64,
+    stake_per_point: u64,
+    reward_split: Vec<u16>,
+) -> Result<()> {
+    require!(
+        starts_at < ends_at && stake_per_point > 0,
+        BPSError::InvalidSeason
+    );
+    require!(
+        !reward_split.is_empty()
+            && reward_split.len() <= MAX_LEADERBOARD_LEN
+            && reward_split.iter().map(|&share| share as u32).sum::<u32>()
+                == PRIZE_SPLIT_TOTAL as u32,
+        BPSError::InvalidRewardSplit
+    );
+
+    ctx.accounts.season.set_inner(Season::new(
+        ctx.bumps.season,
+        season_id,
+        ctx.accounts.mint.key(),
+        starts_at,
+        ends_at,
+        stake_per_point,
+        reward_split,
+    ));
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+
+use crate::{constants::SESSION_TOKEN, error::BPSError, state::SessionToken};
+
+#[derive(Accounts)]
+// Named apart from the `session_signer` account, which would shadow it.
+#[instruction(session_signer_key: Pubkey)]
+pub struct CreateSession<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = SessionToken::size(),
+        seeds = [
+            SESSION_TOKEN.as_ref(),
+            authority.key().as_ref(),
+            session_signer_key.as_ref()
+        ],
+        bump
+    )]
+    pub session_token: Account<'info, SessionToken>,
+    /// CHECK: Address check is enough, it only receives the top up.
+    #[account(
+        mut,
+        address = session_signer_key
+    )]
+    pub session_signer: AccountInfo<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Authorizes `session_signer` to act for the authority until `valid_until`, on games staking
+/// up to `max_stake`. `top_up_lamports` are sent to the session signer to pay for its fees.
+pub fn create_session(
+    ctx: Context<CreateSession>,
+    session_signer: Pubkey,
+    valid_until: i64,
+    max_stake: u64,
+    top_up_lamports: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let session_token = &mut ctx.accounts.session_token;
+    let authority = &ctx.accounts.authority;
+    let bump = ctx.bumps.session_token;
+    require!(valid_until > clock.unix_timestamp, BPSError::SessionExpired);
+
+    if top_up_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(&authority.key(), &session_signer, top_up_lamports),
+            &[
+                authority.to_account_info(),
+                ctx.accounts.session_signer.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    session_token.set_inner(SessionToken::new(
+        bump,
+        authority.key(),
+        session_signer,
+        valid_until,
+        max_stake,
+        clock.unix_timestamp,
+    ));
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, ESCROW, FEE_VAULT, STAKE_LIMITS, TABLE},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, StakeLimits, Table, MAX_SEATS, MIN_SEATS},
+    utils::require_supported_mint,
+};
+
+#[derive(Accounts)]
+#[instruction(table_id: String, max_seats: u8, max_rounds: u8, stake: u64)]
+pub struct CreateTable<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = Table::size(),
+        seeds = [TABLE.as_ref(), creator.key().as_ref(), table_id.as_bytes()],
+        bump
+    )]
+    pub table: Box<Account<'info, Table>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = table,
+        seeds = [table.key().as_ref(), ESCROW.as_ref(), creator.key().as_ref()],
+        bump,
+        payer = creator
+    )]
+    pub creator_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == mint.key(),
+        constraint = creator_token_account.owner == creator.key(),
+        constraint = creator_token_account.amount >= stake @ BPSError::AmountExceedsBalance
+    )]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Limits the admin set for the mint, read by `StakeLimits::enforce`.
+    #[account(
+        seeds = [STAKE_LIMITS.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub stake_limits: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a table for `max_seats` players and takes its first seat. Each seat escrows `stake`
+/// and pays the player fee, the first round starts when the table is full.
+pub fn create_table(
+    ctx: Context<CreateTable>,
+    table_id: String,
+    max_seats: u8,
+    max_rounds: u8,
+    stake: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let creator = &ctx.accounts.creator;
+    let creator_escrow = &mut ctx.accounts.creator_escrow;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let mint = &ctx.accounts.mint;
+    require!(
+        (MIN_SEATS..=MAX_SEATS).contains(&max_seats),
+        BPSError::InvalidSeatCount
+    );
+    require!(max_rounds > 0, BPSError::InvalidGameState);
+    StakeLimits::enforce(&ctx.accounts.stake_limits, stake)?;
+    require_supported_mint(&ctx.accounts.mint.to_account_info())?;
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.creator_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: creator_escrow.to_account_info(),
+                authority: creator.to_account_info(),
+            },
+        ),
+        stake,
+        mint.decimals,
+    )?;
+    // Like games, the stake is what actually reached the escrow.
+    creator_escrow.reload()?;
+    // Pay the fee into the fee vault.
+    invoke(
+        &system_instruction::transfer(
+            &creator.key(),
+            &ctx.accounts.fee_vault.key(),
+            bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            creator.to_account_info(),
+            ctx.accounts.fee_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    ctx.accounts
+        .fee_vault
+        .collect(bps_settings_v3.fees.player_fee_lamports);
+
+    let mut table = Table::new(
+        ctx.bumps.table,
+        table_id,
+        creator.key(),
+        mint.key(),
+        creator_escrow.amount,
+        max_seats,
+        max_rounds,
+        clock.unix_timestamp,
+    );
+    table.add_seat(creator.key(), creator_escrow.key());
+    ctx.accounts.table.set_inner(table);
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{ESCROW, TOURNAMENT},
+    error::BPSError,
+    state::{Tournament, MAX_ENTRANTS, MAX_PRIZE_PLACES, MIN_ENTRANTS, PRIZE_SPLIT_TOTAL},
+};
+
+#[derive(Accounts)]
+#[instruction(tournament_id: String)]
+pub struct CreateTournament<'info> {
+    #[account(
+        init,
+        payer = organizer,
+        space = Tournament::size(),
+        seeds = [TOURNAMENT.as_ref(), organizer.key().as_ref(), tournament_id.as_bytes()],
+        bump
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = tournament,
+        seeds = [tournament.key().as_ref(), ESCROW.as_ref()],
+        bump,
+        payer = organizer
+    )]
+    pub tournament_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens registration until `registration_ends_at`, entrants pay `entry_fee` into the prize pool.
+pub fn create_tournament(
+    ctx: Context<CreateTournament>,
+    tournament_id: String,
+    entry_fee: u64,
+    max_entrants: u8,
+    registration_ends_at: i64,
+    prize_split: Vec<u16>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        (MIN_ENTRANTS..=MAX_ENTRANTS).contains(&max_entrants),
+        BPSError::InvalidEntrantCount
+    );
+    require!(
+        !prize_split.is_empty()
+            && prize_split.len() <= MAX_PRIZE_PLACES.min(max_entrants as usize)
+            && prize_split.iter().map(|&share| share as u32).sum::<u32>()
+                == PRIZE_SPLIT_TOTAL as u32,
+        BPSError::InvalidPrizeSplit
+    );
+    require!(
+        registration_ends_at > clock.unix_timestamp,
+        BPSError::RegistrationClosed
+    );
+
+    ctx.accounts.tournament.set_inner(Tournament::new(
+        ctx.bumps.tournament,
+        tournament_id,
+        ctx.accounts.organizer.key(),
+        ctx.accounts.mint.key(),
+        entry_fee,
+        max_entrants,
+        registration_ends_at,
+        prize_split,
+    ));
+    Ok(())
+}
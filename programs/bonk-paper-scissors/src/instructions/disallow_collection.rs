@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, ALLOWED_COLLECTION},
+    state::AllowedCollection,
+};
+
+#[derive(Accounts)]
+pub struct DisallowCollection<'info> {
+    #[account(
+        mut,
+        close = signer,
+        seeds = [ALLOWED_COLLECTION.as_ref(), allowed_collection.collection.as_ref()],
+        bump = allowed_collection.bump
+    )]
+    pub allowed_collection: Account<'info, AllowedCollection>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+}
+
+/// Games already created with the collection can still be played and claimed.
+pub fn disallow_collection(_ctx: Context<DisallowCollection>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, FEE_VAULT, GAME, OPEN_GAMES},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, GameV3, OpenGames},
+    utils::{release_fees, NftRelease},
+};
+
+#[derive(Accounts)]
+pub struct ExpireNftGame<'info> {
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_nft() @ BPSError::NotNftGame,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+
+    #[account(
+        mut,
+        address = game.load()?.first_player_escrow_address,
+    )]
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub first_player_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub first_player_token_account: AccountInfo<'info>,
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.first_player,
+    )]
+    pub first_player: AccountInfo<'info>,
+
+    // Only when the game was joined, the handler checks the addresses.
+    #[account(mut)]
+    pub second_player_escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub second_player_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// CHECK: Validated by the associated token program, created if missing.
+    #[account(mut)]
+    pub second_player_token_account: Option<AccountInfo<'info>>,
+    /// CHECK: Checked by the handler.
+    pub second_player: Option<AccountInfo<'info>>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can return the NFTs of a game nobody joined, or nobody revealed, in time. The escrow
+/// rent is the reward.
+pub fn expire_nft_game(ctx: Context<ExpireNftGame>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    let time_for_penalization =
+        game.time_for_penalization_or(ctx.accounts.bps_settings_v3.timers.time_for_penalization);
+    require!(
+        game.is_expired(clock.unix_timestamp, time_for_penalization),
+        BPSError::GameNotExpired
+    );
+    let refunds = game.fee_refunds(clock.unix_timestamp, time_for_penalization);
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player,
+        ctx.accounts.second_player.as_ref(),
+        &mut ctx.accounts.fee_vault,
+    )?;
+    let accounts = &ctx.accounts;
+    let game_seeds = &[
+        b"game",
+        game.first_player.as_ref(),
+        game.game_id(),
+        &[game.bump],
+    ];
+    let payer = accounts.payer.to_account_info();
+    let nft_release = NftRelease {
+        game: accounts.game.to_account_info(),
+        game_signer: &[&game_seeds[..]],
+        payer: payer.clone(),
+        token_program: accounts.token_program.to_account_info(),
+        associated_token_program: accounts.associated_token_program.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+    };
+
+    nft_release.release(
+        &accounts.first_player_escrow.to_account_info(),
+        &accounts.first_player_mint.to_account_info(),
+        &accounts.first_player_token_account,
+        &accounts.first_player,
+        &payer,
+    )?;
+    if let (Some(second_player_key), Some(second_player_escrow_key), Some(second_player_mint_key)) = (
+        game.second_player(),
+        game.second_player_escrow_address(),
+        game.second_player_mint(),
+    ) {
+        let missing = || error!(BPSError::InvalidPlayer);
+        let second_player = accounts
+            .second_player
+            .as_ref()
+            .filter(|second_player| second_player.key() == second_player_key)
+            .ok_or_else(missing)?;
+        let second_player_escrow = accounts
+            .second_player_escrow
+            .as_ref()
+            .filter(|escrow| escrow.key() == second_player_escrow_key)
+            .ok_or_else(missing)?;
+        let second_player_mint = accounts
+            .second_player_mint
+            .as_ref()
+            .filter(|mint| mint.key() == second_player_mint_key)
+            .ok_or_else(missing)?;
+        let second_player_token_account = accounts
+            .second_player_token_account
+            .as_ref()
+            .ok_or_else(missing)?;
+        nft_release.release(
+            &second_player_escrow.to_account_info(),
+            &second_player_mint.to_account_info(),
+            second_player_token_account,
+            second_player,
+            &payer,
+        )?;
+    }
+    Ok(())
+}
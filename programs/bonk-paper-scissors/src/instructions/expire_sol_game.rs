@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, FEE_VAULT, GAME, OPEN_GAMES, SOL_VAULT},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, GameV3, OpenGames, SolVault},
+    utils::{release_fees, transfer_lamports},
+};
+
+#[derive(Accounts)]
+pub struct ExpireSolGame<'info> {
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_native_sol() @ BPSError::NotNativeSolGame,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [SOL_VAULT.as_ref(), game.key().as_ref()],
+        bump = sol_vault.bump,
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = game.load()?.first_player,
+    )]
+    pub first_player: AccountInfo<'info>,
+    /// CHECK: Only read when the game was joined, the handler checks the address.
+    #[account(mut)]
+    pub second_player: Option<AccountInfo<'info>>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can refund the stakes of a native SOL game nobody joined, or nobody revealed, in
+/// time. The vault rent is the reward.
+pub fn expire_sol_game(ctx: Context<ExpireSolGame>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    let sol_vault = &ctx.accounts.sol_vault.to_account_info();
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let time_for_penalization =
+        game.time_for_penalization_or(bps_settings_v3.timers.time_for_penalization);
+    require!(
+        game.is_expired(clock.unix_timestamp, time_for_penalization),
+        BPSError::GameNotExpired
+    );
+    let refunds = game.fee_refunds(clock.unix_timestamp, time_for_penalization);
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player,
+        ctx.accounts.second_player.as_ref(),
+        &mut ctx.accounts.fee_vault,
+    )?;
+
+    transfer_lamports(sol_vault, &ctx.accounts.first_player, game.amount_to_match)?;
+    if let Some(second_player_key) = game.second_player() {
+        let second_player = ctx
+            .accounts
+            .second_player
+            .as_ref()
+            .filter(|second_player| second_player.key() == second_player_key)
+            .ok_or_else(|| error!(BPSError::InvalidPlayer))?;
+        transfer_lamports(sol_vault, second_player, game.amount_to_match)?;
+    }
+    Ok(())
+}
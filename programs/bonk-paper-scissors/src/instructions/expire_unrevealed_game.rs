@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::{
+    constants::{
+        BPS_SETTINGS_V3, ESCROW, FEE_VAULT, FIRST_PLAYER, GAME, OPEN_GAMES, SECOND_PLAYER,
+    },
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, GameState, GameV3, OpenGames},
+    utils::{harvest_withheld_fees, release_fees},
+};
+
+#[derive(Accounts)]
+pub struct ExpireUnrevealedGame<'info> {
+    #[account(
+        mut,
+        close = first_player,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.game_state() == GameState::StartedAndWaitingForReveal @ BPSError::InvalidGameState,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.first_player.as_ref()],
+        bump = first_player_open_games.bump,
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        mut,
+        seeds = [OPEN_GAMES.as_ref(), game.load()?.second_player.as_ref()],
+        bump = second_player_open_games.bump,
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+
+    #[account(
+        mut,
+        token::mint = game.load()?.mint,
+        token::authority = game,
+        seeds = [
+            game.key().as_ref(),
+            ESCROW.as_ref(),
+            FIRST_PLAYER.as_ref(),
+        ],
+        bump,
+    )]
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = first_player_token_account.mint == game.load()?.mint,
+        constraint = first_player_token_account.owner == first_player.key(),
+    )]
+    pub first_player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = game.load()?.mint,
+        token::authority = game,
+        seeds = [
+            game.key().as_ref(),
+            ESCROW.as_ref(),
+            SECOND_PLAYER.as_ref(),
+        ],
+        bump,
+    )]
+    pub second_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = second_player_token_account.mint == game.load()?.mint,
+        constraint = second_player_token_account.owner == second_player.key(),
+    )]
+    pub second_player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: No use to check this. (Checked by CPI)
+    #[account(
+        mut,
+        address = game.load()?.first_player,
+    )]
+    pub first_player: AccountInfo<'info>,
+    /// CHECK: No use to check this. (Checked by CPI)
+    #[account(address = game.load()?.second_player().unwrap())]
+    pub second_player: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can refund both players of a game nobody revealed in time, the escrow rent is the
+/// reward. This is the permissionless version of `admin_unwind_stale_game`.
+pub fn expire_unrevealed_game(ctx: Context<ExpireUnrevealedGame>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = *ctx.accounts.game.load()?;
+    game.release_open_game_slots(
+        ctx.accounts.first_player_open_games.as_deref_mut(),
+        ctx.accounts.second_player_open_games.as_deref_mut(),
+    )?;
+    let game_info = ctx.accounts.game.to_account_info();
+    let first_player_escrow = &ctx.accounts.first_player_escrow;
+    let first_player_token_account = &ctx.accounts.first_player_token_account;
+    let second_player_escrow = &ctx.accounts.second_player_escrow;
+    let second_player_token_account = &ctx.accounts.second_player_token_account;
+    let mint = &ctx.accounts.mint;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let time_for_penalization =
+        game.time_for_penalization_or(bps_settings_v3.timers.time_for_penalization);
+    let payer = &ctx.accounts.payer;
+    require!(
+        game.is_expired(clock.unix_timestamp, time_for_penalization),
+        BPSError::GameNotExpired
+    );
+    let refunds = game.fee_refunds(clock.unix_timestamp, time_for_penalization);
+    release_fees(
+        &mut game,
+        &ctx.accounts.game.to_account_info(),
+        refunds,
+        &ctx.accounts.first_player,
+        Some(&ctx.accounts.second_player),
+        &mut ctx.accounts.fee_vault,
+    )?;
+    let game_seeds = &[
+        b"game",
+        game.first_player.as_ref(),
+        game.game_id(),
+        &[game.bump],
+    ];
+    let game_signer = &[&game_seeds[..]];
+
+    for (escrow, token_account) in [
+        (first_player_escrow, first_player_token_account),
+        (second_player_escrow, second_player_token_account),
+    ] {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: escrow.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: token_account.to_account_info(),
+                    authority: game_info.clone(),
+                },
+            )
+            .with_signer(game_signer),
+            game.amount_to_match,
+            mint.decimals,
+        )?;
+        harvest_withheld_fees(
+            &ctx.accounts.token_program.to_account_info(),
+            &mint.to_account_info(),
+            &[escrow.to_account_info()],
+        )?;
+        close_account(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: escrow.to_account_info(),
+                    destination: payer.to_account_info(),
+                    authority: game_info.clone(),
+                },
+            )
+            .with_signer(game_signer),
+        )?;
+    }
+    Ok(())
+}
@@ -4,14 +4,14 @@ use anchor_lang::{
 };
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use solana_program::pubkey;
 
 use crate::{
-    constants::{BPS_SETTINGS_V2, ESCROW, FIRST_PLAYER, GAME},
+    constants::{BPS_SETTINGS_V3, ESCROW, FIRST_PLAYER, GAME, OPEN_GAMES, RULESET, STAKE_LIMITS},
     error::BPSError,
-    state::{BpsSettingsV2, Game},
+    state::{BpsSettingsV3, GameV3, OpenGames, Ruleset, StakeLimits, FIRST_PLAYER_SLOT},
+    utils::require_supported_mint,
 };
 
 #[derive(Accounts)]
@@ -20,7 +20,7 @@ pub struct FirstPlayerMove<'info> {
     #[account(
         init,
         payer = first_player,
-        space = Game::size(),
+        space = GameV3::size(),
         seeds = [
             GAME.as_ref(),
             first_player.key().as_ref(),
@@ -28,7 +28,7 @@ pub struct FirstPlayerMove<'info> {
         ],
         bump
     )]
-    pub game: Account<'info, Game>,
+    pub game: AccountLoader<'info, GameV3>,
 
     #[account(
         init,
@@ -42,36 +42,47 @@ pub struct FirstPlayerMove<'info> {
         bump,
         payer = first_player
     )]
-    pub first_player_escrow: Account<'info, TokenAccount>,
+    pub first_player_escrow: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         constraint = first_player_token_account.mint == mint.key(),
         constraint = first_player_token_account.owner == first_player.key(),
         constraint = first_player_token_account.amount >= amount @ BPSError::AmountExceedsBalance
     )]
-    pub first_player_token_account: Account<'info, TokenAccount>,
+    pub first_player_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Limits the admin set for the mint, read by `StakeLimits::enforce`.
     #[account(
-        // Constraint for Bonk ;)
-        address = pubkey!("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263")
+        seeds = [STAKE_LIMITS.as_ref(), mint.key().as_ref()],
+        bump
     )]
-    pub mint: Box<Account<'info, Mint>>,
+    pub stake_limits: AccountInfo<'info>,
 
     #[account(
-        seeds = [BPS_SETTINGS_V2.as_ref()],
-        bump = bps_settings_v2.bump,
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
     )]
-    pub bps_settings_v2: Box<Account<'info, BpsSettingsV2>>,
-    /// CHECK: Address check is enough.
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    // Bonk-Paper-Scissors when omitted.
     #[account(
-        mut,
-        address = bps_settings_v2.authority
+        seeds = [RULESET.as_ref(), ruleset.ruleset_id.as_bytes()],
+        bump = ruleset.bump,
+    )]
+    pub ruleset: Option<Box<Account<'info, Ruleset>>>,
+    #[account(
+        init_if_needed,
+        payer = first_player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), first_player.key().as_ref()],
+        bump
     )]
-    pub bps_treasury: AccountInfo<'info>,
+    pub first_player_open_games: Box<Account<'info, OpenGames>>,
 
     #[account(mut)]
     pub first_player: Signer<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -81,55 +92,81 @@ pub fn first_player_move(
     game_id: String,
     amount: u64,
     first_player_hash: [u8; 32], // Choice + Salt
+    use_relayer: bool,           // Opts in to relayed reveals, see `reveal_for`
 ) -> Result<()> {
     let clock = Clock::get()?;
-    let game = &mut ctx.accounts.game;
     let first_player = &ctx.accounts.first_player;
     let first_player_token_account = &mut ctx.accounts.first_player_token_account;
     let first_player_escrow = &mut ctx.accounts.first_player_escrow;
-    let bps_settings_v2 = &ctx.accounts.bps_settings_v2;
-    let bps_treasury = &ctx.accounts.bps_treasury;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
     let _hash = anchor_lang::solana_program::hash::Hash::new_from_array(first_player_hash);
-    let bump = ctx.bumps.get("game").unwrap();
+    let bump = ctx.bumps.game;
+    let mint = &ctx.accounts.mint;
 
-    let mint = ctx.accounts.mint.key();
     let first_player_key = first_player.key();
 
+    StakeLimits::enforce(&ctx.accounts.stake_limits, amount)?;
+    require_supported_mint(&mint.to_account_info())?;
+    ctx.accounts.first_player_open_games.open(
+        ctx.bumps.first_player_open_games,
+        first_player_key,
+        ctx.accounts.bps_settings_v3.limits.max_open_games,
+    )?;
     // Transfer the tokens to the escrow account.
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: first_player_token_account.to_account_info(),
+                mint: mint.to_account_info(),
                 to: first_player_escrow.to_account_info(),
                 authority: first_player.to_account_info(),
             },
         ),
         amount,
+        mint.decimals,
     )?;
-    // Pay the fee to the treasury.
+    // Transfer-fee mints withhold part of it, the stake is what actually reached the escrow.
+    first_player_escrow.reload()?;
+    // The game holds the fee until it is over, and the relayer fee until the reveal.
+    let relayer_fee_lamports = if use_relayer {
+        ctx.accounts.bps_settings_v3.fees.relayer_fee_lamports
+    } else {
+        0
+    };
     invoke(
         &system_instruction::transfer(
             &first_player_key,
-            &bps_settings_v2.authority,
-            bps_settings_v2.player_fee_lamports,
+            &ctx.accounts.game.key(),
+            bps_settings_v3.fees.player_fee_lamports + relayer_fee_lamports,
         ),
         &[
             first_player.to_account_info(),
-            bps_treasury.to_account_info(),
+            ctx.accounts.game.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
         ],
     )?;
 
-    game.set_inner(Game::new(
-        *bump,
-        game_id,
-        mint,
-        amount,
+    let mut game = GameV3::new(
+        bump,
+        &game_id,
+        mint.key(),
+        first_player_escrow.amount,
         clock.unix_timestamp,
         first_player_key,
         first_player_hash,
         first_player_escrow.key(),
-    ));
+    );
+    if let Some(ruleset) = ctx.accounts.ruleset.as_ref() {
+        game.set_rules(ruleset.choice_count, ruleset.beats);
+    }
+    game.escrow_fees(
+        ctx.accounts.bps_settings_v3.refund_on_cancel(),
+        ctx.accounts.bps_settings_v3.refund_on_forfeit(),
+    );
+    game.snapshot_settings(bps_settings_v3, bps_settings_v3.fees.player_fee_lamports)?;
+    game.set_open_game_slot(FIRST_PLAYER_SLOT);
+    game.deposit_relayer_fee(FIRST_PLAYER_SLOT, relayer_fee_lamports);
+    *ctx.accounts.game.load_init()? = game;
     Ok(())
 }
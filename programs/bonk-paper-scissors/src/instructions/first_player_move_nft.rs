@@ -0,0 +1,172 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{
+        ALLOWED_COLLECTION, BPS_SETTINGS_V3, ESCROW, FIRST_PLAYER, GAME, OPEN_GAMES, RULESET,
+    },
+    error::BPSError,
+    state::{AllowedCollection, BpsSettingsV3, GameV3, OpenGames, Ruleset, FIRST_PLAYER_SLOT},
+    utils::verified_collection,
+};
+
+#[derive(Accounts)]
+#[instruction(game_id: String)]
+pub struct FirstPlayerMoveNft<'info> {
+    #[account(
+        init,
+        payer = first_player,
+        space = GameV3::size(),
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game_id.as_bytes()
+        ],
+        bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = game,
+        seeds = [
+            game.key().as_ref(),
+            ESCROW.as_ref(),
+            FIRST_PLAYER.as_ref(),
+        ],
+        bump,
+        payer = first_player
+    )]
+    pub first_player_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = first_player_token_account.mint == mint.key(),
+        constraint = first_player_token_account.owner == first_player.key(),
+        constraint = first_player_token_account.amount == 1 @ BPSError::AmountExceedsBalance
+    )]
+    pub first_player_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mint::decimals = 0,
+        mint::token_program = token_program,
+        constraint = mint.supply == 1 @ BPSError::NotAnNft,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Parsed by `verified_collection`.
+    pub metadata: AccountInfo<'info>,
+    #[account(
+        seeds = [ALLOWED_COLLECTION.as_ref(), allowed_collection.collection.as_ref()],
+        bump = allowed_collection.bump,
+    )]
+    pub allowed_collection: Box<Account<'info, AllowedCollection>>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    // Bonk-Paper-Scissors when omitted.
+    #[account(
+        seeds = [RULESET.as_ref(), ruleset.ruleset_id.as_bytes()],
+        bump = ruleset.bump,
+    )]
+    pub ruleset: Option<Box<Account<'info, Ruleset>>>,
+    #[account(
+        init_if_needed,
+        payer = first_player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), first_player.key().as_ref()],
+        bump
+    )]
+    pub first_player_open_games: Box<Account<'info, OpenGames>>,
+
+    #[account(mut)]
+    pub first_player: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Player one creates a game staking a single NFT of an allowed collection.
+pub fn first_player_move_nft(
+    ctx: Context<FirstPlayerMoveNft>,
+    game_id: String,
+    first_player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let first_player = &ctx.accounts.first_player;
+    let first_player_token_account = &ctx.accounts.first_player_token_account;
+    let first_player_escrow = &ctx.accounts.first_player_escrow;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let collection = ctx.accounts.allowed_collection.collection;
+    let mint = &ctx.accounts.mint;
+    require!(
+        verified_collection(&ctx.accounts.metadata, &mint.key())? == Some(collection),
+        BPSError::CollectionMismatch
+    );
+
+    let first_player_key = first_player.key();
+
+    ctx.accounts.first_player_open_games.open(
+        ctx.bumps.first_player_open_games,
+        first_player_key,
+        ctx.accounts.bps_settings_v3.limits.max_open_games,
+    )?;
+
+    // Transfer the NFT to the escrow account.
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: first_player_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: first_player_escrow.to_account_info(),
+                authority: first_player.to_account_info(),
+            },
+        ),
+        1,
+        mint.decimals,
+    )?;
+    // The game holds the fee until it is over.
+    invoke(
+        &system_instruction::transfer(
+            &first_player_key,
+            &ctx.accounts.game.key(),
+            bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            first_player.to_account_info(),
+            ctx.accounts.game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    let mut game = GameV3::new(
+        ctx.bumps.game,
+        &game_id,
+        mint.key(),
+        1,
+        clock.unix_timestamp,
+        first_player_key,
+        first_player_hash,
+        first_player_escrow.key(),
+    );
+    game.set_nft(collection);
+    if let Some(ruleset) = ctx.accounts.ruleset.as_ref() {
+        game.set_rules(ruleset.choice_count, ruleset.beats);
+    }
+    game.escrow_fees(
+        ctx.accounts.bps_settings_v3.refund_on_cancel(),
+        ctx.accounts.bps_settings_v3.refund_on_forfeit(),
+    );
+    game.snapshot_settings(bps_settings_v3, bps_settings_v3.fees.player_fee_lamports)?;
+    game.set_open_game_slot(FIRST_PLAYER_SLOT);
+    *ctx.accounts.game.load_init()? = game;
+    Ok(())
+}
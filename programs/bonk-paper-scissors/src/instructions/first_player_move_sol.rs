@@ -0,0 +1,134 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::token::spl_token::native_mint;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, GAME, OPEN_GAMES, RULESET, SOL_VAULT, STAKE_LIMITS},
+    state::{BpsSettingsV3, GameV3, OpenGames, Ruleset, SolVault, StakeLimits, FIRST_PLAYER_SLOT},
+};
+
+#[derive(Accounts)]
+#[instruction(game_id: String, amount: u64)]
+pub struct FirstPlayerMoveSol<'info> {
+    #[account(
+        init,
+        payer = first_player,
+        space = GameV3::size(),
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game_id.as_bytes()
+        ],
+        bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        init,
+        payer = first_player,
+        space = SolVault::size(),
+        seeds = [SOL_VAULT.as_ref(), game.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+    /// CHECK: Limits the admin set for native SOL, keyed on the native mint, read by
+    /// `StakeLimits::enforce`.
+    #[account(
+        seeds = [STAKE_LIMITS.as_ref(), native_mint::ID.as_ref()],
+        bump
+    )]
+    pub stake_limits: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    // Bonk-Paper-Scissors when omitted.
+    #[account(
+        seeds = [RULESET.as_ref(), ruleset.ruleset_id.as_bytes()],
+        bump = ruleset.bump,
+    )]
+    pub ruleset: Option<Box<Account<'info, Ruleset>>>,
+    #[account(
+        init_if_needed,
+        payer = first_player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), first_player.key().as_ref()],
+        bump
+    )]
+    pub first_player_open_games: Box<Account<'info, OpenGames>>,
+
+    #[account(mut)]
+    pub first_player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Player one creates a native SOL game, staking `amount` lamports in the game vault.
+pub fn first_player_move_sol(
+    ctx: Context<FirstPlayerMoveSol>,
+    game_id: String,
+    amount: u64,
+    first_player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let first_player = &ctx.accounts.first_player;
+    let sol_vault = &mut ctx.accounts.sol_vault;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let first_player_key = first_player.key();
+
+    StakeLimits::enforce(&ctx.accounts.stake_limits, amount)?;
+    ctx.accounts.first_player_open_games.open(
+        ctx.bumps.first_player_open_games,
+        first_player_key,
+        ctx.accounts.bps_settings_v3.limits.max_open_games,
+    )?;
+
+    // Transfer the stake to the vault.
+    invoke(
+        &system_instruction::transfer(&first_player_key, &sol_vault.key(), amount),
+        &[
+            first_player.to_account_info(),
+            sol_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    // The game holds the fee until it is over.
+    invoke(
+        &system_instruction::transfer(
+            &first_player_key,
+            &ctx.accounts.game.key(),
+            bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            first_player.to_account_info(),
+            ctx.accounts.game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    sol_vault.set_inner(SolVault::new(ctx.bumps.sol_vault, ctx.accounts.game.key()));
+    let mut game = GameV3::new(
+        ctx.bumps.game,
+        &game_id,
+        Pubkey::default(),
+        amount,
+        clock.unix_timestamp,
+        first_player_key,
+        first_player_hash,
+        sol_vault.key(),
+    );
+    game.set_native_sol();
+    if let Some(ruleset) = ctx.accounts.ruleset.as_ref() {
+        game.set_rules(ruleset.choice_count, ruleset.beats);
+    }
+    game.escrow_fees(
+        ctx.accounts.bps_settings_v3.refund_on_cancel(),
+        ctx.accounts.bps_settings_v3.refund_on_forfeit(),
+    );
+    game.snapshot_settings(bps_settings_v3, bps_settings_v3.fees.player_fee_lamports)?;
+    game.set_open_game_slot(FIRST_PLAYER_SLOT);
+    *ctx.accounts.game.load_init()? = game;
+    Ok(())
+}
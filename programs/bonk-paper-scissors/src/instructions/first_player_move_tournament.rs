@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{GAME, TOURNAMENT},
+    error::BPSError,
+    state::{GameV3, Tournament, TournamentState},
+};
+
+#[derive(Accounts)]
+#[instruction(game_id: String)]
+pub struct FirstPlayerMoveTournament<'info> {
+    #[account(
+        init,
+        payer = first_player,
+        space = GameV3::size(),
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game_id.as_bytes()
+        ],
+        bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [TOURNAMENT.as_ref(), tournament.organizer.as_ref(), tournament.tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.state == TournamentState::Running @ BPSError::InvalidGameState,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(mut)]
+    pub first_player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The home player of a bracket match creates its game, nothing is staked.
+pub fn first_player_move_tournament(
+    ctx: Context<FirstPlayerMoveTournament>,
+    game_id: String,
+    match_index: u8,
+    first_player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let first_player_key = ctx.accounts.first_player.key();
+    let tournament_key = ctx.accounts.tournament.key();
+    let bracket_match = ctx
+        .accounts
+        .tournament
+        .matches
+        .get_mut(match_index as usize)
+        .ok_or_else(|| error!(BPSError::InvalidPlayer))?;
+    require_keys_eq!(
+        bracket_match.home,
+        first_player_key,
+        BPSError::InvalidPlayer
+    );
+    require!(
+        bracket_match.game.is_none() && bracket_match.winner.is_none(),
+        BPSError::MatchDecided
+    );
+    bracket_match.game = Some(ctx.accounts.game.key());
+
+    let mut game = GameV3::new(
+        ctx.bumps.game,
+        &game_id,
+        Pubkey::default(),
+        0,
+        clock.unix_timestamp,
+        first_player_key,
+        first_player_hash,
+        Pubkey::default(),
+    );
+    game.set_tournament(tournament_key);
+    *ctx.accounts.game.load_init()? = game;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, TOURNAMENT},
+    error::BPSError,
+    state::{BpsSettingsV3, GameState, GameV3, Tournament, TournamentState},
+};
+
+#[derive(Accounts)]
+pub struct ForfeitTournamentMatch<'info> {
+    #[account(
+        mut,
+        seeds = [TOURNAMENT.as_ref(), tournament.organizer.as_ref(), tournament.tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.state == TournamentState::Running @ BPSError::InvalidGameState,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    // The match game, if the home player created one.
+    pub game: Option<AccountLoader<'info, GameV3>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+}
+
+/// Decides a match where a player didn't show up before its deadline, anyone can crank it. The
+/// away player advances when the home player never created the game, the home player when the
+/// away player never joined it or neither revealed. Started games are otherwise settled by
+/// `claim_tournament_match`, which applies the usual reveal timers.
+pub fn forfeit_tournament_match(
+    ctx: Context<ForfeitTournamentMatch>,
+    match_index: u8,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let time_for_penalization = ctx.accounts.bps_settings_v3.timers.time_for_penalization;
+    let tournament = &mut ctx.accounts.tournament;
+    let bracket_match = *tournament
+        .matches
+        .get(match_index as usize)
+        .ok_or_else(|| error!(BPSError::InvalidPlayer))?;
+    require!(bracket_match.winner.is_none(), BPSError::MatchDecided);
+    require!(
+        bracket_match.deadline < clock.unix_timestamp,
+        BPSError::MatchNotExpired
+    );
+
+    let winner = match (bracket_match.game, &ctx.accounts.game) {
+        (None, _) => bracket_match.away,
+        (Some(game_key), Some(game)) => {
+            require_keys_eq!(game_key, game.key(), BPSError::NotTournamentGame);
+            let game = game.load()?;
+            let no_show = game.game_state() == GameState::CreatedAndWaitingForStart
+                || game.is_expired(clock.unix_timestamp, time_for_penalization);
+            require!(no_show, BPSError::MatchNotExpired);
+            bracket_match.home
+        }
+        (Some(_), None) => return err!(BPSError::NotTournamentGame),
+    };
+    tournament.record_winner(
+        match_index as usize,
+        winner,
+        clock.unix_timestamp,
+        time_for_penalization,
+    );
+    Ok(())
+}
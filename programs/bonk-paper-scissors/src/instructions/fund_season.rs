@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::{ESCROW, SEASON},
+    error::BPSError,
+    state::{Season, SeasonState},
+};
+
+#[derive(Accounts)]
+pub struct FundSeason<'info> {
+    #[account(
+        seeds = [SEASON.as_ref(), season.season_id.as_bytes()],
+        bump = season.bump,
+        constraint = season.state == SeasonState::Active @ BPSError::SeasonNotActive,
+    )]
+    pub season: Box<Account<'info, Season>>,
+    #[account(
+        mut,
+        seeds = [season.key().as_ref(), ESCROW.as_ref()],
+        bump,
+    )]
+    pub season_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == season.mint,
+        constraint = funder_token_account.owner == funder.key(),
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = season.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub funder: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Adds to the reward pool, anyone can sponsor a season until it is closed.
+pub fn fund_season(ctx: Context<FundSeason>, amount: u64) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+    require!(
+        ctx.accounts.funder_token_account.amount >= amount,
+        BPSError::AmountExceedsBalance
+    );
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: ctx.accounts.season_vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    Ok(())
+}
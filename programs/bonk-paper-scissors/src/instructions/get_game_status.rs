@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, GAME},
+    state::{BpsSettingsV3, GameStatus, GameV3},
+};
+
+#[derive(Accounts)]
+pub struct GetGameStatus<'info> {
+    #[account(
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+}
+
+/// Read-only, meant to be simulated so clients show the same deadlines and outcome the program
+/// settles with.
+pub fn get_game_status(ctx: Context<GetGameStatus>) -> Result<GameStatus> {
+    let clock = Clock::get()?;
+    let game = *ctx.accounts.game.load()?;
+    Ok(GameStatus::new(
+        &game,
+        clock.unix_timestamp,
+        game.time_for_penalization_or(ctx.accounts.bps_settings_v3.timers.time_for_penalization),
+    ))
+}
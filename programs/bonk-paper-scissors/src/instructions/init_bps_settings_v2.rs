@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
-use solana_program::pubkey;
 
-use crate::{constants::BPS_SETTINGS_V2, state::BpsSettingsV2};
+use crate::{
+    constants::{ADMIN, BPS_SETTINGS_V2},
+    error::BPSError,
+    state::BpsSettingsV2,
+};
 
 #[derive(Accounts)]
 pub struct InitBpsSettingsV2<'info> {
@@ -15,25 +18,17 @@ pub struct InitBpsSettingsV2<'info> {
     pub bps_settings_v2: Account<'info, BpsSettingsV2>,
     #[account(
         mut,
-        address = pubkey!("bpstzWLPDetyjiD33HPGGE96MzkhEA7dhRFzhc8Ay5R")
+        address = ADMIN
     )]
     pub signer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+/// Retired, settings live in `BpsSettingsV3` since `migrate_settings_v2_to_v3`.
 pub fn init_bps_settings_v2(
-    ctx: Context<InitBpsSettingsV2>,
-    time_for_penalization: i64,
-    game_fee_lamports: u64,
+    _ctx: Context<InitBpsSettingsV2>,
+    _time_for_penalization: i64,
+    _game_fee_lamports: u64,
 ) -> Result<()> {
-    let bps_settings = &mut ctx.accounts.bps_settings_v2;
-    let signer = &ctx.accounts.signer;
-    let bump = ctx.bumps.get("bps_settings_v2").unwrap();
-    bps_settings.set_inner(BpsSettingsV2::new(
-        *bump,
-        time_for_penalization,
-        signer.key(),
-        game_fee_lamports,
-    ));
-    Ok(())
+    err!(BPSError::InstructionRemoved)
 }
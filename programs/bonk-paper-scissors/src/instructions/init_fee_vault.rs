@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, FEE_VAULT},
+    state::FeeVault,
+};
+
+#[derive(Accounts)]
+pub struct InitFeeVault<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = FeeVault::size(),
+        seeds = [FEE_VAULT.as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_fee_vault(ctx: Context<InitFeeVault>, destination: Pubkey) -> Result<()> {
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    let bump = ctx.bumps.fee_vault;
+    fee_vault.set_inner(FeeVault::new(bump, destination));
+    Ok(())
+}
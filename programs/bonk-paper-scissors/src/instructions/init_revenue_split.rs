@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, REVENUE_SPLIT},
+    error::BPSError,
+    state::{RevenueRecipient, RevenueSplit},
+};
+
+#[derive(Accounts)]
+pub struct InitRevenueSplit<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = RevenueSplit::size(),
+        seeds = [REVENUE_SPLIT.as_ref()],
+        bump
+    )]
+    pub revenue_split: Account<'info, RevenueSplit>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_revenue_split(
+    ctx: Context<InitRevenueSplit>,
+    recipients: Vec<RevenueRecipient>,
+) -> Result<()> {
+    require!(
+        RevenueSplit::is_valid(&recipients),
+        BPSError::InvalidRevenueSplit
+    );
+    let bump = ctx.bumps.revenue_split;
+    ctx.accounts
+        .revenue_split
+        .set_inner(RevenueSplit::new(bump, recipients));
+    Ok(())
+}
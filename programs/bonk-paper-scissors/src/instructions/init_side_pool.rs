@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, ESCROW, GAME, SIDE_POOL},
+    error::BPSError,
+    state::{BpsSettingsV3, GameState, GameV3, SidePool},
+};
+
+#[derive(Accounts)]
+pub struct InitSidePool<'info> {
+    #[account(
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.game_state() == GameState::CreatedAndWaitingForStart @ BPSError::BettingClosed,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        init,
+        payer = payer,
+        space = SidePool::size(),
+        seeds = [
+            SIDE_POOL.as_ref(),
+            game.key().as_ref(),
+            game.load()?.created_at.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub side_pool: Box<Account<'info, SidePool>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = side_pool,
+        seeds = [side_pool.key().as_ref(), ESCROW.as_ref()],
+        bump,
+        payer = payer
+    )]
+    pub side_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a side pool on a game that is still waiting for its second player. Betting closes
+/// when the second player joins, or when the game can be expired if nobody does.
+pub fn init_side_pool(ctx: Context<InitSidePool>) -> Result<()> {
+    let clock = Clock::get()?;
+    let game = ctx.accounts.game.load()?;
+    let side_pool = &mut ctx.accounts.side_pool;
+    let side_pool_vault = &ctx.accounts.side_pool_vault;
+    let bump = ctx.bumps.side_pool;
+    let betting_closes_at = game.created_at
+        + game.time_for_penalization_or(ctx.accounts.bps_settings_v3.timers.time_for_penalization);
+    require!(
+        betting_closes_at > clock.unix_timestamp,
+        BPSError::BettingClosed
+    );
+    side_pool.set_inner(SidePool::new(
+        bump,
+        ctx.accounts.game.key(),
+        game.created_at,
+        game.mint,
+        side_pool_vault.key(),
+        betting_closes_at,
+    ));
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{ADMIN, STAKE_LIMITS},
+    error::BPSError,
+    state::StakeLimits,
+    utils::require_supported_mint,
+};
+
+#[derive(Accounts)]
+pub struct InitStakeLimits<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = StakeLimits::size(),
+        seeds = [STAKE_LIMITS.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub stake_limits: Account<'info, StakeLimits>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_stake_limits(
+    ctx: Context<InitStakeLimits>,
+    min_stake: u64,
+    max_stake: u64,
+    tiers: Vec<u64>,
+) -> Result<()> {
+    require!(
+        StakeLimits::is_valid(min_stake, max_stake, &tiers),
+        BPSError::InvalidStakeLimits
+    );
+    require_supported_mint(&ctx.accounts.mint.to_account_info())?;
+    let bump = ctx.bumps.stake_limits;
+    let mint = ctx.accounts.mint.key();
+    ctx.accounts
+        .stake_limits
+        .set_inner(StakeLimits::new(bump, mint, min_stake, max_stake, tiers));
+    Ok(())
+}
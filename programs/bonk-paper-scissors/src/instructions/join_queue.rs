@@ -0,0 +1,410 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{
+    constants::{
+        BPS_SETTINGS_V3, ESCROW, FIRST_PLAYER, GAME, OPEN_GAMES, QUEUE_TICKET, SECOND_PLAYER,
+        STAKE_LIMITS,
+    },
+    error::BPSError,
+    state::{
+        BpsSettingsV3, GameV3, OpenGames, QueueTicket, StakeLimits, FIRST_PLAYER_SLOT,
+        SECOND_PLAYER_SLOT,
+    },
+    utils::{
+        gross_amount_for, harvest_withheld_fees, require_supported_mint, transfer_lamports, SeedKey,
+    },
+};
+
+#[derive(Accounts)]
+#[instruction(game_id: String, amount: u64)]
+pub struct JoinQueue<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        constraint = player_token_account.mint == mint.key(),
+        constraint = player_token_account.owner == player.key(),
+        constraint = player_token_account.amount >= amount @ BPSError::AmountExceedsBalance
+    )]
+    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Limits the admin set for the mint, read by `StakeLimits::enforce`.
+    #[account(
+        seeds = [STAKE_LIMITS.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub stake_limits: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+
+    // ----- Enqueue: no compatible ticket was found -----
+    #[account(
+        init,
+        payer = player,
+        space = QueueTicket::size(),
+        seeds = [
+            QUEUE_TICKET.as_ref(),
+            mint.key().as_ref(),
+            amount.to_le_bytes().as_ref(),
+            player.key().as_ref()
+        ],
+        bump
+    )]
+    pub queue_ticket: Option<Box<Account<'info, QueueTicket>>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = queue_ticket,
+        seeds = [
+            queue_ticket.seed_key().as_ref(),
+            ESCROW.as_ref(),
+            FIRST_PLAYER.as_ref(),
+        ],
+        bump,
+        payer = player
+    )]
+    pub queue_ticket_escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // ----- Match: a compatible ticket is waiting -----
+    #[account(
+        mut,
+        close = queued_player,
+        seeds = [
+            QUEUE_TICKET.as_ref(),
+            mint.key().as_ref(),
+            amount.to_le_bytes().as_ref(),
+            queued_ticket.player.as_ref()
+        ],
+        bump = queued_ticket.bump,
+        constraint = queued_ticket.player != player.key() @ BPSError::CantMatchOwnQueueTicket,
+    )]
+    pub queued_ticket: Option<Box<Account<'info, QueueTicket>>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = queued_ticket.as_ref().map(|queued_ticket| queued_ticket.escrow_address)
+            == Some(queued_ticket_escrow.key()) @ BPSError::MissingQueueAccounts,
+    )]
+    pub queued_ticket_escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        constraint = queued_ticket.as_ref().map(|queued_ticket| queued_ticket.player)
+            == Some(queued_player.key()) @ BPSError::MissingQueueAccounts,
+    )]
+    pub queued_player: Option<AccountInfo<'info>>,
+    #[account(
+        init,
+        payer = player,
+        space = GameV3::size(),
+        seeds = [
+            GAME.as_ref(),
+            queued_ticket
+                .as_ref()
+                .map(|queued_ticket| queued_ticket.player)
+                .unwrap_or_default()
+                .as_ref(),
+            game_id.as_bytes()
+        ],
+        bump
+    )]
+    pub game: Option<AccountLoader<'info, GameV3>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = game,
+        seeds = [
+            game.seed_key().as_ref(),
+            ESCROW.as_ref(),
+            FIRST_PLAYER.as_ref(),
+        ],
+        bump,
+        payer = player
+    )]
+    pub first_player_escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = game,
+        seeds = [
+            game.seed_key().as_ref(),
+            ESCROW.as_ref(),
+            SECOND_PLAYER.as_ref(),
+        ],
+        bump,
+        payer = player
+    )]
+    pub second_player_escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = OpenGames::size(),
+        seeds = [
+            OPEN_GAMES.as_ref(),
+            queued_ticket
+                .as_ref()
+                .map(|queued_ticket| queued_ticket.player)
+                .unwrap_or_default()
+                .as_ref()
+        ],
+        bump
+    )]
+    pub first_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub second_player_open_games: Option<Box<Account<'info, OpenGames>>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pairs the player with a queued ticket of the same mint and amount, or enqueues a new ticket.
+/// `game_id` is only used when a match happens, it names the game created for both players.
+pub fn join_queue(
+    ctx: Context<JoinQueue>,
+    game_id: String,
+    amount: u64,
+    player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let player = &ctx.accounts.player;
+    let player_token_account = &ctx.accounts.player_token_account;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let mint = &ctx.accounts.mint;
+    let player_key = player.key();
+
+    StakeLimits::enforce(&ctx.accounts.stake_limits, amount)?;
+    require_supported_mint(&mint.to_account_info())?;
+
+    if let Some(queued_ticket) = ctx.accounts.queued_ticket.as_ref() {
+        let missing = || error!(BPSError::MissingQueueAccounts);
+        let queued_ticket_escrow = ctx
+            .accounts
+            .queued_ticket_escrow
+            .as_ref()
+            .ok_or_else(missing)?;
+        let queued_player = ctx.accounts.queued_player.as_ref().ok_or_else(missing)?;
+        let first_player_escrow = ctx
+            .accounts
+            .first_player_escrow
+            .as_mut()
+            .ok_or_else(missing)?;
+        let second_player_escrow = ctx
+            .accounts
+            .second_player_escrow
+            .as_mut()
+            .ok_or_else(missing)?;
+        let game = ctx.accounts.game.as_ref().ok_or_else(missing)?;
+
+        // Both players take a slot, the queued one as the first player.
+        let max_open_games = ctx.accounts.bps_settings_v3.limits.max_open_games;
+        ctx.accounts
+            .first_player_open_games
+            .as_mut()
+            .ok_or_else(missing)?
+            .open(
+                ctx.bumps.first_player_open_games,
+                queued_ticket.player,
+                max_open_games,
+            )?;
+        ctx.accounts
+            .second_player_open_games
+            .as_mut()
+            .ok_or_else(missing)?
+            .open(
+                ctx.bumps.second_player_open_games,
+                player_key,
+                max_open_games,
+            )?;
+
+        // The game holds both fees until it is over, the queued player's comes from its ticket.
+        invoke(
+            &system_instruction::transfer(
+                &player_key,
+                &game.key(),
+                bps_settings_v3.fees.player_fee_lamports,
+            ),
+            &[
+                player.to_account_info(),
+                game.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+        let queued_fee = QueueTicket::held_fee(&queued_ticket.to_account_info())?;
+        transfer_lamports(
+            &queued_ticket.to_account_info(),
+            &game.to_account_info(),
+            queued_fee,
+        )?;
+
+        let amount_bytes = queued_ticket.amount.to_le_bytes();
+        let ticket_seeds = &[
+            QUEUE_TICKET.as_ref(),
+            queued_ticket.mint.as_ref(),
+            amount_bytes.as_ref(),
+            queued_ticket.player.as_ref(),
+            &[queued_ticket.bump],
+        ];
+        let ticket_signer = &[&ticket_seeds[..]];
+
+        // Move the queued player's stake from the ticket escrow to the game escrow. With a
+        // transfer-fee mint this is charged again, the stake is what reaches the game escrow.
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: queued_ticket_escrow.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: first_player_escrow.to_account_info(),
+                    authority: queued_ticket.to_account_info(),
+                },
+            )
+            .with_signer(ticket_signer),
+            queued_ticket_escrow.amount,
+            mint.decimals,
+        )?;
+        harvest_withheld_fees(
+            &ctx.accounts.token_program.to_account_info(),
+            &mint.to_account_info(),
+            &[queued_ticket_escrow.to_account_info()],
+        )?;
+        close_account(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: queued_ticket_escrow.to_account_info(),
+                    destination: queued_player.to_account_info(),
+                    authority: queued_ticket.to_account_info(),
+                },
+            )
+            .with_signer(ticket_signer),
+        )?;
+        first_player_escrow.reload()?;
+        let amount_to_match = first_player_escrow.amount;
+
+        // Transfer the tokens to the escrow account, covering the transfer fee so both escrows
+        // hold the same amount.
+        let player_amount = gross_amount_for(&mint.to_account_info(), amount_to_match)?;
+        require!(
+            player_token_account.amount >= player_amount,
+            BPSError::AmountExceedsBalance
+        );
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: player_token_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: second_player_escrow.to_account_info(),
+                    authority: player.to_account_info(),
+                },
+            ),
+            player_amount,
+            mint.decimals,
+        )?;
+        second_player_escrow.reload()?;
+        require!(
+            second_player_escrow.amount == amount_to_match,
+            BPSError::EscrowAmountMismatch
+        );
+
+        let mut new_game = GameV3::new(
+            ctx.bumps.game,
+            &game_id,
+            mint.key(),
+            amount_to_match,
+            clock.unix_timestamp,
+            queued_ticket.player,
+            queued_ticket.player_hash,
+            first_player_escrow.key(),
+        );
+        new_game.escrow_fees(
+            ctx.accounts.bps_settings_v3.refund_on_cancel(),
+            ctx.accounts.bps_settings_v3.refund_on_forfeit(),
+        );
+        // The queued player paid its fee when queueing, what its ticket held above rent.
+        new_game.snapshot_settings(bps_settings_v3, queued_fee)?;
+        new_game.set_second_player_fee(bps_settings_v3.fees.player_fee_lamports);
+        new_game.set_second_player(
+            player_key,
+            player_hash,
+            second_player_escrow.key(),
+            clock.unix_timestamp,
+        );
+        new_game.set_open_game_slot(FIRST_PLAYER_SLOT);
+        new_game.set_open_game_slot(SECOND_PLAYER_SLOT);
+        *game.load_init()? = new_game;
+    } else {
+        let missing = || error!(BPSError::MissingQueueAccounts);
+        let queue_ticket_escrow = ctx
+            .accounts
+            .queue_ticket_escrow
+            .as_ref()
+            .ok_or_else(missing)?;
+        let queue_ticket = ctx.accounts.queue_ticket.as_mut().ok_or_else(missing)?;
+
+        // The ticket holds the fee until it's matched, leaving the queue refunds it.
+        invoke(
+            &system_instruction::transfer(
+                &player_key,
+                &queue_ticket.key(),
+                bps_settings_v3.fees.player_fee_lamports,
+            ),
+            &[
+                player.to_account_info(),
+                queue_ticket.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        // Transfer the tokens to the escrow account.
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: player_token_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: queue_ticket_escrow.to_account_info(),
+                    authority: player.to_account_info(),
+                },
+            ),
+            amount,
+            mint.decimals,
+        )?;
+
+        queue_ticket.set_inner(QueueTicket::new(
+            ctx.bumps.queue_ticket,
+            player_key,
+            mint.key(),
+            amount,
+            player_hash,
+            queue_ticket_escrow.key(),
+            clock.unix_timestamp,
+        ));
+    }
+    Ok(())
+}
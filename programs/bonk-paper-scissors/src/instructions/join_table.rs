@@ -0,0 +1,127 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, ESCROW, FEE_VAULT, TABLE},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, Table, TableState},
+    utils::gross_amount_for,
+};
+
+#[derive(Accounts)]
+pub struct JoinTable<'info> {
+    #[account(
+        mut,
+        seeds = [TABLE.as_ref(), table.creator.as_ref(), table.table_id.as_bytes()],
+        bump = table.bump,
+        constraint = table.state == TableState::Open @ BPSError::InvalidGameState,
+        constraint = !table.is_full() @ BPSError::TableFull,
+        constraint = table.seat_index(&player.key()).is_none() @ BPSError::AlreadySeated,
+    )]
+    pub table: Box<Account<'info, Table>>,
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = table,
+        seeds = [table.key().as_ref(), ESCROW.as_ref(), player.key().as_ref()],
+        bump,
+        payer = player
+    )]
+    pub player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = player_token_account.mint == mint.key(),
+        constraint = player_token_account.owner == player.key(),
+    )]
+    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = table.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Takes a seat at an open table, the last seat starts the first round.
+pub fn join_table(ctx: Context<JoinTable>) -> Result<()> {
+    let clock = Clock::get()?;
+    let table = &mut ctx.accounts.table;
+    let player = &ctx.accounts.player;
+    let player_token_account = &ctx.accounts.player_token_account;
+    let player_escrow = &mut ctx.accounts.player_escrow;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let mint = &ctx.accounts.mint;
+
+    // Cover the transfer fee so every escrow holds the same stake.
+    let amount = gross_amount_for(&mint.to_account_info(), table.stake)?;
+    require!(
+        player_token_account.amount >= amount,
+        BPSError::AmountExceedsBalance
+    );
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: player_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: player_escrow.to_account_info(),
+                authority: player.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    player_escrow.reload()?;
+    require!(
+        player_escrow.amount == table.stake,
+        BPSError::EscrowAmountMismatch
+    );
+    // Pay the fee into the fee vault.
+    invoke(
+        &system_instruction::transfer(
+            &player.key(),
+            &ctx.accounts.fee_vault.key(),
+            bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            player.to_account_info(),
+            ctx.accounts.fee_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    ctx.accounts
+        .fee_vault
+        .collect(bps_settings_v3.fees.player_fee_lamports);
+
+    table.add_seat(player.key(), player_escrow.key());
+    if table.is_full() {
+        table.start_round(
+            clock.unix_timestamp,
+            bps_settings_v3.timers.time_for_penalization,
+        );
+    }
+    Ok(())
+}
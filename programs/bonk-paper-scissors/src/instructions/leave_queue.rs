@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::{
+    constants::{ESCROW, FIRST_PLAYER, QUEUE_TICKET},
+    state::QueueTicket,
+    utils::harvest_withheld_fees,
+};
+
+#[derive(Accounts)]
+pub struct LeaveQueue<'info> {
+    #[account(
+        mut,
+        close = player,
+        seeds = [
+            QUEUE_TICKET.as_ref(),
+            queue_ticket.mint.as_ref(),
+            queue_ticket.amount.to_le_bytes().as_ref(),
+            player.key().as_ref()
+        ],
+        bump = queue_ticket.bump,
+    )]
+    pub queue_ticket: Account<'info, QueueTicket>,
+    #[account(
+        mut,
+        token::mint = queue_ticket.mint,
+        token::authority = queue_ticket,
+        seeds = [
+            queue_ticket.key().as_ref(),
+            ESCROW.as_ref(),
+            FIRST_PLAYER.as_ref(),
+        ],
+        bump,
+    )]
+    pub queue_ticket_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = player_token_account.mint == queue_ticket.mint,
+        constraint = player_token_account.owner == player.key(),
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = queue_ticket.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = queue_ticket.player,
+    )]
+    pub player: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraws a queued ticket, returning the escrowed stake, the fee and the rent to the player.
+pub fn leave_queue(ctx: Context<LeaveQueue>) -> Result<()> {
+    let queue_ticket = &ctx.accounts.queue_ticket;
+    let queue_ticket_escrow = &ctx.accounts.queue_ticket_escrow;
+    let player_token_account = &ctx.accounts.player_token_account;
+    let mint = &ctx.accounts.mint;
+    let player = &ctx.accounts.player;
+    let amount_bytes = queue_ticket.amount.to_le_bytes();
+    let ticket_seeds = &[
+        QUEUE_TICKET.as_ref(),
+        queue_ticket.mint.as_ref(),
+        amount_bytes.as_ref(),
+        queue_ticket.player.as_ref(),
+        &[queue_ticket.bump],
+    ];
+    let ticket_signer = &[&ticket_seeds[..]];
+    // The escrow holds what arrived after the transfer fee, which can be less than the ticket amount.
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: queue_ticket_escrow.to_account_info(),
+                mint: mint.to_account_info(),
+                to: player_token_account.to_account_info(),
+                authority: queue_ticket.to_account_info(),
+            },
+        )
+        .with_signer(ticket_signer),
+        queue_ticket_escrow.amount,
+        mint.decimals,
+    )?;
+    harvest_withheld_fees(
+        &ctx.accounts.token_program.to_account_info(),
+        &mint.to_account_info(),
+        &[queue_ticket_escrow.to_account_info()],
+    )?;
+    close_account(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: queue_ticket_escrow.to_account_info(),
+                destination: player.to_account_info(),
+                authority: queue_ticket.to_account_info(),
+            },
+        )
+        .with_signer(ticket_signer),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anchor_lang::{prelude::*, AccountsClose};
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::{
+    constants::{ESCROW, TABLE},
+    error::BPSError,
+    state::{Table, TableState},
+    utils::harvest_withheld_fees,
+};
+
+#[derive(Accounts)]
+pub struct LeaveTable<'info> {
+    #[account(
+        mut,
+        seeds = [TABLE.as_ref(), table.creator.as_ref(), table.table_id.as_bytes()],
+        bump = table.bump,
+        constraint = table.state == TableState::Open @ BPSError::InvalidGameState,
+        constraint = table.seat_index(&player.key()).is_some() @ BPSError::InvalidPlayer,
+    )]
+    pub table: Box<Account<'info, Table>>,
+    #[account(
+        mut,
+        token::mint = table.mint,
+        token::authority = table,
+        seeds = [table.key().as_ref(), ESCROW.as_ref(), player.key().as_ref()],
+        bump,
+    )]
+    pub player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = player_token_account.mint == table.mint,
+        constraint = player_token_account.owner == player.key(),
+    )]
+    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = table.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Address check is enough, gets the table rent back once everyone left.
+    #[account(
+        mut,
+        address = table.creator,
+    )]
+    pub creator: AccountInfo<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Gives up a seat before the first round, returning the stake and the escrow rent.
+pub fn leave_table(ctx: Context<LeaveTable>) -> Result<()> {
+    let player_escrow = &ctx.accounts.player_escrow;
+    let mint = &ctx.accounts.mint;
+    let player = &ctx.accounts.player;
+    let table_info = ctx.accounts.table.to_account_info();
+    // Owned copies, the table is updated while the signer is still needed.
+    let creator_key = ctx.accounts.table.creator;
+    let table_id = ctx.accounts.table.table_id.clone();
+    let table_seeds = &[
+        TABLE.as_ref(),
+        creator_key.as_ref(),
+        table_id.as_bytes(),
+        &[ctx.accounts.table.bump],
+    ];
+    let table_signer = &[&table_seeds[..]];
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: player_escrow.to_account_info(),
+                mint: mint.to_account_info(),
+                to: ctx.accounts.player_token_account.to_account_info(),
+                authority: table_info.clone(),
+            },
+        )
+        .with_signer(table_signer),
+        player_escrow.amount,
+        mint.decimals,
+    )?;
+    harvest_withheld_fees(
+        &ctx.accounts.token_program.to_account_info(),
+        &mint.to_account_info(),
+        &[player_escrow.to_account_info()],
+    )?;
+    close_account(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: player_escrow.to_account_info(),
+                destination: player.to_account_info(),
+                authority: table_info,
+            },
+        )
+        .with_signer(table_signer),
+    )?;
+
+    let table = &mut ctx.accounts.table;
+    table.seats.retain(|seat| seat.player != player.key());
+    if table.seats.is_empty() {
+        table.close(ctx.accounts.creator.to_account_info())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+    Discriminator,
+};
+
+use crate::{
+    constants::GAME,
+    error::BPSError,
+    state::{Game, GameV3},
+    utils::transfer_lamports,
+};
+
+#[derive(Accounts)]
+pub struct MigrateGame<'info> {
+    /// CHECK: Deserialized by hand, it's a Borsh `Game` until it gets rewritten as a `GameV3`.
+    #[account(
+        mut,
+        owner = crate::ID
+    )]
+    pub game: AccountInfo<'info>,
+    /// CHECK: Checked against the legacy game, it paid the rent the smaller account frees.
+    #[account(mut)]
+    pub first_player: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrites a Borsh game as a zero-copy `GameV3` in place, resizing the account to fit. The
+/// rent a smaller account no longer needs goes back to the first player.
+pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+    let game_info = &ctx.accounts.game;
+    let payer = &ctx.accounts.payer;
+
+    require!(
+        !game_info
+            .data
+            .borrow()
+            .starts_with(&GameV3::discriminator()),
+        BPSError::GameAlreadyMigrated
+    );
+    let game = Game::try_deserialize(&mut &game_info.data.borrow()[..])?;
+    let game_address = Pubkey::create_program_address(
+        &[
+            GAME.as_ref(),
+            game.first_player.as_ref(),
+            game.game_id.as_bytes(),
+            &[game.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(BPSError::InvalidGameState))?;
+    require_keys_eq!(game_address, game_info.key(), BPSError::InvalidGameState);
+    require_keys_eq!(
+        ctx.accounts.first_player.key(),
+        game.first_player,
+        BPSError::InvalidPlayer
+    );
+
+    let rent = Rent::get()?.minimum_balance(GameV3::size());
+    let lamports_needed = rent.saturating_sub(game_info.lamports());
+    if lamports_needed > 0 {
+        invoke(
+            &system_instruction::transfer(&payer.key(), &game_info.key(), lamports_needed),
+            &[
+                payer.to_account_info(),
+                game_info.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    let surplus = game_info.lamports().saturating_sub(rent);
+    if surplus > 0 {
+        transfer_lamports(game_info, &ctx.accounts.first_player, surplus)?;
+    }
+    game_info.realloc(GameV3::size(), true)?;
+
+    let mut data = game_info.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(&GameV3::discriminator());
+    data[8..].copy_from_slice(bytemuck::bytes_of(&GameV3::from_legacy(&game)));
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, BPS_SETTINGS_V2, BPS_SETTINGS_V3},
+    state::{BpsSettingsV2, BpsSettingsV3},
+};
+
+#[derive(Accounts)]
+pub struct MigrateSettingsV2ToV3<'info> {
+    #[account(
+        mut,
+        close = signer,
+        seeds = [BPS_SETTINGS_V2.as_ref()],
+        bump = bps_settings_v2.bump,
+    )]
+    pub bps_settings_v2: Account<'info, BpsSettingsV2>,
+    #[account(
+        init,
+        payer = signer,
+        space = BpsSettingsV3::size(),
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Copies the V2 settings into `BpsSettingsV3` and closes the V2 account, so it only runs once.
+pub fn migrate_settings_v2_to_v3(ctx: Context<MigrateSettingsV2ToV3>) -> Result<()> {
+    let bump = ctx.bumps.bps_settings_v3;
+    let bps_settings_v3 = BpsSettingsV3::from_v2(bump, &ctx.accounts.bps_settings_v2);
+    ctx.accounts.bps_settings_v3.set_inner(bps_settings_v3);
+    Ok(())
+}
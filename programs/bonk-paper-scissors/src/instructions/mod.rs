@@ -1,17 +1,135 @@
+pub mod accept_rematch;
 pub mod admin_unwind_game;
+pub mod admin_unwind_nft_game;
+pub mod admin_unwind_sol_game;
+pub mod advance_table;
+pub mod allow_collection;
+pub mod audit_game;
 pub mod cancel_game;
+pub mod cancel_nft_game;
+pub mod cancel_sol_game;
 pub mod claim;
+pub mod claim_nft;
+pub mod claim_season_reward;
+pub mod claim_side_bet;
+pub mod claim_sol;
+pub mod claim_table;
+pub mod claim_tournament_match;
+pub mod claim_tournament_prize;
+pub mod close_season;
+pub mod commit_table_move;
+pub mod create_ruleset;
+pub mod create_season;
+pub mod create_session;
+pub mod create_table;
+pub mod create_tournament;
+pub mod disallow_collection;
+pub mod expire_nft_game;
+pub mod expire_open_game;
+pub mod expire_sol_game;
+pub mod expire_unrevealed_game;
 pub mod first_player_move;
+pub mod first_player_move_nft;
+pub mod first_player_move_sol;
+pub mod first_player_move_tournament;
+pub mod forfeit_tournament_match;
+pub mod fund_season;
+pub mod get_game_status;
 pub mod init_bps_settings_v2;
+pub mod init_fee_vault;
+pub mod init_revenue_split;
+pub mod init_side_pool;
+pub mod init_stake_limits;
+pub mod join_queue;
+pub mod join_table;
+pub mod leave_queue;
+pub mod leave_table;
+pub mod migrate_game;
+pub mod migrate_settings_v2_to_v3;
+pub mod place_side_bet;
+pub mod preview_settlement;
+pub mod propose_rematch;
+pub mod register_for_tournament;
 pub mod reveal;
+pub mod reveal_for;
+pub mod reveal_table_move;
+pub mod revoke_session;
 pub mod second_player_move;
+pub mod second_player_move_nft;
+pub mod second_player_move_sol;
+pub mod second_player_move_tournament;
+pub mod start_tournament;
+pub mod sweep_fees;
 pub mod update_bps_settings_v2;
+pub mod update_bps_settings_v3;
+pub mod update_fee_vault;
+pub mod update_revenue_split;
+pub mod update_stake_limits;
 
+pub use accept_rematch::*;
 pub use admin_unwind_game::*;
+pub use admin_unwind_nft_game::*;
+pub use admin_unwind_sol_game::*;
+pub use advance_table::*;
+pub use allow_collection::*;
+pub use audit_game::*;
 pub use cancel_game::*;
+pub use cancel_nft_game::*;
+pub use cancel_sol_game::*;
 pub use claim::*;
+pub use claim_nft::*;
+pub use claim_season_reward::*;
+pub use claim_side_bet::*;
+pub use claim_sol::*;
+pub use claim_table::*;
+pub use claim_tournament_match::*;
+pub use claim_tournament_prize::*;
+pub use close_season::*;
+pub use commit_table_move::*;
+pub use create_ruleset::*;
+pub use create_season::*;
+pub use create_session::*;
+pub use create_table::*;
+pub use create_tournament::*;
+pub use disallow_collection::*;
+pub use expire_nft_game::*;
+pub use expire_open_game::*;
+pub use expire_sol_game::*;
+pub use expire_unrevealed_game::*;
 pub use first_player_move::*;
+pub use first_player_move_nft::*;
+pub use first_player_move_sol::*;
+pub use first_player_move_tournament::*;
+pub use forfeit_tournament_match::*;
+pub use fund_season::*;
+pub use get_game_status::*;
 pub use init_bps_settings_v2::*;
+pub use init_fee_vault::*;
+pub use init_revenue_split::*;
+pub use init_side_pool::*;
+pub use init_stake_limits::*;
+pub use join_queue::*;
+pub use join_table::*;
+pub use leave_queue::*;
+pub use leave_table::*;
+pub use migrate_game::*;
+pub use migrate_settings_v2_to_v3::*;
+pub use place_side_bet::*;
+pub use preview_settlement::*;
+pub use propose_rematch::*;
+pub use register_for_tournament::*;
 pub use reveal::*;
+pub use reveal_for::*;
+pub use reveal_table_move::*;
+pub use revoke_session::*;
 pub use second_player_move::*;
+pub use second_player_move_nft::*;
+pub use second_player_move_sol::*;
+pub use second_player_move_tournament::*;
+pub use start_tournament::*;
+pub use sweep_fees::*;
 pub use update_bps_settings_v2::*;
+pub use update_bps_settings_v3::*;
+pub use update_fee_vault::*;
+pub use update_revenue_split::*;
+pub use update_stake_limits::*;
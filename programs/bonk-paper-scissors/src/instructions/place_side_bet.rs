@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::{ESCROW, GAME, SIDE_BET, SIDE_POOL},
+    error::BPSError,
+    state::{GameState, GameV3, SideBet, SidePool},
+};
+
+#[derive(Accounts)]
+#[instruction(outcome: GameState, amount: u64)]
+pub struct PlaceSideBet<'info> {
+    #[account(
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.game_state() == GameState::CreatedAndWaitingForStart @ BPSError::BettingClosed,
+        constraint = game.load()?.first_player != bettor.key() @ BPSError::PlayerCantBetOnOwnGame,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [
+            SIDE_POOL.as_ref(),
+            game.key().as_ref(),
+            side_pool.game_created_at.to_le_bytes().as_ref()
+        ],
+        bump = side_pool.bump,
+        constraint = side_pool.game_created_at == game.load()?.created_at @ BPSError::BettingClosed,
+    )]
+    pub side_pool: Box<Account<'info, SidePool>>,
+    #[account(
+        mut,
+        seeds = [side_pool.key().as_ref(), ESCROW.as_ref()],
+        bump,
+    )]
+    pub side_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = bettor,
+        space = SideBet::size(),
+        seeds = [SIDE_BET.as_ref(), side_pool.key().as_ref(), bettor.key().as_ref()],
+        bump
+    )]
+    pub side_bet: Box<Account<'info, SideBet>>,
+    #[account(
+        mut,
+        constraint = bettor_token_account.mint == game.load()?.mint,
+        constraint = bettor_token_account.owner == bettor.key(),
+        constraint = bettor_token_account.amount >= amount @ BPSError::AmountExceedsBalance
+    )]
+    pub bettor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Wagers on the final state of a game, the bet is locked until the game settles.
+pub fn place_side_bet(ctx: Context<PlaceSideBet>, outcome: GameState, amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let side_pool = &mut ctx.accounts.side_pool;
+    let side_pool_vault = &mut ctx.accounts.side_pool_vault;
+    let side_bet = &mut ctx.accounts.side_bet;
+    let bettor = &ctx.accounts.bettor;
+    let bettor_token_account = &ctx.accounts.bettor_token_account;
+    let mint = &ctx.accounts.mint;
+    let bump = ctx.bumps.side_bet;
+    require!(
+        clock.unix_timestamp < side_pool.betting_closes_at,
+        BPSError::BettingClosed
+    );
+    require!(
+        matches!(
+            outcome,
+            GameState::FirstPlayerWon | GameState::SecondPlayerWon | GameState::Draw
+        ),
+        BPSError::InvalidSideBetOutcome
+    );
+
+    let vault_balance = side_pool_vault.amount;
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: bettor_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: side_pool_vault.to_account_info(),
+                authority: bettor.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    // Transfer-fee mints withhold part of it, the bet is what actually reached the vault.
+    side_pool_vault.reload()?;
+    let amount_received = side_pool_vault.amount - vault_balance;
+
+    side_pool.add_bet(&outcome, amount_received);
+    side_bet.set_inner(SideBet::new(
+        bump,
+        side_pool.key(),
+        bettor.key(),
+        outcome,
+        amount_received,
+    ));
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, GAME},
+    error::BPSError,
+    state::{BpsSettingsV3, GameState, GameV3, SettlementPreview},
+    utils::transfer_fee,
+};
+
+#[derive(Accounts)]
+pub struct PreviewSettlement<'info> {
+    #[account(
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    /// CHECK: Only read for its transfer fee, required for token games.
+    #[account(address = game.load()?.mint)]
+    pub mint: Option<AccountInfo<'info>>,
+}
+
+/// Read-only, meant to be simulated. Fails like `claim` does when the game can't be settled yet.
+pub fn preview_settlement(ctx: Context<PreviewSettlement>) -> Result<SettlementPreview> {
+    let clock = Clock::get()?;
+    let game = *ctx.accounts.game.load()?;
+    let time_for_penalization =
+        game.time_for_penalization_or(ctx.accounts.bps_settings_v3.timers.time_for_penalization);
+    require!(
+        game.is_claimable(clock.unix_timestamp, time_for_penalization),
+        BPSError::InvalidGameState
+    );
+
+    let outcome = game.outcome(clock.unix_timestamp, time_for_penalization);
+    // NFTs are handed over whole.
+    let (amount_to_pay, amount_to_burn) = if game.is_nft() {
+        (game.amount_to_match, 0)
+    } else {
+        game.payout_split()
+    };
+    // Transfer-fee mints withhold part of each payout.
+    let received = |amount: u64| -> Result<u64> {
+        if game.is_native_sol() || game.is_nft() || game.is_tournament() {
+            return Ok(amount);
+        }
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or_else(|| error!(BPSError::MissingMint))?;
+        Ok(amount - transfer_fee(mint, amount)?)
+    };
+
+    let (first_player_payout, second_player_payout, amount_burned) = match outcome {
+        GameState::FirstPlayerWon => (received(amount_to_pay)? * 2, 0, amount_to_burn * 2),
+        GameState::SecondPlayerWon => (0, received(amount_to_pay)? * 2, amount_to_burn * 2),
+        _ => {
+            let refund = received(game.amount_to_match)?;
+            (refund, refund, 0)
+        }
+    };
+    let winner = match outcome {
+        GameState::FirstPlayerWon => Some(game.first_player),
+        GameState::SecondPlayerWon => game.second_player(),
+        _ => None,
+    };
+
+    Ok(SettlementPreview {
+        outcome,
+        winner,
+        first_player_payout,
+        second_player_payout,
+        amount_burned,
+    })
+}
@@ -0,0 +1,184 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, ESCROW, FIRST_PLAYER, GAME, OPEN_GAMES, STAKE_LIMITS},
+    error::BPSError,
+    state::{BpsSettingsV3, GameV3, OpenGames, StakeLimits, FIRST_PLAYER_SLOT},
+    utils::gross_amount_for,
+};
+
+#[derive(Accounts)]
+#[instruction(game_id: String)]
+pub struct ProposeRematch<'info> {
+    #[account(
+        seeds = [
+            GAME.as_ref(),
+            previous_game.load()?.first_player.as_ref(),
+            previous_game.load()?.game_id()
+        ],
+        bump = previous_game.load()?.bump,
+        constraint = previous_game.load()?.is_settled() @ BPSError::GameNotSettled,
+        constraint = previous_game.load()?.opponent_of(&first_player.key()).is_some() @ BPSError::InvalidPlayer,
+        constraint = !previous_game.load()?.is_native_sol()
+            && !previous_game.load()?.is_nft()
+            && !previous_game.load()?.is_tournament() @ BPSError::RematchNotSupported,
+    )]
+    pub previous_game: AccountLoader<'info, GameV3>,
+
+    #[account(
+        init,
+        payer = first_player,
+        space = GameV3::size(),
+        seeds = [
+            GAME.as_ref(),
+            first_player.key().as_ref(),
+            game_id.as_bytes()
+        ],
+        bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = game,
+        seeds = [
+            game.key().as_ref(),
+            ESCROW.as_ref(),
+            FIRST_PLAYER.as_ref(),
+        ],
+        bump,
+        payer = first_player
+    )]
+    pub first_player_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = first_player_token_account.mint == mint.key(),
+        constraint = first_player_token_account.owner == first_player.key(),
+        constraint = first_player_token_account.amount >= previous_game.load()?.amount_to_match @ BPSError::AmountExceedsBalance
+    )]
+    pub first_player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = previous_game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Limits the admin set for the mint, read by `StakeLimits::enforce`.
+    #[account(
+        seeds = [STAKE_LIMITS.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub stake_limits: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        init_if_needed,
+        payer = first_player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), first_player.key().as_ref()],
+        bump
+    )]
+    pub first_player_open_games: Box<Account<'info, OpenGames>>,
+
+    #[account(mut)]
+    pub first_player: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Either player of a settled game offers a rematch with the same stake, moving first.
+pub fn propose_rematch(
+    ctx: Context<ProposeRematch>,
+    game_id: String,
+    first_player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let previous_game = ctx.accounts.previous_game.load()?;
+    let first_player = &ctx.accounts.first_player;
+    let first_player_token_account = &ctx.accounts.first_player_token_account;
+    let first_player_escrow = &mut ctx.accounts.first_player_escrow;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let mint = &ctx.accounts.mint;
+    let bump = ctx.bumps.game;
+
+    let first_player_key = first_player.key();
+
+    // The limits may have changed since the previous game.
+    StakeLimits::enforce(&ctx.accounts.stake_limits, previous_game.amount_to_match)?;
+    ctx.accounts.first_player_open_games.open(
+        ctx.bumps.first_player_open_games,
+        first_player_key,
+        ctx.accounts.bps_settings_v3.limits.max_open_games,
+    )?;
+    // Transfer the tokens to the escrow account, covering the transfer fee so the stake doesn't
+    // shrink from one rematch to the next.
+    let amount = gross_amount_for(&mint.to_account_info(), previous_game.amount_to_match)?;
+    require!(
+        first_player_token_account.amount >= amount,
+        BPSError::AmountExceedsBalance
+    );
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: first_player_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: first_player_escrow.to_account_info(),
+                authority: first_player.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    first_player_escrow.reload()?;
+    require!(
+        first_player_escrow.amount == previous_game.amount_to_match,
+        BPSError::EscrowAmountMismatch
+    );
+    // The game holds the fee until it is over.
+    invoke(
+        &system_instruction::transfer(
+            &first_player_key,
+            &ctx.accounts.game.key(),
+            bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            first_player.to_account_info(),
+            ctx.accounts.game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    let mut new_game = GameV3::new(
+        bump,
+        &game_id,
+        previous_game.mint,
+        previous_game.amount_to_match,
+        clock.unix_timestamp,
+        first_player_key,
+        first_player_hash,
+        first_player_escrow.key(),
+    );
+    new_game.escrow_fees(
+        ctx.accounts.bps_settings_v3.refund_on_cancel(),
+        ctx.accounts.bps_settings_v3.refund_on_forfeit(),
+    );
+    new_game.snapshot_settings(bps_settings_v3, bps_settings_v3.fees.player_fee_lamports)?;
+    new_game.set_previous_game(ctx.accounts.previous_game.key());
+    new_game.set_rules(previous_game.choice_count, previous_game.beats);
+    new_game.set_open_game_slot(FIRST_PLAYER_SLOT);
+    *ctx.accounts.game.load_init()? = new_game;
+    Ok(())
+}
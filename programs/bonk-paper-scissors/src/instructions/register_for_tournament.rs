@@ -0,0 +1,120 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, ESCROW, FEE_VAULT, TOURNAMENT},
+    error::BPSError,
+    state::{BpsSettingsV3, FeeVault, Tournament, TournamentState},
+    utils::gross_amount_for,
+};
+
+#[derive(Accounts)]
+pub struct RegisterForTournament<'info> {
+    #[account(
+        mut,
+        seeds = [TOURNAMENT.as_ref(), tournament.organizer.as_ref(), tournament.tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.state == TournamentState::Registering @ BPSError::RegistrationClosed,
+        constraint = !tournament.is_full() @ BPSError::TournamentFull,
+        constraint = tournament.entrant_index(&player.key()).is_none() @ BPSError::AlreadyRegistered,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        mut,
+        seeds = [tournament.key().as_ref(), ESCROW.as_ref()],
+        bump,
+    )]
+    pub tournament_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = player_token_account.mint == tournament.mint,
+        constraint = player_token_account.owner == player.key(),
+    )]
+    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        address = tournament.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays the entry fee into the prize pool and the player fee into the fee vault.
+pub fn register_for_tournament(ctx: Context<RegisterForTournament>) -> Result<()> {
+    let clock = Clock::get()?;
+    let tournament = &mut ctx.accounts.tournament;
+    let tournament_escrow = &mut ctx.accounts.tournament_escrow;
+    let player = &ctx.accounts.player;
+    let player_token_account = &ctx.accounts.player_token_account;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let mint = &ctx.accounts.mint;
+    require!(
+        clock.unix_timestamp <= tournament.registration_ends_at,
+        BPSError::RegistrationClosed
+    );
+
+    // Cover the transfer fee so every entrant adds the same amount to the pool.
+    let amount = gross_amount_for(&mint.to_account_info(), tournament.entry_fee)?;
+    require!(
+        player_token_account.amount >= amount,
+        BPSError::AmountExceedsBalance
+    );
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: player_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: tournament_escrow.to_account_info(),
+                authority: player.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    tournament_escrow.reload()?;
+    require!(
+        tournament_escrow.amount == tournament.prize_pool + tournament.entry_fee,
+        BPSError::EscrowAmountMismatch
+    );
+    // Pay the fee into the fee vault.
+    invoke(
+        &system_instruction::transfer(
+            &player.key(),
+            &ctx.accounts.fee_vault.key(),
+            bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            player.to_account_info(),
+            ctx.accounts.fee_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    ctx.accounts
+        .fee_vault
+        .collect(bps_settings_v3.fees.player_fee_lamports);
+
+    tournament.register(player.key());
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::GAME,
+    constants::{GAME, SESSION_TOKEN},
     error::BPSError,
-    state::{Choice, Game, GameState},
+    state::{GameState, GameV3, SessionToken},
+    utils::transfer_lamports,
 };
 
 fn vec_to_arr_of_n<T, const N: usize>(v: Vec<T>) -> [T; N] {
@@ -17,50 +18,89 @@ pub struct Reveal<'info> {
         mut,
         seeds = [
             GAME.as_ref(),
-            game.first_player.as_ref(),
-            game.game_id.as_bytes()
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
         ],
-        bump = game.bump
+        bump = game.load()?.bump
     )]
-    pub game: Account<'info, Game>,
+    pub game: AccountLoader<'info, GameV3>,
+    // The player, or a session signer acting for it.
+    #[account(mut)]
     pub player: Signer<'info>,
+    #[account(
+        seeds = [
+            SESSION_TOKEN.as_ref(),
+            session_token.authority.as_ref(),
+            player.key().as_ref()
+        ],
+        bump = session_token.bump,
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn reveal(ctx: Context<Reveal>, choice: Choice, salt: [u8; 32]) -> Result<()> {
+/// A player revealing for themselves gets back the relayer fee they deposited, to the signer when
+/// it is their session key.
+pub fn reveal(ctx: Context<Reveal>, choice: u8, salt: [u8; 32]) -> Result<()> {
     let clock = Clock::get()?;
-    let game = &mut ctx.accounts.game;
+    let mut game = ctx.accounts.game.load_mut()?;
+
+    let player_key = SessionToken::resolve_player(
+        ctx.accounts.session_token.as_deref(),
+        ctx.accounts.player.key(),
+        clock.unix_timestamp,
+        game.amount_to_match,
+    )?;
 
-    let choice_bytes = choice.clone() as u8;
-    let player_key = ctx.accounts.player.key();
+    reveal_choice(&mut game, player_key, choice, salt, clock.unix_timestamp)?;
 
+    let relayer_fee = game.take_relayer_fee(player_key);
+    if relayer_fee > 0 {
+        transfer_lamports(
+            &ctx.accounts.game.to_account_info(),
+            &ctx.accounts.player.to_account_info(),
+            relayer_fee,
+        )?;
+    }
+    Ok(())
+}
+
+/// Checks `choice + salt` against the hash committed by `player_key` and stores the choice, the
+/// index of one of the choices of the game's ruleset.
+pub(crate) fn reveal_choice(
+    game: &mut GameV3,
+    player_key: Pubkey,
+    choice: u8,
+    salt: [u8; 32],
+    now: i64,
+) -> Result<()> {
     require!(
-        game.game_state == GameState::StartedAndWaitingForReveal,
+        game.game_state() == GameState::StartedAndWaitingForReveal,
         BPSError::InvalidGameState
     );
+    require!(choice < game.choice_count(), BPSError::InvalidChoice);
     if player_key == game.first_player {
         require!(
-            game.first_player_choice.is_none(),
+            game.first_player_choice().is_none(),
             BPSError::PlayerAlreadyMoved
         );
-        let val_to_hash = vec_to_arr_of_n::<u8, 33>([&[choice_bytes], &salt[..]].concat());
+        let val_to_hash = vec_to_arr_of_n::<u8, 33>([&[choice], &salt[..]].concat());
         let created_hash = anchor_lang::solana_program::hash::hashv(&[&val_to_hash]);
         let stored_hash =
             anchor_lang::solana_program::hash::Hash::new_from_array(game.first_player_hash);
         require_eq!(created_hash, stored_hash, BPSError::InvalidHash);
-        game.set_first_player_choice(choice.into(), clock.unix_timestamp);
-    } else if player_key == game.second_player.unwrap() {
+        game.set_first_player_choice(choice, now);
+    } else if Some(player_key) == game.second_player() {
         require!(
-            game.second_player_choice.is_none(),
+            game.second_player_choice().is_none(),
             BPSError::PlayerAlreadyMoved
         );
-        let val_to_hash = vec_to_arr_of_n::<u8, 33>([&[choice_bytes], &salt[..]].concat());
+        let val_to_hash = vec_to_arr_of_n::<u8, 33>([&[choice], &salt[..]].concat());
         let created_hash = anchor_lang::solana_program::hash::hashv(&[&val_to_hash]);
-        let stored_hash = anchor_lang::solana_program::hash::Hash::new_from_array(
-            game.second_player_hash.unwrap(),
-        );
+        let stored_hash =
+            anchor_lang::solana_program::hash::Hash::new_from_array(game.second_player_hash);
         require_eq!(created_hash, stored_hash, BPSError::InvalidHash);
-        game.set_second_player_choice(choice, clock.unix_timestamp);
+        game.set_second_player_choice(choice, now);
     } else {
         return err!(BPSError::InvalidPlayer);
     }
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::GAME, instructions::reveal::reveal_choice, state::GameV3, utils::transfer_lamports,
+};
+
+#[derive(Accounts)]
+pub struct RevealFor<'info> {
+    #[account(
+        mut,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Reveals on behalf of `player`, the salted hash is what proves the reveal is authentic.
+/// The relayer is paid the relayer fee `player` deposited when they opted in to relayed reveals.
+pub fn reveal_for(
+    ctx: Context<RevealFor>,
+    player: Pubkey,
+    choice: u8,
+    salt: [u8; 32],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut game = ctx.accounts.game.load_mut()?;
+
+    reveal_choice(&mut game, player, choice, salt, clock.unix_timestamp)?;
+
+    let relayer_fee = game.take_relayer_fee(player);
+    if relayer_fee > 0 {
+        transfer_lamports(
+            &ctx.accounts.game.to_account_info(),
+            &ctx.accounts.relayer.to_account_info(),
+            relayer_fee,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, TABLE},
+    error::BPSError,
+    state::{BpsSettingsV3, Choice, Table, TableState},
+};
+
+#[derive(Accounts)]
+pub struct RevealTableMove<'info> {
+    #[account(
+        mut,
+        seeds = [TABLE.as_ref(), table.creator.as_ref(), table.table_id.as_bytes()],
+        bump = table.bump,
+        constraint = table.state == TableState::Revealing @ BPSError::InvalidGameState,
+    )]
+    pub table: Box<Account<'info, Table>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    pub player: Signer<'info>,
+}
+
+/// Reveals the player's choice for the current round, the last reveal resolves it.
+pub fn reveal_table_move(
+    ctx: Context<RevealTableMove>,
+    choice: Choice,
+    salt: [u8; 32],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let table = &mut ctx.accounts.table;
+    let index = table
+        .seat_index(&ctx.accounts.player.key())
+        .ok_or_else(|| error!(BPSError::InvalidPlayer))?;
+    let seat = &mut table.seats[index];
+    require!(!seat.eliminated, BPSError::PlayerEliminated);
+    require!(seat.choice.is_none(), BPSError::PlayerAlreadyMoved);
+    let created_hash = hashv(&[&[choice as u8], &salt[..]]);
+    require!(
+        seat.hash == Some(created_hash.to_bytes()),
+        BPSError::InvalidHash
+    );
+    seat.choice = Some(choice);
+
+    table.advance(
+        clock.unix_timestamp,
+        ctx.accounts.bps_settings_v3.timers.time_for_penalization,
+    );
+    Ok(())
+}
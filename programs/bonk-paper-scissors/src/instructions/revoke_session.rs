@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::SESSION_TOKEN, error::BPSError, state::SessionToken};
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            SESSION_TOKEN.as_ref(),
+            session_token.authority.as_ref(),
+            session_token.session_signer.as_ref()
+        ],
+        bump = session_token.bump,
+    )]
+    pub session_token: Account<'info, SessionToken>,
+    /// CHECK: Address check is enough, it only receives the rent.
+    #[account(
+        mut,
+        address = session_token.authority
+    )]
+    pub authority: AccountInfo<'info>,
+    // Either the authority or the session signer itself.
+    #[account(
+        constraint = signer.key() == session_token.authority
+            || signer.key() == session_token.session_signer @ BPSError::InvalidPlayer
+    )]
+    pub signer: Signer<'info>,
+}
+
+/// Closes a session token, the session signer can't act for the player from now on.
+pub fn revoke_session(_ctx: Context<RevokeSession>) -> Result<()> {
+    Ok(())
+}
@@ -4,13 +4,14 @@ use anchor_lang::{
 };
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::{
-    constants::{BPS_SETTINGS_V2, ESCROW, GAME, SECOND_PLAYER},
+    constants::{BPS_SETTINGS_V3, ESCROW, GAME, OPEN_GAMES, SECOND_PLAYER, SIDE_BET},
     error::BPSError,
-    state::{BpsSettingsV2, Game, GameState},
+    state::{BpsSettingsV3, GameState, GameV3, OpenGames, SidePool, SECOND_PLAYER_SLOT},
+    utils::gross_amount_for,
 };
 
 #[derive(Accounts)]
@@ -19,12 +20,13 @@ pub struct SecondPlayerMove<'info> {
         mut,
         seeds = [
             GAME.as_ref(),
-            game.first_player.as_ref(),
-            game.game_id.as_bytes()
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
         ],
-        bump = game.bump
+        bump = game.load()?.bump,
+        constraint = game.load()?.previous_game().is_none() @ BPSError::RematchReservedForOpponent,
     )]
-    pub game: Account<'info, Game>,
+    pub game: AccountLoader<'info, GameV3>,
 
     #[account(
         init,
@@ -34,38 +36,54 @@ pub struct SecondPlayerMove<'info> {
         bump,
         payer = second_player
     )]
-    pub second_player_escrow: Account<'info, TokenAccount>,
+    pub second_player_escrow: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         constraint = second_player_token_account.mint == mint.key(),
         constraint = second_player_token_account.owner == second_player.key(),
-        constraint = second_player_token_account.amount >= game.amount_to_match @ BPSError::AmountExceedsBalance
+        constraint = second_player_token_account.amount >= game.load()?.amount_to_match @ BPSError::AmountExceedsBalance
     )]
-    pub second_player_token_account: Account<'info, TokenAccount>,
-    #[account(address = game.mint)]
-    pub mint: Box<Account<'info, Mint>>,
+    pub second_player_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        address = game.load()?.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
-        constraint = game.first_player != second_player.key() @ BPSError::FirstPlayerCantJoinAsSecondPlayer,
+        constraint = game.load()?.first_player != second_player.key() @ BPSError::FirstPlayerCantJoinAsSecondPlayer,
     )]
     pub second_player: Signer<'info>,
+    /// CHECK: Must be empty, players can't bet on their own games.
+    #[account(
+        seeds = [
+            SIDE_BET.as_ref(),
+            SidePool::address(&game.key(), game.load()?.created_at).as_ref(),
+            second_player.key().as_ref()
+        ],
+        bump,
+        constraint = second_player_side_bet.data_is_empty() @ BPSError::PlayerCantBetOnOwnGame,
+    )]
+    pub second_player_side_bet: AccountInfo<'info>,
 
     #[account(
-        seeds = [BPS_SETTINGS_V2.as_ref()],
-        bump = bps_settings_v2.bump,
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
     )]
-    pub bps_settings_v2: Box<Account<'info, BpsSettingsV2>>,
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
 
-    /// CHECK: Address check is enough.
     #[account(
-        mut,
-        address = bps_settings_v2.authority
+        init_if_needed,
+        payer = second_player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), second_player.key().as_ref()],
+        bump
     )]
-    pub bps_treasury: AccountInfo<'info>,
+    pub second_player_open_games: Box<Account<'info, OpenGames>>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -73,51 +91,81 @@ pub struct SecondPlayerMove<'info> {
 pub fn second_player_move(
     ctx: Context<SecondPlayerMove>,
     second_player_hash: [u8; 32], // Choice + Salt
+    use_relayer: bool,            // Opts in to relayed reveals, see `reveal_for`
 ) -> Result<()> {
-    let game = &mut ctx.accounts.game;
+    let clock = Clock::get()?;
+    // The game holds the fee until it is over, and the relayer fee until the reveal. Paid before
+    // the game is loaded, the CPI can't take an account that is borrowed.
+    let relayer_fee_lamports = if use_relayer {
+        ctx.accounts.bps_settings_v3.fees.relayer_fee_lamports
+    } else {
+        0
+    };
+    invoke(
+        &system_instruction::transfer(
+            &ctx.accounts.second_player.key(),
+            &ctx.accounts.game.key(),
+            ctx.accounts.bps_settings_v3.fees.player_fee_lamports + relayer_fee_lamports,
+        ),
+        &[
+            ctx.accounts.second_player.to_account_info(),
+            ctx.accounts.game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    let mut game = ctx.accounts.game.load_mut()?;
     let second_player_token_account = &mut ctx.accounts.second_player_token_account;
     let second_player_escrow = &mut ctx.accounts.second_player_escrow;
     let second_player = &mut ctx.accounts.second_player;
-    let bps_settings_v2 = &ctx.accounts.bps_settings_v2;
-    let bps_treasury = &mut ctx.accounts.bps_treasury;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let mint = &ctx.accounts.mint;
     require!(
-        game.game_state == GameState::CreatedAndWaitingForStart,
+        game.game_state() == GameState::CreatedAndWaitingForStart,
         BPSError::InvalidGameState
     );
 
     let second_player_key = second_player.key();
 
-    // Transfer the tokens to the escrow account.
-    transfer(
+    ctx.accounts.second_player_open_games.open(
+        ctx.bumps.second_player_open_games,
+        second_player_key,
+        ctx.accounts.bps_settings_v3.limits.max_open_games,
+    )?;
+
+    // Transfer the tokens to the escrow account, covering the transfer fee so both escrows hold
+    // the same amount.
+    let amount = gross_amount_for(&mint.to_account_info(), game.amount_to_match)?;
+    require!(
+        second_player_token_account.amount >= amount,
+        BPSError::AmountExceedsBalance
+    );
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: second_player_token_account.to_account_info(),
+                mint: mint.to_account_info(),
                 to: second_player_escrow.to_account_info(),
                 authority: second_player.to_account_info(),
             },
         ),
-        game.amount_to_match,
-    )?;
-
-    // Pay the fee to the treasury.
-    invoke(
-        &system_instruction::transfer(
-            &second_player_key,
-            &bps_settings_v2.authority,
-            bps_settings_v2.player_fee_lamports,
-        ),
-        &[
-            second_player.to_account_info(),
-            bps_treasury.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
+        amount,
+        mint.decimals,
     )?;
+    second_player_escrow.reload()?;
+    require!(
+        second_player_escrow.amount == game.amount_to_match,
+        BPSError::EscrowAmountMismatch
+    );
 
+    game.set_open_game_slot(SECOND_PLAYER_SLOT);
+    game.set_second_player_fee(bps_settings_v3.fees.player_fee_lamports);
+    game.deposit_relayer_fee(SECOND_PLAYER_SLOT, relayer_fee_lamports);
     game.set_second_player(
         second_player_key,
         second_player_hash,
         second_player_escrow.key(),
+        clock.unix_timestamp,
     );
 
     Ok(())
@@ -0,0 +1,161 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, ESCROW, GAME, OPEN_GAMES, SECOND_PLAYER, SIDE_BET},
+    error::BPSError,
+    state::{BpsSettingsV3, GameState, GameV3, OpenGames, SidePool, SECOND_PLAYER_SLOT},
+    utils::verified_collection,
+};
+
+#[derive(Accounts)]
+pub struct SecondPlayerMoveNft<'info> {
+    #[account(
+        mut,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_nft() @ BPSError::NotNftGame,
+        constraint = game.load()?.previous_game().is_none() @ BPSError::RematchReservedForOpponent,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = game,
+        seeds = [game.key().as_ref(), ESCROW.as_ref(), SECOND_PLAYER.as_ref()],
+        bump,
+        payer = second_player
+    )]
+    pub second_player_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = second_player_token_account.mint == mint.key(),
+        constraint = second_player_token_account.owner == second_player.key(),
+        constraint = second_player_token_account.amount == 1 @ BPSError::AmountExceedsBalance
+    )]
+    pub second_player_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mint::decimals = 0,
+        mint::token_program = token_program,
+        constraint = mint.supply == 1 @ BPSError::NotAnNft,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Parsed by `verified_collection`.
+    pub metadata: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = game.load()?.first_player != second_player.key() @ BPSError::FirstPlayerCantJoinAsSecondPlayer,
+    )]
+    pub second_player: Signer<'info>,
+    /// CHECK: Must be empty, players can't bet on their own games.
+    #[account(
+        seeds = [
+            SIDE_BET.as_ref(),
+            SidePool::address(&game.key(), game.load()?.created_at).as_ref(),
+            second_player.key().as_ref()
+        ],
+        bump,
+        constraint = second_player_side_bet.data_is_empty() @ BPSError::PlayerCantBetOnOwnGame,
+    )]
+    pub second_player_side_bet: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        init_if_needed,
+        payer = second_player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), second_player.key().as_ref()],
+        bump
+    )]
+    pub second_player_open_games: Box<Account<'info, OpenGames>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Player two joins an NFT game with an NFT of the same collection.
+pub fn second_player_move_nft(
+    ctx: Context<SecondPlayerMoveNft>,
+    second_player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    // The game holds the fee until it is over. Paid before the game is loaded, the CPI can't
+    // take an account that is borrowed.
+    invoke(
+        &system_instruction::transfer(
+            &ctx.accounts.second_player.key(),
+            &ctx.accounts.game.key(),
+            ctx.accounts.bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            ctx.accounts.second_player.to_account_info(),
+            ctx.accounts.game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    let mut game = ctx.accounts.game.load_mut()?;
+    let second_player_token_account = &ctx.accounts.second_player_token_account;
+    let second_player_escrow = &ctx.accounts.second_player_escrow;
+    let second_player = &ctx.accounts.second_player;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    let mint = &ctx.accounts.mint;
+    require!(
+        game.game_state() == GameState::CreatedAndWaitingForStart,
+        BPSError::InvalidGameState
+    );
+    require!(
+        verified_collection(&ctx.accounts.metadata, &mint.key())? == Some(game.collection),
+        BPSError::CollectionMismatch
+    );
+
+    let second_player_key = second_player.key();
+
+    ctx.accounts.second_player_open_games.open(
+        ctx.bumps.second_player_open_games,
+        second_player_key,
+        ctx.accounts.bps_settings_v3.limits.max_open_games,
+    )?;
+
+    // Transfer the NFT to the escrow account.
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: second_player_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: second_player_escrow.to_account_info(),
+                authority: second_player.to_account_info(),
+            },
+        ),
+        1,
+        mint.decimals,
+    )?;
+    game.set_second_player_mint(mint.key());
+    game.set_open_game_slot(SECOND_PLAYER_SLOT);
+    game.set_second_player_fee(bps_settings_v3.fees.player_fee_lamports);
+    game.set_second_player(
+        second_player_key,
+        second_player_hash,
+        second_player_escrow.key(),
+        clock.unix_timestamp,
+    );
+
+    Ok(())
+}
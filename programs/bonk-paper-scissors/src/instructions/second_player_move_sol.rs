@@ -0,0 +1,123 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, GAME, OPEN_GAMES, SIDE_BET, SOL_VAULT},
+    error::BPSError,
+    state::{BpsSettingsV3, GameState, GameV3, OpenGames, SidePool, SolVault, SECOND_PLAYER_SLOT},
+};
+
+#[derive(Accounts)]
+pub struct SecondPlayerMoveSol<'info> {
+    #[account(
+        mut,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_native_sol() @ BPSError::NotNativeSolGame,
+        constraint = game.load()?.previous_game().is_none() @ BPSError::RematchReservedForOpponent,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(
+        mut,
+        seeds = [SOL_VAULT.as_ref(), game.key().as_ref()],
+        bump = sol_vault.bump,
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    #[account(
+        mut,
+        constraint = game.load()?.first_player != second_player.key() @ BPSError::FirstPlayerCantJoinAsSecondPlayer,
+    )]
+    pub second_player: Signer<'info>,
+    /// CHECK: Must be empty, players can't bet on their own games.
+    #[account(
+        seeds = [
+            SIDE_BET.as_ref(),
+            SidePool::address(&game.key(), game.load()?.created_at).as_ref(),
+            second_player.key().as_ref()
+        ],
+        bump,
+        constraint = second_player_side_bet.data_is_empty() @ BPSError::PlayerCantBetOnOwnGame,
+    )]
+    pub second_player_side_bet: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        init_if_needed,
+        payer = second_player,
+        space = OpenGames::size(),
+        seeds = [OPEN_GAMES.as_ref(), second_player.key().as_ref()],
+        bump
+    )]
+    pub second_player_open_games: Box<Account<'info, OpenGames>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Player two joins a native SOL game, matching the stake in lamports.
+pub fn second_player_move_sol(
+    ctx: Context<SecondPlayerMoveSol>,
+    second_player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    // The game holds the fee until it is over. Paid before the game is loaded, the CPI can't
+    // take an account that is borrowed.
+    invoke(
+        &system_instruction::transfer(
+            &ctx.accounts.second_player.key(),
+            &ctx.accounts.game.key(),
+            ctx.accounts.bps_settings_v3.fees.player_fee_lamports,
+        ),
+        &[
+            ctx.accounts.second_player.to_account_info(),
+            ctx.accounts.game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    let mut game = ctx.accounts.game.load_mut()?;
+    let sol_vault = &ctx.accounts.sol_vault;
+    let second_player = &ctx.accounts.second_player;
+    let bps_settings_v3 = &ctx.accounts.bps_settings_v3;
+    require!(
+        game.game_state() == GameState::CreatedAndWaitingForStart,
+        BPSError::InvalidGameState
+    );
+
+    let second_player_key = second_player.key();
+
+    ctx.accounts.second_player_open_games.open(
+        ctx.bumps.second_player_open_games,
+        second_player_key,
+        ctx.accounts.bps_settings_v3.limits.max_open_games,
+    )?;
+
+    // Transfer the stake to the vault.
+    invoke(
+        &system_instruction::transfer(&second_player_key, &sol_vault.key(), game.amount_to_match),
+        &[
+            second_player.to_account_info(),
+            sol_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    game.set_open_game_slot(SECOND_PLAYER_SLOT);
+    game.set_second_player_fee(bps_settings_v3.fees.player_fee_lamports);
+    game.set_second_player(
+        second_player_key,
+        second_player_hash,
+        sol_vault.key(),
+        clock.unix_timestamp,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{GAME, SIDE_BET},
+    error::BPSError,
+    state::{GameState, GameV3, SidePool, Tournament},
+};
+
+#[derive(Accounts)]
+pub struct SecondPlayerMoveTournament<'info> {
+    #[account(
+        mut,
+        seeds = [
+            GAME.as_ref(),
+            game.load()?.first_player.as_ref(),
+            game.load()?.game_id()
+        ],
+        bump = game.load()?.bump,
+        constraint = game.load()?.is_tournament() @ BPSError::NotTournamentGame,
+        constraint = game.load()?.game_state() == GameState::CreatedAndWaitingForStart @ BPSError::InvalidGameState,
+    )]
+    pub game: AccountLoader<'info, GameV3>,
+    #[account(address = game.load()?.tournament)]
+    pub tournament: Box<Account<'info, Tournament>>,
+    pub second_player: Signer<'info>,
+    /// CHECK: Must be empty, players can't bet on their own games.
+    #[account(
+        seeds = [
+            SIDE_BET.as_ref(),
+            SidePool::address(&game.key(), game.load()?.created_at).as_ref(),
+            second_player.key().as_ref()
+        ],
+        bump,
+        constraint = second_player_side_bet.data_is_empty() @ BPSError::PlayerCantBetOnOwnGame,
+    )]
+    pub second_player_side_bet: AccountInfo<'info>,
+}
+
+/// The away player of a bracket match joins its game.
+pub fn second_player_move_tournament(
+    ctx: Context<SecondPlayerMoveTournament>,
+    second_player_hash: [u8; 32], // Choice + Salt
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let tournament = &ctx.accounts.tournament;
+    let second_player_key = ctx.accounts.second_player.key();
+    let index = tournament
+        .match_index(&ctx.accounts.game.key())
+        .ok_or_else(|| error!(BPSError::NotTournamentGame))?;
+    require!(
+        tournament.matches[index].winner.is_none(),
+        BPSError::MatchDecided
+    );
+    require_keys_eq!(
+        tournament.matches[index].away,
+        second_player_key,
+        BPSError::InvalidPlayer
+    );
+
+    let mut game = ctx.accounts.game.load_mut()?;
+    game.set_second_player(
+        second_player_key,
+        second_player_hash,
+        Pubkey::default(),
+        clock.unix_timestamp,
+    );
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_SETTINGS_V3, TOURNAMENT},
+    error::BPSError,
+    state::{BpsSettingsV3, Tournament, TournamentState},
+};
+
+#[derive(Accounts)]
+pub struct StartTournament<'info> {
+    #[account(
+        mut,
+        seeds = [TOURNAMENT.as_ref(), tournament.organizer.as_ref(), tournament.tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.state == TournamentState::Registering @ BPSError::InvalidGameState,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    #[account(
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+}
+
+/// Pairs the first round once registration ended or the tournament is full, anyone can crank it.
+pub fn start_tournament(ctx: Context<StartTournament>) -> Result<()> {
+    let clock = Clock::get()?;
+    let tournament = &mut ctx.accounts.tournament;
+    require!(
+        tournament.is_full() || clock.unix_timestamp > tournament.registration_ends_at,
+        BPSError::RegistrationOpen
+    );
+    tournament.start(
+        clock.unix_timestamp,
+        ctx.accounts.bps_settings_v3.timers.time_for_penalization,
+    );
+    Ok(())
+}
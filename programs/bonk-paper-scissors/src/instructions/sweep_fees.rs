@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, FEE_VAULT, REVENUE_SPLIT},
+    error::BPSError,
+    state::{FeeVault, RevenueDistributed, RevenueSplit},
+    utils::transfer_lamports,
+};
+
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    /// CHECK: Address check is enough.
+    #[account(
+        mut,
+        address = fee_vault.destination
+    )]
+    pub destination: AccountInfo<'info>,
+    /// CHECK: Always passed so a sweep can't skip the split once the admin created it.
+    #[account(
+        seeds = [REVENUE_SPLIT.as_ref()],
+        bump,
+    )]
+    pub revenue_split: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    // remaining_accounts: the recipients of the revenue split in its order, once it exists.
+}
+
+/// Moves everything the fee vault holds above its rent out of it. Once the revenue split exists
+/// each recipient gets its share and the rounding dust goes to the destination, which otherwise
+/// gets it all.
+pub fn sweep_fees<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SweepFees<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let fee_vault_info = ctx.accounts.fee_vault.to_account_info();
+    let rent = Rent::get()?.minimum_balance(fee_vault_info.data_len());
+    let amount = fee_vault_info.lamports().saturating_sub(rent);
+    require!(amount > 0, BPSError::NothingToSweep);
+
+    let mut payouts = vec![];
+    if let Some(revenue_split) = RevenueSplit::load(&ctx.accounts.revenue_split)? {
+        require!(
+            revenue_split.matches(ctx.remaining_accounts),
+            BPSError::InvalidRevenueRecipients
+        );
+        payouts.extend(
+            ctx.remaining_accounts
+                .iter()
+                .zip(revenue_split.shares(amount)),
+        );
+    }
+    let remainder = amount - payouts.iter().map(|(_, share)| share).sum::<u64>();
+    if remainder > 0 {
+        payouts.push((&ctx.accounts.destination, remainder));
+    }
+    for (recipient, share) in payouts.iter() {
+        transfer_lamports(&fee_vault_info, recipient, *share)?;
+    }
+
+    ctx.accounts.fee_vault.sweep(amount);
+    emit!(RevenueDistributed {
+        amount,
+        recipients: payouts
+            .iter()
+            .map(|(recipient, _)| recipient.key())
+            .collect(),
+        amounts: payouts.iter().map(|(_, share)| *share).collect(),
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
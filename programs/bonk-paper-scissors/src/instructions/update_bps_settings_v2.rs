@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
-use solana_program::pubkey;
 
-use crate::{constants::BPS_SETTINGS_V2, state::BpsSettingsV2};
+use crate::{
+    constants::{ADMIN, BPS_SETTINGS_V2},
+    error::BPSError,
+    state::BpsSettingsV2,
+};
 
 #[derive(Accounts)]
 pub struct UpdateBpsSettingsV2<'info> {
@@ -13,21 +16,17 @@ pub struct UpdateBpsSettingsV2<'info> {
     pub bps_settings_v2: Account<'info, BpsSettingsV2>,
     #[account(
         mut,
-        address = pubkey!("bpstzWLPDetyjiD33HPGGE96MzkhEA7dhRFzhc8Ay5R")
+        address = ADMIN
     )]
     pub signer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+/// Retired, settings live in `BpsSettingsV3` since `migrate_settings_v2_to_v3`.
 pub fn update_bps_settings_v2(
-    ctx: Context<UpdateBpsSettingsV2>,
-    time_for_penalization: i64,
-    player_fee_lamports: u64,
+    _ctx: Context<UpdateBpsSettingsV2>,
+    _time_for_penalization: i64,
+    _player_fee_lamports: u64,
 ) -> Result<()> {
-    let bps_settings = &mut ctx.accounts.bps_settings_v2;
-    let signer = &ctx.accounts.signer;
-    bps_settings.time_for_penalization = time_for_penalization;
-    bps_settings.authority = signer.key();
-    bps_settings.player_fee_lamports = player_fee_lamports;
-    Ok(())
+    err!(BPSError::InstructionRemoved)
 }
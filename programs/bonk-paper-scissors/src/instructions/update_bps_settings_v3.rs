@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, BPS_SETTINGS_V3},
+    error::BPSError,
+    state::{BpsSettingsV3, SETTINGS_FLAGS},
+};
+
+#[derive(Accounts)]
+pub struct UpdateBpsSettingsV3<'info> {
+    #[account(
+        mut,
+        seeds = [BPS_SETTINGS_V3.as_ref()],
+        bump = bps_settings_v3.bump,
+    )]
+    pub bps_settings_v3: Box<Account<'info, BpsSettingsV3>>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Games keep the fees, payout, timer and refund flags they were created with, see
+/// `GameV3::snapshot_settings`. Open games count against the new `max_open_games` right away.
+pub fn update_bps_settings_v3(
+    ctx: Context<UpdateBpsSettingsV3>,
+    time_for_penalization: i64,
+    player_fee_lamports: u64,
+    payout_basis_points: u16,
+    relayer_fee_lamports: u64,
+    max_open_games: u32,
+    flags: u64,
+) -> Result<()> {
+    require!(
+        (1..=u32::MAX as i64).contains(&time_for_penalization),
+        BPSError::InvalidTimeForPenalization
+    );
+    require!(
+        (1..=10_000).contains(&payout_basis_points),
+        BPSError::InvalidPayoutBasisPoints
+    );
+    require!(flags & !SETTINGS_FLAGS == 0, BPSError::UnknownSettingsFlags);
+    let bps_settings = &mut ctx.accounts.bps_settings_v3;
+    let signer = &ctx.accounts.signer;
+    bps_settings.timers.time_for_penalization = time_for_penalization;
+    bps_settings.authority = signer.key();
+    bps_settings.fees.player_fee_lamports = player_fee_lamports;
+    bps_settings.fees.payout_basis_points = payout_basis_points;
+    bps_settings.fees.relayer_fee_lamports = relayer_fee_lamports;
+    bps_settings.limits.max_open_games = max_open_games;
+    bps_settings.flags = flags;
+    Ok(())
+}
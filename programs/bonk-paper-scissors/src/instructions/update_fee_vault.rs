@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, FEE_VAULT},
+    state::FeeVault,
+};
+
+#[derive(Accounts)]
+pub struct UpdateFeeVault<'info> {
+    #[account(
+        mut,
+        seeds = [FEE_VAULT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_fee_vault(ctx: Context<UpdateFeeVault>, destination: Pubkey) -> Result<()> {
+    ctx.accounts.fee_vault.destination = destination;
+    Ok(())
+}
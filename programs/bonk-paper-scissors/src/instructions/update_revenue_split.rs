@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, REVENUE_SPLIT},
+    error::BPSError,
+    state::{RevenueRecipient, RevenueSplit},
+};
+
+#[derive(Accounts)]
+pub struct UpdateRevenueSplit<'info> {
+    #[account(
+        mut,
+        seeds = [REVENUE_SPLIT.as_ref()],
+        bump = revenue_split.bump,
+    )]
+    pub revenue_split: Account<'info, RevenueSplit>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Replaces the recipients, the next sweep follows the new split.
+pub fn update_revenue_split(
+    ctx: Context<UpdateRevenueSplit>,
+    recipients: Vec<RevenueRecipient>,
+) -> Result<()> {
+    require!(
+        RevenueSplit::is_valid(&recipients),
+        BPSError::InvalidRevenueSplit
+    );
+    ctx.accounts.revenue_split.recipients = recipients;
+    Ok(())
+}
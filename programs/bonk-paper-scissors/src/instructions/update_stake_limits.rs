@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ADMIN, STAKE_LIMITS},
+    error::BPSError,
+    state::StakeLimits,
+};
+
+#[derive(Accounts)]
+pub struct UpdateStakeLimits<'info> {
+    #[account(
+        mut,
+        seeds = [STAKE_LIMITS.as_ref(), stake_limits.mint.as_ref()],
+        bump = stake_limits.bump,
+    )]
+    pub stake_limits: Account<'info, StakeLimits>,
+    #[account(
+        mut,
+        address = ADMIN
+    )]
+    pub signer: Signer<'info>,
+}
+
+/// Games already created keep their stake.
+pub fn update_stake_limits(
+    ctx: Context<UpdateStakeLimits>,
+    min_stake: u64,
+    max_stake: u64,
+    tiers: Vec<u64>,
+) -> Result<()> {
+    require!(
+        StakeLimits::is_valid(min_stake, max_stake, &tiers),
+        BPSError::InvalidStakeLimits
+    );
+    let stake_limits = &mut ctx.accounts.stake_limits;
+    stake_limits.min_stake = min_stake;
+    stake_limits.max_stake = max_stake;
+    stake_limits.tiers = tiers;
+    Ok(())
+}
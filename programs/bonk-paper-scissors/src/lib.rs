@@ -2,11 +2,12 @@ pub mod constants;
 pub mod error;
 pub mod instructions;
 pub mod state;
+pub mod utils;
 
 use anchor_lang::prelude::*;
 
 use instructions::*;
-use state::Choice;
+use state::{Choice, GameAudit, GameState, GameStatus, RevenueRecipient, SettlementPreview};
 
 declare_id!("bonk8yVf477u7s7nqttS6VXFTCjbV2S5MKxmojGAa4i");
 
@@ -21,8 +22,9 @@ pub mod bonk_paper_scissors {
         game_id: String,
         amount: u64,
         first_player_hash: [u8; 32], // Choice + Salt
+        use_relayer: bool,
     ) -> Result<()> {
-        instructions::first_player_move(ctx, game_id, amount, first_player_hash)
+        instructions::first_player_move(ctx, game_id, amount, first_player_hash, use_relayer)
     }
 
     /// Cancels a game and returns the funds to the first player.
@@ -34,24 +36,316 @@ pub mod bonk_paper_scissors {
     pub fn second_player_move(
         ctx: Context<SecondPlayerMove>,
         second_player_hash: [u8; 32], // Choice + Salt
+        use_relayer: bool,
     ) -> Result<()> {
-        instructions::second_player_move(ctx, second_player_hash)
+        instructions::second_player_move(ctx, second_player_hash, use_relayer)
     }
 
     /// This involves some hashing magic, but I'm a wizard ;).
-    pub fn reveal(ctx: Context<Reveal>, choice: Choice, salt: [u8; 32]) -> Result<()> {
+    pub fn reveal(ctx: Context<Reveal>, choice: u8, salt: [u8; 32]) -> Result<()> {
         instructions::reveal(ctx, choice, salt)
     }
 
+    /// Lets anyone reveal for a player, the commitment is what proves the reveal is authentic.
+    pub fn reveal_for(
+        ctx: Context<RevealFor>,
+        player: Pubkey,
+        choice: u8,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_for(ctx, player, choice, salt)
+    }
+
+    /// Authorizes an ephemeral keypair to reveal for the player, see `SessionToken`.
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_signer: Pubkey,
+        valid_until: i64,
+        max_stake: u64,
+        top_up_lamports: u64,
+    ) -> Result<()> {
+        instructions::create_session(ctx, session_signer, valid_until, max_stake, top_up_lamports)
+    }
+
+    /// Revokes a session token right away.
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        instructions::revoke_session(ctx)
+    }
+
     /// After both players have revealed, the game can be claimed.
+    /// Anyone can pay for the claim, so a session signer doesn't need a token for it.
     pub fn claim(ctx: Context<Claim>) -> Result<()> {
         instructions::claim(ctx)
     }
 
+    /// Matches the player against a queued opponent with the same stake, or joins the queue.
+    pub fn join_queue(
+        ctx: Context<JoinQueue>,
+        game_id: String,
+        amount: u64,
+        player_hash: [u8; 32], // Choice + Salt
+    ) -> Result<()> {
+        instructions::join_queue(ctx, game_id, amount, player_hash)
+    }
+
+    /// Leaves the matchmaking queue and returns the escrowed funds.
+    pub fn leave_queue(ctx: Context<LeaveQueue>) -> Result<()> {
+        instructions::leave_queue(ctx)
+    }
+
+    /// Opens a spectator side pool on a game waiting for its second player.
+    pub fn init_side_pool(ctx: Context<InitSidePool>) -> Result<()> {
+        instructions::init_side_pool(ctx)
+    }
+
+    /// Wagers on the final state of a game, players can't bet on their own games.
+    pub fn place_side_bet(
+        ctx: Context<PlaceSideBet>,
+        outcome: GameState,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::place_side_bet(ctx, outcome, amount)
+    }
+
+    /// Pays out a side bet pari-mutuel after the game is claimed, or refunds it.
+    pub fn claim_side_bet(ctx: Context<ClaimSideBet>) -> Result<()> {
+        instructions::claim_side_bet(ctx)
+    }
+
+    /// Offers the previous opponent a new game with the same mint and stake.
+    pub fn propose_rematch(
+        ctx: Context<ProposeRematch>,
+        game_id: String,
+        first_player_hash: [u8; 32], // Choice + Salt
+    ) -> Result<()> {
+        instructions::propose_rematch(ctx, game_id, first_player_hash)
+    }
+
+    /// The previous opponent joins the rematch, providing the second hash (choice + salt).
+    pub fn accept_rematch(
+        ctx: Context<AcceptRematch>,
+        second_player_hash: [u8; 32], // Choice + Salt
+    ) -> Result<()> {
+        instructions::accept_rematch(ctx, second_player_hash)
+    }
+
+    /// Returns the stake of a game nobody joined in time, the caller keeps the escrow rent.
+    pub fn expire_open_game(ctx: Context<ExpireOpenGame>) -> Result<()> {
+        instructions::expire_open_game(ctx)
+    }
+
+    /// Refunds a game nobody revealed in time, the caller keeps the escrow rent.
+    pub fn expire_unrevealed_game(ctx: Context<ExpireUnrevealedGame>) -> Result<()> {
+        instructions::expire_unrevealed_game(ctx)
+    }
+
+    /// Rewrites a Borsh game as a zero-copy `GameV3`, anyone can pay for it.
+    pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+        instructions::migrate_game(ctx)
+    }
+
+    /// Compares the escrow balances of a game with its state, the result is in the return data.
+    pub fn audit_game(ctx: Context<AuditGame>) -> Result<GameAudit> {
+        instructions::audit_game(ctx)
+    }
+
+    /// Outcome, forfeits and deadlines of a game as the program sees them, in the return data.
+    pub fn get_game_status(ctx: Context<GetGameStatus>) -> Result<GameStatus> {
+        instructions::get_game_status(ctx)
+    }
+
+    /// What `claim` would pay each player right now, in the return data.
+    pub fn preview_settlement(ctx: Context<PreviewSettlement>) -> Result<SettlementPreview> {
+        instructions::preview_settlement(ctx)
+    }
+
     pub fn admin_unwind_stale_game(ctx: Context<AdminUnwindStaleGame>) -> Result<()> {
         instructions::admin_unwind_stale_game(ctx)
     }
 
+    /// Creates a game staked in native SOL, held as lamports in a vault owned by the game.
+    pub fn first_player_move_sol(
+        ctx: Context<FirstPlayerMoveSol>,
+        game_id: String,
+        amount: u64,
+        first_player_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::first_player_move_sol(ctx, game_id, amount, first_player_hash)
+    }
+
+    pub fn second_player_move_sol(
+        ctx: Context<SecondPlayerMoveSol>,
+        second_player_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::second_player_move_sol(ctx, second_player_hash)
+    }
+
+    /// Cancels a native SOL game and returns the stake to the first player.
+    pub fn cancel_sol_game(ctx: Context<CancelSolGame>) -> Result<()> {
+        instructions::cancel_sol_game(ctx)
+    }
+
+    /// Claims a native SOL game, the fee goes to the fee vault instead of being burned.
+    pub fn claim_sol(ctx: Context<ClaimSol>) -> Result<()> {
+        instructions::claim_sol(ctx)
+    }
+
+    /// Refunds a native SOL game nobody joined or revealed in time, the caller keeps the vault rent.
+    pub fn expire_sol_game(ctx: Context<ExpireSolGame>) -> Result<()> {
+        instructions::expire_sol_game(ctx)
+    }
+
+    pub fn admin_unwind_stale_sol_game(ctx: Context<AdminUnwindStaleSolGame>) -> Result<()> {
+        instructions::admin_unwind_stale_sol_game(ctx)
+    }
+
+    /// Creates a game staking a single NFT, which must be a verified member of an allowed collection.
+    pub fn first_player_move_nft(
+        ctx: Context<FirstPlayerMoveNft>,
+        game_id: String,
+        first_player_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::first_player_move_nft(ctx, game_id, first_player_hash)
+    }
+
+    pub fn second_player_move_nft(
+        ctx: Context<SecondPlayerMoveNft>,
+        second_player_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::second_player_move_nft(ctx, second_player_hash)
+    }
+
+    /// Cancels an NFT game and returns the NFT to the first player.
+    pub fn cancel_nft_game(ctx: Context<CancelNftGame>) -> Result<()> {
+        instructions::cancel_nft_game(ctx)
+    }
+
+    /// Claims an NFT game, the winner gets both NFTs.
+    pub fn claim_nft(ctx: Context<ClaimNft>) -> Result<()> {
+        instructions::claim_nft(ctx)
+    }
+
+    /// Returns the NFTs of a game nobody joined or revealed in time, the caller keeps the escrow rent.
+    pub fn expire_nft_game(ctx: Context<ExpireNftGame>) -> Result<()> {
+        instructions::expire_nft_game(ctx)
+    }
+
+    pub fn admin_unwind_stale_nft_game(ctx: Context<AdminUnwindStaleNftGame>) -> Result<()> {
+        instructions::admin_unwind_stale_nft_game(ctx)
+    }
+
+    /// Opens an elimination table for 3 to 8 players, the creator takes the first seat.
+    pub fn create_table(
+        ctx: Context<CreateTable>,
+        table_id: String,
+        max_seats: u8,
+        max_rounds: u8,
+        stake: u64,
+    ) -> Result<()> {
+        instructions::create_table(ctx, table_id, max_seats, max_rounds, stake)
+    }
+
+    /// Takes a seat at a table, the first round starts once every seat is taken.
+    pub fn join_table(ctx: Context<JoinTable>) -> Result<()> {
+        instructions::join_table(ctx)
+    }
+
+    /// Leaves a table that hasn't started and returns the stake.
+    pub fn leave_table(ctx: Context<LeaveTable>) -> Result<()> {
+        instructions::leave_table(ctx)
+    }
+
+    pub fn commit_table_move(
+        ctx: Context<CommitTableMove>,
+        player_hash: [u8; 32], // Choice + Salt
+    ) -> Result<()> {
+        instructions::commit_table_move(ctx, player_hash)
+    }
+
+    pub fn reveal_table_move(
+        ctx: Context<RevealTableMove>,
+        choice: Choice,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_table_move(ctx, choice, salt)
+    }
+
+    /// Forfeits the seats that missed their deadline, anyone can crank it.
+    pub fn advance_table(ctx: Context<AdvanceTable>) -> Result<()> {
+        instructions::advance_table(ctx)
+    }
+
+    /// Splits the pot of a settled table between the survivors.
+    pub fn claim_table<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClaimTable<'info>>,
+    ) -> Result<()> {
+        instructions::claim_table(ctx)
+    }
+
+    /// Opens registration for a single elimination tournament with a prize split table.
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        tournament_id: String,
+        entry_fee: u64,
+        max_entrants: u8,
+        registration_ends_at: i64,
+        prize_split: Vec<u16>,
+    ) -> Result<()> {
+        instructions::create_tournament(
+            ctx,
+            tournament_id,
+            entry_fee,
+            max_entrants,
+            registration_ends_at,
+            prize_split,
+        )
+    }
+
+    pub fn register_for_tournament(ctx: Context<RegisterForTournament>) -> Result<()> {
+        instructions::register_for_tournament(ctx)
+    }
+
+    /// Pairs the first round once registration is over, anyone can crank it.
+    pub fn start_tournament(ctx: Context<StartTournament>) -> Result<()> {
+        instructions::start_tournament(ctx)
+    }
+
+    /// The home player of a bracket match creates its game, reveals are shared with other games.
+    pub fn first_player_move_tournament(
+        ctx: Context<FirstPlayerMoveTournament>,
+        game_id: String,
+        match_index: u8,
+        first_player_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::first_player_move_tournament(ctx, game_id, match_index, first_player_hash)
+    }
+
+    pub fn second_player_move_tournament(
+        ctx: Context<SecondPlayerMoveTournament>,
+        second_player_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::second_player_move_tournament(ctx, second_player_hash)
+    }
+
+    /// Settles a bracket game and advances its winner.
+    pub fn claim_tournament_match(ctx: Context<ClaimTournamentMatch>) -> Result<()> {
+        instructions::claim_tournament_match(ctx)
+    }
+
+    /// Advances the opponent of a player who didn't show up for a match in time.
+    pub fn forfeit_tournament_match(
+        ctx: Context<ForfeitTournamentMatch>,
+        match_index: u8,
+    ) -> Result<()> {
+        instructions::forfeit_tournament_match(ctx, match_index)
+    }
+
+    /// Pays an entrant its prize, or refunds its entry fee if the tournament was cancelled.
+    pub fn claim_tournament_prize(ctx: Context<ClaimTournamentPrize>) -> Result<()> {
+        instructions::claim_tournament_prize(ctx)
+    }
+
+    /// Removed, fails with `InstructionRemoved`.
     pub fn init_bps_settings_v2(
         ctx: Context<InitBpsSettingsV2>,
         time_for_penalization: i64,
@@ -60,6 +354,7 @@ pub mod bonk_paper_scissors {
         instructions::init_bps_settings_v2(ctx, time_for_penalization, game_fee_lamports)
     }
 
+    /// Removed, fails with `InstructionRemoved`.
     pub fn update_bps_settings_v2(
         ctx: Context<UpdateBpsSettingsV2>,
         time_for_penalization: i64,
@@ -67,4 +362,128 @@ pub mod bonk_paper_scissors {
     ) -> Result<()> {
         instructions::update_bps_settings_v2(ctx, time_for_penalization, player_fee_lamports)
     }
+
+    /// Moves the settings to `BpsSettingsV3`, which every instruction reads from then on.
+    pub fn migrate_settings_v2_to_v3(ctx: Context<MigrateSettingsV2ToV3>) -> Result<()> {
+        instructions::migrate_settings_v2_to_v3(ctx)
+    }
+
+    pub fn update_bps_settings_v3(
+        ctx: Context<UpdateBpsSettingsV3>,
+        time_for_penalization: i64,
+        player_fee_lamports: u64,
+        payout_basis_points: u16,
+        relayer_fee_lamports: u64,
+        max_open_games: u32,
+        flags: u64,
+    ) -> Result<()> {
+        instructions::update_bps_settings_v3(
+            ctx,
+            time_for_penalization,
+            player_fee_lamports,
+            payout_basis_points,
+            relayer_fee_lamports,
+            max_open_games,
+            flags,
+        )
+    }
+
+    pub fn allow_collection(ctx: Context<AllowCollection>, collection: Pubkey) -> Result<()> {
+        instructions::allow_collection(ctx, collection)
+    }
+
+    pub fn disallow_collection(ctx: Context<DisallowCollection>) -> Result<()> {
+        instructions::disallow_collection(ctx)
+    }
+
+    pub fn create_season(
+        ctx: Context<CreateSeason>,
+        season_id: String,
+        starts_at: i64,
+        ends_at: i64,
+        stake_per_point: u64,
+        reward_split: Vec<u16>,
+    ) -> Result<()> {
+        instructions::create_season(
+            ctx,
+            season_id,
+            starts_at,
+            ends_at,
+            stake_per_point,
+            reward_split,
+        )
+    }
+
+    /// Adds to the reward pool of a season, anyone can sponsor one.
+    pub fn fund_season(ctx: Context<FundSeason>, amount: u64) -> Result<()> {
+        instructions::fund_season(ctx, amount)
+    }
+
+    pub fn close_season(ctx: Context<CloseSeason>) -> Result<()> {
+        instructions::close_season(ctx)
+    }
+
+    /// Pays a player ranked on the leaderboard of a closed season.
+    pub fn claim_season_reward(ctx: Context<ClaimSeasonReward>) -> Result<()> {
+        instructions::claim_season_reward(ctx)
+    }
+
+    /// Adds a game variant, `beats` holds the choices every choice beats as a bitmask.
+    pub fn create_ruleset(
+        ctx: Context<CreateRuleset>,
+        ruleset_id: String,
+        choice_count: u8,
+        beats: Vec<u8>,
+    ) -> Result<()> {
+        instructions::create_ruleset(ctx, ruleset_id, choice_count, beats)
+    }
+
+    /// Sets the stakes games of a mint can be created with.
+    pub fn init_stake_limits(
+        ctx: Context<InitStakeLimits>,
+        min_stake: u64,
+        max_stake: u64,
+        tiers: Vec<u64>,
+    ) -> Result<()> {
+        instructions::init_stake_limits(ctx, min_stake, max_stake, tiers)
+    }
+
+    pub fn update_stake_limits(
+        ctx: Context<UpdateStakeLimits>,
+        min_stake: u64,
+        max_stake: u64,
+        tiers: Vec<u64>,
+    ) -> Result<()> {
+        instructions::update_stake_limits(ctx, min_stake, max_stake, tiers)
+    }
+
+    /// Fees collect in a vault the admin sweeps to `destination`.
+    pub fn init_fee_vault(ctx: Context<InitFeeVault>, destination: Pubkey) -> Result<()> {
+        instructions::init_fee_vault(ctx, destination)
+    }
+
+    pub fn update_fee_vault(ctx: Context<UpdateFeeVault>, destination: Pubkey) -> Result<()> {
+        instructions::update_fee_vault(ctx, destination)
+    }
+
+    pub fn sweep_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SweepFees<'info>>,
+    ) -> Result<()> {
+        instructions::sweep_fees(ctx)
+    }
+
+    /// Shares swept fees between up to 5 recipients, weighted in basis points.
+    pub fn init_revenue_split(
+        ctx: Context<InitRevenueSplit>,
+        recipients: Vec<RevenueRecipient>,
+    ) -> Result<()> {
+        instructions::init_revenue_split(ctx, recipients)
+    }
+
+    pub fn update_revenue_split(
+        ctx: Context<UpdateRevenueSplit>,
+        recipients: Vec<RevenueRecipient>,
+    ) -> Result<()> {
+        instructions::update_revenue_split(ctx, recipients)
+    }
 }
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// A Metaplex collection whose NFTs can be wagered, created by the admin.
+#[account]
+pub struct AllowedCollection {
+    pub bump: u8,
+    pub collection: Pubkey,
+}
+
+impl AllowedCollection {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 // collection
+    }
+
+    pub fn new(bump: u8, collection: Pubkey) -> Self {
+        Self { bump, collection }
+    }
+}
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+use crate::state::PAYOUT_BASIS_POINTS;
+
+/// Retired, only read by `migrate_settings_v2_to_v3`.
 #[account]
 pub struct BpsSettingsV2 {
     pub bump: u8,
@@ -30,3 +33,113 @@ impl BpsSettingsV2 {
         }
     }
 }
+
+pub const BPS_SETTINGS_VERSION: u8 = 3;
+// Until the admin changes it with `update_bps_settings_v3`.
+pub const DEFAULT_MAX_OPEN_GAMES: u32 = 10;
+
+// `BpsSettingsV3::flags` bits, games snapshot them when created.
+pub const REFUND_ON_CANCEL: u64 = 1 << 0; // cancelling a game nobody joined, or expiring it, refunds the creator's fee
+pub const REFUND_ON_FORFEIT: u64 = 1 << 1; // the player who revealed gets their fee back when the opponent forfeits
+pub const SETTINGS_FLAGS: u64 = REFUND_ON_CANCEL | REFUND_ON_FORFEIT;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FeeSettings {
+    pub player_fee_lamports: u64,
+    // Share of each stake paid to the winner, snapshotted by every game.
+    pub payout_basis_points: u16,
+    // Deposited by players who opt in to `reveal_for`, paid to whoever submits it.
+    pub relayer_fee_lamports: u64,
+    pub reserved: [u8; 14],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TimerSettings {
+    pub time_for_penalization: i64,
+    pub reserved: [u8; 24],
+}
+
+// Stakes are limited per mint, by `StakeLimits`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LimitSettings {
+    // Games a player can have going at once, see `OpenGames`.
+    pub max_open_games: u32,
+    pub reserved: [u8; 28],
+}
+
+/// Replaces `BpsSettingsV2`, which `migrate_settings_v2_to_v3` copies over. Each group has
+/// reserved space for new parameters, so they don't need a new PDA or a new version of the
+/// account.
+#[account]
+pub struct BpsSettingsV3 {
+    pub bump: u8,
+    // `BPS_SETTINGS_VERSION` of the layout the account was written with.
+    pub version: u8,
+    pub authority: Pubkey,
+    pub fees: FeeSettings,
+    pub timers: TimerSettings,
+    pub limits: LimitSettings,
+    // `SETTINGS_FLAGS` bits.
+    pub flags: u64,
+    pub reserved: [u8; 64],
+}
+
+impl BpsSettingsV3 {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        1 + // version
+        32 + // authority
+        8 + 24 + // fees
+        8 + 24 + // timers
+        32 + // limits
+        8 + // flags
+        64 // reserved
+    }
+
+    pub fn new(
+        bump: u8,
+        authority: Pubkey,
+        player_fee_lamports: u64,
+        time_for_penalization: i64,
+    ) -> Self {
+        Self {
+            bump,
+            version: BPS_SETTINGS_VERSION,
+            authority,
+            fees: FeeSettings {
+                player_fee_lamports,
+                payout_basis_points: PAYOUT_BASIS_POINTS,
+                relayer_fee_lamports: 0,
+                reserved: [0; 14],
+            },
+            timers: TimerSettings {
+                time_for_penalization,
+                reserved: [0; 24],
+            },
+            limits: LimitSettings {
+                max_open_games: DEFAULT_MAX_OPEN_GAMES,
+                reserved: [0; 28],
+            },
+            flags: REFUND_ON_CANCEL | REFUND_ON_FORFEIT,
+            reserved: [0; 64],
+        }
+    }
+
+    pub fn from_v2(bump: u8, bps_settings_v2: &BpsSettingsV2) -> Self {
+        Self::new(
+            bump,
+            bps_settings_v2.authority,
+            bps_settings_v2.player_fee_lamports,
+            bps_settings_v2.time_for_penalization,
+        )
+    }
+
+    pub fn refund_on_cancel(&self) -> bool {
+        self.flags & REFUND_ON_CANCEL != 0
+    }
+
+    pub fn refund_on_forfeit(&self) -> bool {
+        self.flags & REFUND_ON_FORFEIT != 0
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+/// Collects the fees the program takes, on top of its own rent, until the admin sweeps them to
+/// `destination`.
+#[account]
+pub struct FeeVault {
+    pub bump: u8,
+    pub destination: Pubkey,
+    // Lifetime totals for accounting, the vault holds the difference.
+    pub total_collected: u64,
+    pub total_swept: u64,
+}
+
+impl FeeVault {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // destination
+        8 + // total_collected
+        8 // total_swept
+    }
+
+    pub fn new(bump: u8, destination: Pubkey) -> Self {
+        Self {
+            bump,
+            destination,
+            total_collected: 0,
+            total_swept: 0,
+        }
+    }
+
+    pub fn collect(&mut self, amount: u64) {
+        self.total_collected += amount;
+    }
+
+    pub fn sweep(&mut self, amount: u64) {
+        self.total_swept += amount;
+    }
+}
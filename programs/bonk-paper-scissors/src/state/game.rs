@@ -1,13 +1,29 @@
 use anchor_lang::prelude::*;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+use crate::{
+    error::BPSError,
+    state::{BpsSettingsV3, Game, OpenGames, CLASSIC_BEATS, CLASSIC_CHOICE_COUNT, MAX_CHOICES},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum Choice {
     Bonk,
     Paper,
     Scissors,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+impl Choice {
+    pub fn beats(&self, other: &Choice) -> bool {
+        matches!(
+            (self, other),
+            (Choice::Bonk, Choice::Scissors)
+                | (Choice::Paper, Choice::Bonk)
+                | (Choice::Scissors, Choice::Paper)
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum GameState {
     CreatedAndWaitingForStart,
     StartedAndWaitingForReveal,
@@ -16,69 +32,183 @@ pub enum GameState {
     Draw,
 }
 
-#[account]
-pub struct Game {
-    pub bump: u8,
-    pub game_id: String,
-    pub mint: Pubkey,
-    pub amount_to_match: u64,
+impl GameState {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(GameState::CreatedAndWaitingForStart),
+            1 => Some(GameState::StartedAndWaitingForReveal),
+            2 => Some(GameState::FirstPlayerWon),
+            3 => Some(GameState::SecondPlayerWon),
+            4 => Some(GameState::Draw),
+            _ => None,
+        }
+    }
+}
+
+/// Bump this when new fields change invariants.
+pub const GAME_VERSION: u8 = 3;
+/// Share of each stake paid to the winner, the rest is burned or sent to the fee vault.
+pub const PAYOUT_BASIS_POINTS: u16 = 9_000;
+/// Game ids are PDA seeds, which can't be longer than this.
+pub const MAX_GAME_ID_LEN: usize = 32;
 
+// Presence flags, the matching fields are zeroed while their flag is unset.
+pub const SECOND_PLAYER_JOINED: u8 = 1 << 0; // second_player, second_player_hash, second_player_escrow_address, started_at
+pub const FIRST_PLAYER_REVEALED: u8 = 1 << 1; // first_player_choice, first_player_revealed_at
+pub const SECOND_PLAYER_REVEALED: u8 = 1 << 2; // second_player_choice, second_player_revealed_at
+pub const GAME_SETTLED: u8 = 1 << 3; // winner, loser, amount_won, amount_burned, drawn_at
+pub const HAS_PREVIOUS_GAME: u8 = 1 << 4; // previous_game
+
+// Mode flags.
+pub const NATIVE_SOL: u8 = 1 << 5; // stakes are lamports in a `SolVault`, mint is unused
+pub const NFT: u8 = 1 << 6; // stakes are single NFTs of `collection`, mint is the first player's
+pub const TOURNAMENT: u8 = 1 << 7; // bracket match of `tournament`, nothing is staked and mint is unused
+
+// Players whose `OpenGames` counts this game, stored in `open_game_slots`.
+pub const FIRST_PLAYER_SLOT: u8 = 1 << 0;
+pub const SECOND_PLAYER_SLOT: u8 = 1 << 1;
+
+// Fee policy bits, stored in `fee_policy`.
+pub const FEES_ESCROWED: u8 = 1 << 0; // the game holds the players' fees until it is over
+pub const FEE_REFUND_ON_CANCEL: u8 = 1 << 1; // cancels and open game expiries refund the first player
+pub const FEE_REFUND_ON_FORFEIT: u8 = 1 << 2; // a forfeit refunds the player who revealed
+
+/// Fixed layout game, read in place instead of being Borsh-deserialized. Every field sits at the
+/// offset in the `GameV3::*_OFFSET` constants (discriminator included), so `getProgramAccounts`
+/// memcmp filters can rely on them. Fields are ordered by alignment, leaving no implicit padding.
+#[account(zero_copy)]
+pub struct GameV3 {
+    pub mint: Pubkey,
     pub first_player: Pubkey,
     pub first_player_hash: [u8; 32],
     pub first_player_escrow_address: Pubkey,
-    pub first_player_choice: Option<Choice>,
-    pub first_player_revealed_at: Option<i64>,
-
-    pub second_player: Option<Pubkey>,
-    pub second_player_hash: Option<[u8; 32]>,
-    pub second_player_escrow_address: Option<Pubkey>,
-    pub second_player_choice: Option<Choice>,
-    pub second_player_revealed_at: Option<i64>,
-
-    pub winner: Option<Pubkey>,
-    pub loser: Option<Pubkey>,
-    pub amount_won: Option<u64>,
-    pub amount_burned: Option<u64>,
-    pub drawn_at: Option<i64>,
-
-    pub game_state: GameState,
+    pub second_player: Pubkey,
+    pub second_player_hash: [u8; 32],
+    pub second_player_escrow_address: Pubkey,
+    pub winner: Pubkey,
+    pub loser: Pubkey,
+    pub previous_game: Pubkey,
+    // Right padded with zeroes, the seed is the first `game_id_len` bytes.
+    pub game_id: [u8; 32],
+
+    pub amount_to_match: u64,
+    pub first_player_revealed_at: i64,
+    pub second_player_revealed_at: i64,
+    pub amount_won: u64,
+    pub amount_burned: u64,
+    pub drawn_at: i64,
     pub created_at: i64,
+    pub started_at: i64,
+
+    pub bump: u8,
+    pub game_id_len: u8,
+    pub game_state: u8,
+    pub first_player_choice: u8,
+    pub second_player_choice: u8,
+    pub flags: u8,
+    pub version: u8,
+    // `FEE_*` bits, snapshotted from the `BpsSettingsV3` flags when the game is created.
+    pub fee_policy: u8,
+
+    // NFT games only.
+    pub collection: Pubkey,
+    pub second_player_mint: Pubkey,
+    // Tournament games only.
+    pub tournament: Pubkey,
+
+    // Copied from the `Ruleset` the game was created with, zeroed for Bonk-Paper-Scissors.
+    pub choice_count: u8,
+    pub beats: [u8; 8],
+
+    // Zero for games created before open games were counted.
+    pub open_game_slots: u8,
+
+    // Settings the game is settled with, snapshotted when it is created and joined so updating
+    // them doesn't change the rules of a live game. Zero for games created before snapshots,
+    // which fall back to the current settings.
+    pub payout_basis_points: u16,
+    pub time_for_penalization: u32,
+    pub first_player_fee_lamports: u64,
+    pub second_player_fee_lamports: u64,
+
+    // Deposited by a player who opted in to relayed reveals, paid to the relayer who reveals for
+    // them and refunded otherwise.
+    pub first_player_relayer_fee_lamports: u64,
+    pub second_player_relayer_fee_lamports: u64,
 }
 
-impl Game {
+impl GameV3 {
+    pub const MINT_OFFSET: usize = 8;
+    pub const FIRST_PLAYER_OFFSET: usize = 40;
+    pub const SECOND_PLAYER_OFFSET: usize = 136;
+    pub const WINNER_OFFSET: usize = 232;
+    pub const PREVIOUS_GAME_OFFSET: usize = 296;
+    pub const GAME_ID_OFFSET: usize = 328;
+    pub const AMOUNT_TO_MATCH_OFFSET: usize = 360;
+    pub const CREATED_AT_OFFSET: usize = 408;
+    pub const GAME_STATE_OFFSET: usize = 426;
+    pub const FLAGS_OFFSET: usize = 429;
+    pub const VERSION_OFFSET: usize = 430;
+    pub const COLLECTION_OFFSET: usize = 432;
+    pub const TOURNAMENT_OFFSET: usize = 496;
+    pub const CHOICE_COUNT_OFFSET: usize = 528;
+    pub const PAYOUT_BASIS_POINTS_OFFSET: usize = 538;
+
     pub fn size() -> usize {
         8 + // Discriminator
-        1 + // bump
-        (4 + 32) + // game_id
         32 + // mint
-        8 + // amount_to_match
-
         32 + // first_player
         32 + // first_player_hash
         32 + // first_player_escrow_address
-        (1 + 1) + // first_player_choice
-        (1 + 8) + // first_player_revealed_at
-
-        (1 + 32) + // second_player
-        (1 + 32) + // second_player_hash
-        (1 + 32) + // second_player_escrow_address
-        (1 + 1) + // second_player_choice
-        (1 + 8)+ // second_player_revealed_at
-
-        (1 + 32) + // winner
-        (1 + 32) + // loser
-        (1 + 8) + // amount_won
-        (1 + 8) + // amount_burned
-        (1 + 8) + // drawn_at
-
-        (1) + // game_state
+        32 + // second_player
+        32 + // second_player_hash
+        32 + // second_player_escrow_address
+        32 + // winner
+        32 + // loser
+        32 + // previous_game
+        32 + // game_id
+
+        8 + // amount_to_match
+        8 + // first_player_revealed_at
+        8 + // second_player_revealed_at
+        8 + // amount_won
+        8 + // amount_burned
+        8 + // drawn_at
         8 + // created_at
-        256 // padding
+        8 + // started_at
+
+        1 + // bump
+        1 + // game_id_len
+        1 + // game_state
+        1 + // first_player_choice
+        1 + // second_player_choice
+        1 + // flags
+        1 + // version
+        1 + // fee_policy
+
+        32 + // collection
+        32 + // second_player_mint
+        32 + // tournament
+
+        1 + // choice_count
+        MAX_CHOICES as usize + // beats
+
+        1 + // open_game_slots
+
+        2 + // payout_basis_points
+        4 + // time_for_penalization
+        8 + // first_player_fee_lamports
+        8 + // second_player_fee_lamports
+
+        8 + // first_player_relayer_fee_lamports
+        8 // second_player_relayer_fee_lamports
     }
 
+    /// `game_id` must fit in `MAX_GAME_ID_LEN` bytes, which the game PDA derivation enforces.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         bump: u8,
-        game_id: String,
+        game_id: &str,
         mint: Pubkey,
         amount_to_match: u64,
         created_at: i64,
@@ -86,32 +216,276 @@ impl Game {
         first_player_hash: [u8; 32],
         first_player_escrow_address: Pubkey,
     ) -> Self {
+        let mut game_id_bytes = [0u8; 32];
+        game_id_bytes[..game_id.len()].copy_from_slice(game_id.as_bytes());
         Self {
-            bump,
-            game_id,
             mint,
-            amount_to_match,
-            created_at,
-
             first_player,
             first_player_hash,
             first_player_escrow_address,
-            first_player_choice: None,
-            first_player_revealed_at: None,
+            second_player: Pubkey::default(),
+            second_player_hash: [0; 32],
+            second_player_escrow_address: Pubkey::default(),
+            winner: Pubkey::default(),
+            loser: Pubkey::default(),
+            previous_game: Pubkey::default(),
+            game_id: game_id_bytes,
+
+            amount_to_match,
+            first_player_revealed_at: 0,
+            second_player_revealed_at: 0,
+            amount_won: 0,
+            amount_burned: 0,
+            drawn_at: 0,
+            created_at,
+            started_at: 0,
+
+            bump,
+            game_id_len: game_id.len() as u8,
+            game_state: GameState::CreatedAndWaitingForStart as u8,
+            first_player_choice: 0,
+            second_player_choice: 0,
+            flags: 0,
+            version: GAME_VERSION,
+            fee_policy: 0,
+
+            collection: Pubkey::default(),
+            second_player_mint: Pubkey::default(),
+            tournament: Pubkey::default(),
+
+            choice_count: 0,
+            beats: [0; MAX_CHOICES as usize],
 
-            second_player: None,
-            second_player_hash: None,
-            second_player_escrow_address: None,
-            second_player_choice: None,
-            second_player_revealed_at: None,
+            open_game_slots: 0,
 
-            winner: None,
-            loser: None,
-            amount_won: None,
-            amount_burned: None,
-            drawn_at: None,
+            payout_basis_points: 0,
+            time_for_penalization: 0,
+            first_player_fee_lamports: 0,
+            second_player_fee_lamports: 0,
+
+            first_player_relayer_fee_lamports: 0,
+            second_player_relayer_fee_lamports: 0,
+        }
+    }
 
-            game_state: GameState::CreatedAndWaitingForStart,
+    /// Carries a Borsh `Game` over, both the versioned and the pre-versioning layouts.
+    pub fn from_legacy(game: &Game) -> Self {
+        let mut new_game = Self::new(
+            game.bump,
+            &game.game_id,
+            game.mint,
+            game.amount_to_match,
+            game.created_at,
+            game.first_player,
+            game.first_player_hash,
+            game.first_player_escrow_address,
+        );
+        if let (Some(second_player), Some(second_player_hash), Some(escrow), Some(started_at)) = (
+            game.second_player,
+            game.second_player_hash,
+            game.second_player_escrow_address,
+            game.started_at_or_created_at(),
+        ) {
+            new_game.set_second_player(second_player, second_player_hash, escrow, started_at);
+        }
+        if let (Some(choice), Some(revealed_at)) =
+            (game.first_player_choice, game.first_player_revealed_at)
+        {
+            new_game.set_first_player_choice(choice as u8, revealed_at);
+        }
+        if let (Some(choice), Some(revealed_at)) =
+            (game.second_player_choice, game.second_player_revealed_at)
+        {
+            new_game.set_second_player_choice(choice as u8, revealed_at);
+        }
+        if let (Some(winner), Some(loser), Some(amount_won), Some(amount_burned), Some(drawn_at)) = (
+            game.winner,
+            game.loser,
+            game.amount_won,
+            game.amount_burned,
+            game.drawn_at,
+        ) {
+            new_game.set_claim_fields(winner, loser, amount_won, amount_burned, drawn_at);
+        }
+        if let Some(previous_game) = game.previous_game {
+            new_game.set_previous_game(previous_game);
+        }
+        new_game.set_game_state(game.game_state);
+        new_game
+    }
+
+    fn has(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+
+    pub fn game_id(&self) -> &[u8] {
+        &self.game_id[..self.game_id_len as usize]
+    }
+
+    pub fn game_state(&self) -> GameState {
+        // Only ever written through `set_game_state`.
+        GameState::from_u8(self.game_state).unwrap()
+    }
+
+    pub fn set_game_state(&mut self, game_state: GameState) {
+        self.game_state = game_state as u8;
+    }
+
+    pub fn second_player(&self) -> Option<Pubkey> {
+        self.has(SECOND_PLAYER_JOINED).then_some(self.second_player)
+    }
+
+    pub fn second_player_hash(&self) -> Option<[u8; 32]> {
+        self.has(SECOND_PLAYER_JOINED)
+            .then_some(self.second_player_hash)
+    }
+
+    pub fn second_player_escrow_address(&self) -> Option<Pubkey> {
+        self.has(SECOND_PLAYER_JOINED)
+            .then_some(self.second_player_escrow_address)
+    }
+
+    pub fn started_at(&self) -> Option<i64> {
+        self.has(SECOND_PLAYER_JOINED).then_some(self.started_at)
+    }
+
+    pub fn first_player_choice(&self) -> Option<u8> {
+        self.has(FIRST_PLAYER_REVEALED)
+            .then_some(self.first_player_choice)
+    }
+
+    pub fn first_player_revealed_at(&self) -> Option<i64> {
+        self.has(FIRST_PLAYER_REVEALED)
+            .then_some(self.first_player_revealed_at)
+    }
+
+    pub fn second_player_choice(&self) -> Option<u8> {
+        self.has(SECOND_PLAYER_REVEALED)
+            .then_some(self.second_player_choice)
+    }
+
+    pub fn second_player_revealed_at(&self) -> Option<i64> {
+        self.has(SECOND_PLAYER_REVEALED)
+            .then_some(self.second_player_revealed_at)
+    }
+
+    pub fn winner(&self) -> Option<Pubkey> {
+        self.has(GAME_SETTLED).then_some(self.winner)
+    }
+
+    pub fn loser(&self) -> Option<Pubkey> {
+        self.has(GAME_SETTLED).then_some(self.loser)
+    }
+
+    pub fn previous_game(&self) -> Option<Pubkey> {
+        self.has(HAS_PREVIOUS_GAME).then_some(self.previous_game)
+    }
+
+    pub fn set_previous_game(&mut self, previous_game: Pubkey) {
+        self.previous_game = previous_game;
+        self.flags |= HAS_PREVIOUS_GAME;
+    }
+
+    pub fn is_native_sol(&self) -> bool {
+        self.has(NATIVE_SOL)
+    }
+
+    pub fn set_native_sol(&mut self) {
+        self.flags |= NATIVE_SOL;
+    }
+
+    pub fn is_nft(&self) -> bool {
+        self.has(NFT)
+    }
+
+    pub fn set_nft(&mut self, collection: Pubkey) {
+        self.collection = collection;
+        self.flags |= NFT;
+    }
+
+    /// Mint of the NFT the second player escrowed, `mint` is the first player's.
+    pub fn second_player_mint(&self) -> Option<Pubkey> {
+        (self.has(NFT) && self.has(SECOND_PLAYER_JOINED)).then_some(self.second_player_mint)
+    }
+
+    pub fn set_second_player_mint(&mut self, second_player_mint: Pubkey) {
+        self.second_player_mint = second_player_mint;
+    }
+
+    pub fn is_tournament(&self) -> bool {
+        self.has(TOURNAMENT)
+    }
+
+    pub fn set_tournament(&mut self, tournament: Pubkey) {
+        self.tournament = tournament;
+        self.flags |= TOURNAMENT;
+    }
+
+    /// Plays the game with a `Ruleset` instead of Bonk-Paper-Scissors.
+    pub fn set_rules(&mut self, choice_count: u8, beats: [u8; MAX_CHOICES as usize]) {
+        self.choice_count = choice_count;
+        self.beats = beats;
+    }
+
+    pub fn choice_count(&self) -> u8 {
+        match self.choice_count {
+            0 => CLASSIC_CHOICE_COUNT,
+            choice_count => choice_count,
+        }
+    }
+
+    /// Whether `choice` beats `other` under the game's rules.
+    pub fn beats(&self, choice: u8, other: u8) -> bool {
+        let beats = match self.choice_count {
+            0 => &CLASSIC_BEATS,
+            _ => &self.beats,
+        };
+        beats[choice as usize] & 1 << other != 0
+    }
+
+    pub fn holds_open_game_slot(&self, slot: u8) -> bool {
+        self.open_game_slots & slot != 0
+    }
+
+    /// Marks the slot the player took in their `OpenGames`, which ending the game gives back.
+    pub fn set_open_game_slot(&mut self, slot: u8) {
+        self.open_game_slots |= slot;
+    }
+
+    /// Gives the players back the slots this game holds, once it is over. The `OpenGames` of a
+    /// player is required exactly when the game holds its slot.
+    pub fn release_open_game_slots<'info>(
+        &mut self,
+        first_player_open_games: Option<&mut Account<'info, OpenGames>>,
+        second_player_open_games: Option<&mut Account<'info, OpenGames>>,
+    ) -> Result<()> {
+        for (slot, open_games) in [
+            (FIRST_PLAYER_SLOT, first_player_open_games),
+            (SECOND_PLAYER_SLOT, second_player_open_games),
+        ] {
+            if self.holds_open_game_slot(slot) {
+                let open_games = open_games.ok_or_else(|| error!(BPSError::MissingOpenGames))?;
+                open_games.release()?;
+                self.open_game_slots &= !slot;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_settled(&self) -> bool {
+        matches!(
+            self.game_state(),
+            GameState::FirstPlayerWon | GameState::SecondPlayerWon | GameState::Draw
+        )
+    }
+
+    pub fn opponent_of(&self, player: &Pubkey) -> Option<Pubkey> {
+        if *player == self.first_player {
+            self.second_player()
+        } else if Some(*player) == self.second_player() {
+            Some(self.first_player)
+        } else {
+            None
         }
     }
 
@@ -120,49 +494,208 @@ impl Game {
         second_player: Pubkey,
         second_player_hash: [u8; 32],
         second_player_escrow_address: Pubkey,
+        started_at: i64,
     ) {
-        self.second_player = Some(second_player);
-        self.second_player_hash = Some(second_player_hash);
-        self.second_player_escrow_address = Some(second_player_escrow_address);
-        self.game_state = GameState::StartedAndWaitingForReveal;
+        self.second_player = second_player;
+        self.second_player_hash = second_player_hash;
+        self.second_player_escrow_address = second_player_escrow_address;
+        self.started_at = started_at;
+        self.flags |= SECOND_PLAYER_JOINED;
+        self.set_game_state(GameState::StartedAndWaitingForReveal);
     }
 
-    pub fn set_first_player_choice(&mut self, choice: Choice, revealed_at: i64) {
-        self.first_player_choice = Some(choice);
-        self.first_player_revealed_at = Some(revealed_at);
+    pub fn set_first_player_choice(&mut self, choice: u8, revealed_at: i64) {
+        self.first_player_choice = choice;
+        self.first_player_revealed_at = revealed_at;
+        self.flags |= FIRST_PLAYER_REVEALED;
     }
 
-    pub fn set_second_player_choice(&mut self, choice: Choice, revealed_at: i64) {
-        self.second_player_choice = Some(choice);
-        self.second_player_revealed_at = Some(revealed_at);
+    pub fn set_second_player_choice(&mut self, choice: u8, revealed_at: i64) {
+        self.second_player_choice = choice;
+        self.second_player_revealed_at = revealed_at;
+        self.flags |= SECOND_PLAYER_REVEALED;
     }
 
     // A Player forfeits if seven days has passed since it's opponent revealed its choice and he didn't reveal his choice
     pub fn did_first_player_forfeit(&self, now: i64, time_for_expiry: i64) -> bool {
-        // This player did reveal, can't forfeit.
-        if self.first_player_revealed_at.is_some() {
-            return false;
+        match (
+            self.first_player_revealed_at(),
+            self.second_player_revealed_at(),
+        ) {
+            // This player didn't reveal, but seven days has passed since the other player revealed.
+            (None, Some(second_player_revealed_at)) => {
+                second_player_revealed_at + time_for_expiry < now
+            }
+            // This player did reveal, or no player has revealed.
+            _ => false,
         }
-        // No player has revealed.
-        if self.first_player_revealed_at.is_none() && self.second_player_revealed_at.is_none() {
-            return false;
-        }
-        // This player didn't reveal, but seven days has passed since the other player revealed.
-        return self.second_player_revealed_at.unwrap() + time_for_expiry < now;
     }
 
     // A Player forfeits if seven days has passed since it's opponent revealed its choice and he didn't reveal his choice
     pub fn did_second_player_forfeit(&self, now: i64, time_for_expiry: i64) -> bool {
-        // This player did reveal, can't forfeit.
-        if self.second_player_revealed_at.is_some() {
-            return false;
+        match (
+            self.second_player_revealed_at(),
+            self.first_player_revealed_at(),
+        ) {
+            // This player didn't reveal, but seven days has passed since the other player revealed.
+            (None, Some(first_player_revealed_at)) => {
+                first_player_revealed_at + time_for_expiry < now
+            }
+            // This player did reveal, or no player has revealed.
+            _ => false,
+        }
+    }
+
+    /// Both players revealed, or one of them forfeited by not revealing in time.
+    pub fn is_claimable(&self, now: i64, time_for_expiry: i64) -> bool {
+        self.game_state() == GameState::StartedAndWaitingForReveal
+            && ((self.has(FIRST_PLAYER_REVEALED) && self.has(SECOND_PLAYER_REVEALED))
+                || self.did_first_player_forfeit(now, time_for_expiry)
+                || self.did_second_player_forfeit(now, time_for_expiry))
+    }
+
+    /// Final state of a claimable game, a forfeit counts as a loss.
+    pub fn outcome(&self, now: i64, time_for_expiry: i64) -> GameState {
+        let first_player_wins = self.did_second_player_forfeit(now, time_for_expiry)
+            || match (self.first_player_choice(), self.second_player_choice()) {
+                (Some(first_player_choice), Some(second_player_choice)) => {
+                    self.beats(first_player_choice, second_player_choice)
+                }
+                _ => false,
+            };
+        let second_player_wins = self.did_first_player_forfeit(now, time_for_expiry)
+            || match (self.second_player_choice(), self.first_player_choice()) {
+                (Some(second_player_choice), Some(first_player_choice)) => {
+                    self.beats(second_player_choice, first_player_choice)
+                }
+                _ => false,
+            };
+        if first_player_wins {
+            GameState::FirstPlayerWon
+        } else if second_player_wins {
+            GameState::SecondPlayerWon
+        } else {
+            GameState::Draw
+        }
+    }
+
+    /// Snapshots the settings the game is settled with. Called when the game is created.
+    pub fn snapshot_settings(
+        &mut self,
+        bps_settings: &BpsSettingsV3,
+        first_player_fee_lamports: u64,
+    ) -> Result<()> {
+        self.payout_basis_points = bps_settings.fees.payout_basis_points;
+        // Kept non-zero, zero meaning the game predates snapshots.
+        self.time_for_penalization = u32::try_from(bps_settings.timers.time_for_penalization)
+            .ok()
+            .filter(|time_for_penalization| *time_for_penalization > 0)
+            .ok_or_else(|| error!(BPSError::InvalidTimeForPenalization))?;
+        self.first_player_fee_lamports = first_player_fee_lamports;
+        Ok(())
+    }
+
+    /// Records the fee the second player paid to join.
+    pub fn set_second_player_fee(&mut self, second_player_fee_lamports: u64) {
+        self.second_player_fee_lamports = second_player_fee_lamports;
+    }
+
+    /// The game holds the fees it is paid from now on, refunding them as the policy says.
+    pub fn escrow_fees(&mut self, refund_on_cancel: bool, refund_on_forfeit: bool) {
+        self.fee_policy = FEES_ESCROWED;
+        if refund_on_cancel {
+            self.fee_policy |= FEE_REFUND_ON_CANCEL;
+        }
+        if refund_on_forfeit {
+            self.fee_policy |= FEE_REFUND_ON_FORFEIT;
+        }
+    }
+
+    /// Lamports the game holds for the players' fees, on top of its rent.
+    pub fn escrowed_fees(&self) -> u64 {
+        if self.fee_policy & FEES_ESCROWED == 0 {
+            return 0;
+        }
+        self.first_player_fee_lamports + self.second_player_fee_lamports
+    }
+
+    /// Whether each player's fee is refunded rather than kept, for a game ending now. Only a
+    /// game nobody joined, or one a player forfeited, refunds anything.
+    pub fn fee_refunds(&self, now: i64, time_for_expiry: i64) -> (bool, bool) {
+        if self.game_state() == GameState::CreatedAndWaitingForStart {
+            return (self.fee_policy & FEE_REFUND_ON_CANCEL != 0, false);
+        }
+        let refund_on_forfeit = self.fee_policy & FEE_REFUND_ON_FORFEIT != 0;
+        (
+            refund_on_forfeit && self.did_second_player_forfeit(now, time_for_expiry),
+            refund_on_forfeit && self.did_first_player_forfeit(now, time_for_expiry),
+        )
+    }
+
+    /// Hands over the fees the game holds for each player, so they are only paid out once.
+    pub fn take_escrowed_fees(&mut self) -> (u64, u64) {
+        if self.fee_policy & FEES_ESCROWED == 0 {
+            return (0, 0);
+        }
+        self.fee_policy &= !FEES_ESCROWED;
+        (
+            self.first_player_fee_lamports,
+            self.second_player_fee_lamports,
+        )
+    }
+
+    /// Records the relayer fee the player holding `slot` deposited in the game.
+    pub fn deposit_relayer_fee(&mut self, slot: u8, relayer_fee_lamports: u64) {
+        if slot == FIRST_PLAYER_SLOT {
+            self.first_player_relayer_fee_lamports = relayer_fee_lamports;
+        } else {
+            self.second_player_relayer_fee_lamports = relayer_fee_lamports;
         }
-        // No player has revealed.
-        if self.first_player_revealed_at.is_none() && self.second_player_revealed_at.is_none() {
-            return false;
+    }
+
+    /// Hands over the relayer fee `player` deposited, so it is only paid out once. Zero when they
+    /// didn't opt in to relayed reveals.
+    pub fn take_relayer_fee(&mut self, player: Pubkey) -> u64 {
+        if player == self.first_player {
+            std::mem::take(&mut self.first_player_relayer_fee_lamports)
+        } else if Some(player) == self.second_player() {
+            std::mem::take(&mut self.second_player_relayer_fee_lamports)
+        } else {
+            0
+        }
+    }
+
+    /// The snapshotted penalization time, or `current` for games created before snapshots.
+    pub fn time_for_penalization_or(&self, current: i64) -> i64 {
+        match self.time_for_penalization {
+            0 => current,
+            time_for_penalization => time_for_penalization as i64,
+        }
+    }
+
+    /// What each stake pays the winner, and what is left of it, burned or sent to the fee vault
+    /// for native SOL games.
+    pub fn payout_split(&self) -> (u64, u64) {
+        let payout_basis_points = match self.payout_basis_points {
+            0 => PAYOUT_BASIS_POINTS,
+            payout_basis_points => payout_basis_points,
+        };
+        let amount_to_pay =
+            (self.amount_to_match as u128 * payout_basis_points as u128 / 10_000) as u64;
+        (amount_to_pay, self.amount_to_match - amount_to_pay)
+    }
+
+    /// Nobody joined, or nobody revealed, within `time_for_expiry`.
+    pub fn is_expired(&self, now: i64, time_for_expiry: i64) -> bool {
+        match self.game_state() {
+            GameState::CreatedAndWaitingForStart => self.created_at + time_for_expiry < now,
+            GameState::StartedAndWaitingForReveal => {
+                !self.has(FIRST_PLAYER_REVEALED)
+                    && !self.has(SECOND_PLAYER_REVEALED)
+                    && self.started_at + time_for_expiry < now
+            }
+            _ => false,
         }
-        // This player didn't reveal, but seven days has passed since the other player revealed.
-        return self.first_player_revealed_at.unwrap() + time_for_expiry < now;
     }
 
     pub fn set_claim_fields(
@@ -173,10 +706,11 @@ impl Game {
         amount_burned: u64,
         drawn_at: i64,
     ) {
-        self.winner = Some(winner);
-        self.loser = Some(loser);
-        self.amount_won = Some(amount_won);
-        self.amount_burned = Some(amount_burned);
-        self.drawn_at = Some(drawn_at);
+        self.winner = winner;
+        self.loser = loser;
+        self.amount_won = amount_won;
+        self.amount_burned = amount_burned;
+        self.drawn_at = drawn_at;
+        self.flags |= GAME_SETTLED;
     }
 }
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::state::GameState;
+
+/// Balance of an account holding stakes of a game, next to what the game state says it should
+/// hold. Token escrows are counted in tokens, SOL vaults in lamports above their rent.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct EscrowAudit {
+    pub address: Pubkey,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl EscrowAudit {
+    /// Left behind by a settlement, e.g. the rounding of the payout.
+    pub fn surplus(&self) -> u64 {
+        self.actual.saturating_sub(self.expected)
+    }
+
+    /// Owed to the players but missing from the account.
+    pub fn shortfall(&self) -> u64 {
+        self.expected.saturating_sub(self.actual)
+    }
+}
+
+/// Returned by `audit_game`. Tournament games stake nothing and have no escrows.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct GameAudit {
+    pub game_state: GameState,
+    pub escrows: Vec<EscrowAudit>,
+}
+
+impl GameAudit {
+    pub fn is_solvent(&self) -> bool {
+        self.escrows.iter().all(|escrow| escrow.shortfall() == 0)
+    }
+
+    /// Every escrow holds exactly what the game state implies.
+    pub fn is_balanced(&self) -> bool {
+        self.escrows
+            .iter()
+            .all(|escrow| escrow.actual == escrow.expected)
+    }
+}
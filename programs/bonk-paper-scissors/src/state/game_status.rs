@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{GameState, GameV3};
+
+/// Returned by `get_game_status`, computed with the same checks `claim` and the expiries use.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GameStatus {
+    pub game_state: GameState,
+    // Cluster time the status was computed at.
+    pub now: i64,
+    pub first_player_revealed: bool,
+    pub second_player_revealed: bool,
+    pub first_player_forfeited: bool,
+    pub second_player_forfeited: bool,
+    pub claimable: bool,
+    pub expired: bool,
+    // How `claim` would settle the game right now, once claimable.
+    pub outcome: Option<GameState>,
+    // Past this, nobody having joined or revealed, the game can be expired.
+    pub expires_at: Option<i64>,
+    // Past this, the player who hasn't revealed forfeits.
+    pub forfeits_at: Option<i64>,
+}
+
+impl GameStatus {
+    pub fn new(game: &GameV3, now: i64, time_for_expiry: i64) -> Self {
+        let game_state = game.game_state();
+        let first_player_revealed_at = game.first_player_revealed_at();
+        let second_player_revealed_at = game.second_player_revealed_at();
+        let claimable = game.is_claimable(now, time_for_expiry);
+        let expires_at = match (
+            game_state,
+            first_player_revealed_at,
+            second_player_revealed_at,
+        ) {
+            (GameState::CreatedAndWaitingForStart, _, _) => Some(game.created_at + time_for_expiry),
+            (GameState::StartedAndWaitingForReveal, None, None) => {
+                Some(game.started_at + time_for_expiry)
+            }
+            _ => None,
+        };
+        let forfeits_at = match (
+            game_state,
+            first_player_revealed_at,
+            second_player_revealed_at,
+        ) {
+            (GameState::StartedAndWaitingForReveal, Some(revealed_at), None)
+            | (GameState::StartedAndWaitingForReveal, None, Some(revealed_at)) => {
+                Some(revealed_at + time_for_expiry)
+            }
+            _ => None,
+        };
+
+        Self {
+            game_state,
+            now,
+            first_player_revealed: first_player_revealed_at.is_some(),
+            second_player_revealed: second_player_revealed_at.is_some(),
+            first_player_forfeited: game.did_first_player_forfeit(now, time_for_expiry),
+            second_player_forfeited: game.did_second_player_forfeit(now, time_for_expiry),
+            claimable,
+            expired: game.is_expired(now, time_for_expiry),
+            outcome: claimable.then(|| game.outcome(now, time_for_expiry)),
+            expires_at,
+            forfeits_at,
+        }
+    }
+}
+
+/// Returned by `preview_settlement`, what `claim` would pay if it ran now.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SettlementPreview {
+    pub outcome: GameState,
+    pub winner: Option<Pubkey>,
+    // What reaches each player after transfer fees, in NFTs for NFT games.
+    pub first_player_payout: u64,
+    pub second_player_payout: u64,
+    // Sent to the fee vault instead for native SOL games.
+    pub amount_burned: u64,
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Choice, GameState};
+
+/// Borsh layout games were stored with before `GameV3`. Only read by `migrate_game`, which
+/// rewrites them in place; `version` is 0 for accounts created before it existed.
+#[account]
+pub struct Game {
+    pub bump: u8,
+    pub game_id: String,
+    pub mint: Pubkey,
+    pub amount_to_match: u64,
+
+    pub first_player: Pubkey,
+    pub first_player_hash: [u8; 32],
+    pub first_player_escrow_address: Pubkey,
+    pub first_player_choice: Option<Choice>,
+    pub first_player_revealed_at: Option<i64>,
+
+    pub second_player: Option<Pubkey>,
+    pub second_player_hash: Option<[u8; 32]>,
+    pub second_player_escrow_address: Option<Pubkey>,
+    pub second_player_choice: Option<Choice>,
+    pub second_player_revealed_at: Option<i64>,
+
+    pub winner: Option<Pubkey>,
+    pub loser: Option<Pubkey>,
+    pub amount_won: Option<u64>,
+    pub amount_burned: Option<u64>,
+    pub drawn_at: Option<i64>,
+
+    pub game_state: GameState,
+    pub created_at: i64,
+
+    // ----- V2 -----
+    pub previous_game: Option<Pubkey>,
+    pub started_at: Option<i64>,
+    pub version: u8,
+}
+
+impl Game {
+    pub fn size() -> usize {
+        8 + // Discriminator
+        1 + // bump
+        (4 + 32) + // game_id
+        32 + // mint
+        8 + // amount_to_match
+
+        32 + // first_player
+        32 + // first_player_hash
+        32 + // first_player_escrow_address
+        (1 + 1) + // first_player_choice
+        (1 + 8) + // first_player_revealed_at
+
+        (1 + 32) + // second_player
+        (1 + 32) + // second_player_hash
+        (1 + 32) + // second_player_escrow_address
+        (1 + 1) + // second_player_choice
+        (1 + 8)+ // second_player_revealed_at
+
+        (1 + 32) + // winner
+        (1 + 32) + // loser
+        (1 + 8) + // amount_won
+        (1 + 8) + // amount_burned
+        (1 + 8) + // drawn_at
+
+        (1) + // game_state
+        8 + // created_at
+
+        (1 + 32) + // previous_game
+        (1 + 8) + // started_at
+        1 + // version
+        213 // padding
+    }
+
+    /// Games started before `started_at` existed fall back to their creation.
+    pub fn started_at_or_created_at(&self) -> Option<i64> {
+        match self.game_state {
+            GameState::CreatedAndWaitingForStart => None,
+            _ => Some(self.started_at.unwrap_or(self.created_at)),
+        }
+    }
+}
@@ -1,5 +1,37 @@
+pub mod allowed_collection;
 pub mod bps_settings;
+pub mod fee_vault;
 pub mod game;
+pub mod game_audit;
+pub mod game_status;
+pub mod legacy_game;
+pub mod open_games;
+pub mod queue_ticket;
+pub mod revenue_split;
+pub mod ruleset;
+pub mod season;
+pub mod session_token;
+pub mod side_pool;
+pub mod sol_vault;
+pub mod stake_limits;
+pub mod table;
+pub mod tournament;
 
+pub use allowed_collection::*;
 pub use bps_settings::*;
+pub use fee_vault::*;
 pub use game::*;
+pub use game_audit::*;
+pub use game_status::*;
+pub use legacy_game::*;
+pub use open_games::*;
+pub use queue_ticket::*;
+pub use revenue_split::*;
+pub use ruleset::*;
+pub use season::*;
+pub use session_token::*;
+pub use side_pool::*;
+pub use sol_vault::*;
+pub use stake_limits::*;
+pub use table::*;
+pub use tournament::*;
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::error::BPSError;
+
+/// Games a player created or joined that aren't over yet, capped by `max_open_games` in
+/// `BpsSettingsV3`. Created on the player's first game and never closed. Instructions that end a
+/// game take both players' `OpenGames` as optional accounts, required for each player whose slot
+/// the game holds.
+#[account]
+pub struct OpenGames {
+    pub bump: u8,
+    pub player: Pubkey,
+    pub count: u32,
+}
+
+impl OpenGames {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // player
+        4 // count
+    }
+
+    /// Takes a slot for a new game, `bump` and `player` fill in the account the first time.
+    pub fn open(&mut self, bump: u8, player: Pubkey, max_open_games: u32) -> Result<()> {
+        self.bump = bump;
+        self.player = player;
+        require_gt!(max_open_games, self.count, BPSError::TooManyOpenGames);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Gives back the slot of a game that is over.
+    pub fn release(&mut self) -> Result<()> {
+        self.count = self
+            .count
+            .checked_sub(1)
+            .ok_or_else(|| error!(BPSError::NoOpenGames))?;
+        Ok(())
+    }
+}
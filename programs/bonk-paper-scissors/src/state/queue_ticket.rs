@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+/// A player waiting in the matchmaking queue for an opponent with the same mint and stake. The
+/// ticket holds the player's fee on top of its rent, so it's returned along with the rent.
+#[account]
+pub struct QueueTicket {
+    pub bump: u8,
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub player_hash: [u8; 32],
+    pub escrow_address: Pubkey,
+    pub created_at: i64,
+}
+
+impl QueueTicket {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // player
+        32 + // mint
+        8 + // amount
+        32 + // player_hash
+        32 + // escrow_address
+        8 // created_at
+    }
+
+    pub fn new(
+        bump: u8,
+        player: Pubkey,
+        mint: Pubkey,
+        amount: u64,
+        player_hash: [u8; 32],
+        escrow_address: Pubkey,
+        created_at: i64,
+    ) -> Self {
+        Self {
+            bump,
+            player,
+            mint,
+            amount,
+            player_hash,
+            escrow_address,
+            created_at,
+        }
+    }
+
+    /// Fee `queue_ticket` holds on top of its rent, zero for tickets queued before it held one.
+    pub fn held_fee(queue_ticket: &AccountInfo) -> Result<u64> {
+        let rent = Rent::get()?.minimum_balance(QueueTicket::size());
+        Ok(queue_ticket.lamports().saturating_sub(rent))
+    }
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+use crate::state::PRIZE_SPLIT_TOTAL;
+
+pub const MAX_REVENUE_RECIPIENTS: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RevenueRecipient {
+    pub address: Pubkey,
+    pub basis_points: u16,
+}
+
+impl RevenueRecipient {
+    pub fn size() -> usize {
+        32 + // address
+        2 // basis_points
+    }
+}
+
+/// How `sweep_fees` shares the fee vault between up to `MAX_REVENUE_RECIPIENTS` wallets, set by
+/// the admin. The weights add up to 10,000 basis points.
+#[account]
+pub struct RevenueSplit {
+    pub bump: u8,
+    pub recipients: Vec<RevenueRecipient>,
+}
+
+impl RevenueSplit {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        4 + MAX_REVENUE_RECIPIENTS * RevenueRecipient::size() // recipients
+    }
+
+    pub fn new(bump: u8, recipients: Vec<RevenueRecipient>) -> Self {
+        Self { bump, recipients }
+    }
+
+    pub fn is_valid(recipients: &[RevenueRecipient]) -> bool {
+        !recipients.is_empty()
+            && recipients.len() <= MAX_REVENUE_RECIPIENTS
+            && recipients
+                .iter()
+                .all(|recipient| recipient.basis_points > 0)
+            && recipients
+                .iter()
+                .map(|recipient| recipient.basis_points as u32)
+                .sum::<u32>()
+                == PRIZE_SPLIT_TOTAL as u32
+            && recipients.iter().enumerate().all(|(index, recipient)| {
+                recipients[..index]
+                    .iter()
+                    .all(|other| other.address != recipient.address)
+            })
+    }
+
+    /// The split at `revenue_split`, or `None` while the admin hasn't created it.
+    pub fn load(revenue_split: &AccountInfo) -> Result<Option<Self>> {
+        if revenue_split.owner != &crate::ID || revenue_split.data_is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(RevenueSplit::try_deserialize(
+            &mut &revenue_split.try_borrow_data()?[..],
+        )?))
+    }
+
+    /// Whether `accounts` are the recipients, in order, and can be paid.
+    pub fn matches(&self, accounts: &[AccountInfo]) -> bool {
+        self.recipients.len() == accounts.len()
+            && self
+                .recipients
+                .iter()
+                .zip(accounts)
+                .all(|(recipient, account)| {
+                    recipient.address == account.key() && account.is_writable
+                })
+    }
+
+    /// Each recipient's share of `amount`, rounded down.
+    pub fn shares(&self, amount: u64) -> Vec<u64> {
+        self.recipients
+            .iter()
+            .map(|recipient| {
+                (amount as u128 * recipient.basis_points as u128 / PRIZE_SPLIT_TOTAL as u128) as u64
+            })
+            .collect()
+    }
+}
+
+/// Emitted by every `sweep_fees`, with what each wallet got out of the fee vault.
+#[event]
+pub struct RevenueDistributed {
+    pub amount: u64,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub timestamp: i64,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MAX_GAME_ID_LEN;
+
+pub const MIN_CHOICES: u8 = 2;
+pub const MAX_CHOICES: u8 = 8;
+
+/// Bonk-Paper-Scissors, what games without a ruleset play.
+pub const CLASSIC_CHOICE_COUNT: u8 = 3;
+pub const CLASSIC_BEATS: [u8; MAX_CHOICES as usize] = [
+    0b100, // Bonk beats Scissors
+    0b001, // Paper beats Bonk
+    0b010, // Scissors beats Paper
+    0, 0, 0, 0, 0,
+];
+
+/// A game variant created by the admin, choices are revealed as their index. Bit `j` of
+/// `beats[i]` is set when choice `i` beats choice `j`, pairs where neither is set are draws.
+#[account]
+pub struct Ruleset {
+    pub bump: u8,
+    pub ruleset_id: String,
+    pub choice_count: u8,
+    pub beats: [u8; 8],
+}
+
+impl Ruleset {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        4 + MAX_GAME_ID_LEN + // ruleset_id
+        1 + // choice_count
+        MAX_CHOICES as usize // beats
+    }
+
+    pub fn new(
+        bump: u8,
+        ruleset_id: String,
+        choice_count: u8,
+        beats: [u8; MAX_CHOICES as usize],
+    ) -> Self {
+        Self {
+            bump,
+            ruleset_id,
+            choice_count,
+            beats,
+        }
+    }
+
+    /// Every row only covers existing choices, no choice beats itself and no two choices beat
+    /// each other.
+    pub fn is_valid(choice_count: u8, beats: &[u8]) -> bool {
+        if !(MIN_CHOICES..=MAX_CHOICES).contains(&choice_count)
+            || beats.len() != choice_count as usize
+        {
+            return false;
+        }
+        let existing = (1u16 << choice_count) - 1;
+        (0..choice_count).all(|choice| {
+            let row = beats[choice as usize];
+            row as u16 & !existing == 0
+                && row & 1 << choice == 0
+                && (0..choice_count)
+                    .all(|other| row & 1 << other == 0 || beats[other as usize] & 1 << choice == 0)
+        })
+    }
+}
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BPSError,
+    state::{GameState, GameV3, MAX_GAME_ID_LEN, PRIZE_SPLIT_TOTAL},
+};
+
+pub const MAX_LEADERBOARD_LEN: usize = 10;
+pub const WIN_POINTS: u64 = 100;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SeasonState {
+    Active,
+    Closed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,
+    pub points: u64,
+    pub wins: u32,
+    pub paid: bool,
+}
+
+impl LeaderboardEntry {
+    pub fn size() -> usize {
+        32 + // player
+        8 + // points
+        4 + // wins
+        1 // paid
+    }
+}
+
+/// Ranks the winners of games claimed between `starts_at` and `ends_at` with `mint`. A win is
+/// worth `WIN_POINTS` plus a point for every `stake_per_point` staked. Only the top
+/// `MAX_LEADERBOARD_LEN` players are kept, a player pushed off the board loses its points.
+/// Once closed, the ranked players split the vault following `reward_split`.
+#[account]
+pub struct Season {
+    pub bump: u8,
+    pub season_id: String,
+    pub mint: Pubkey,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub stake_per_point: u64,
+    pub reward_split: Vec<u16>,
+    pub state: SeasonState,
+    // Vault balance when the season was closed.
+    pub reward_pool: u64,
+    // Sorted by points, ties keep whoever got there first ahead.
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+impl Season {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        4 + MAX_GAME_ID_LEN + // season_id
+        32 + // mint
+        8 + // starts_at
+        8 + // ends_at
+        8 + // stake_per_point
+        4 + MAX_LEADERBOARD_LEN * 2 + // reward_split
+        1 + // state
+        8 + // reward_pool
+        4 + MAX_LEADERBOARD_LEN * LeaderboardEntry::size() // leaderboard
+    }
+
+    pub fn new(
+        bump: u8,
+        season_id: String,
+        mint: Pubkey,
+        starts_at: i64,
+        ends_at: i64,
+        stake_per_point: u64,
+        reward_split: Vec<u16>,
+    ) -> Self {
+        Self {
+            bump,
+            season_id,
+            mint,
+            starts_at,
+            ends_at,
+            stake_per_point,
+            reward_split,
+            state: SeasonState::Active,
+            reward_pool: 0,
+            leaderboard: vec![],
+        }
+    }
+
+    pub fn points_for(&self, amount_to_match: u64) -> u64 {
+        WIN_POINTS + amount_to_match / self.stake_per_point
+    }
+
+    /// Credits the winner of a game `claim` just settled, draws are worth nothing.
+    pub fn record_game(&mut self, game: &GameV3, now: i64) -> Result<()> {
+        require_keys_eq!(self.mint, game.mint, BPSError::SeasonMismatch);
+        require!(
+            self.state == SeasonState::Active && self.starts_at <= now && now <= self.ends_at,
+            BPSError::SeasonNotActive
+        );
+        let winner = match (game.game_state(), game.winner()) {
+            (GameState::FirstPlayerWon | GameState::SecondPlayerWon, Some(winner)) => winner,
+            _ => return Ok(()),
+        };
+        let points = self.points_for(game.amount_to_match);
+
+        let mut entry = match self
+            .leaderboard
+            .iter()
+            .position(|entry| entry.player == winner)
+        {
+            Some(index) => self.leaderboard.remove(index),
+            None => LeaderboardEntry {
+                player: winner,
+                points: 0,
+                wins: 0,
+                paid: false,
+            },
+        };
+        entry.points += points;
+        entry.wins += 1;
+        let rank = self
+            .leaderboard
+            .iter()
+            .position(|other| other.points < entry.points)
+            .unwrap_or(self.leaderboard.len());
+        self.leaderboard.insert(rank, entry);
+        self.leaderboard.truncate(MAX_LEADERBOARD_LEN);
+        Ok(())
+    }
+
+    /// Reward of the player ranked `rank`, ranks nobody reached go to the first place.
+    pub fn reward_of(&self, rank: usize) -> u64 {
+        let mut basis_points = self.reward_split.get(rank).copied().unwrap_or(0) as u64;
+        if rank == 0 {
+            basis_points += self
+                .reward_split
+                .iter()
+                .skip(self.leaderboard.len())
+                .map(|&basis_points| basis_points as u64)
+                .sum::<u64>();
+        }
+        (self.reward_pool as u128 * basis_points as u128 / PRIZE_SPLIT_TOTAL as u128) as u64
+    }
+
+    /// Every ranked player with a reward was paid, what is left in the vault is rounding dust.
+    pub fn is_paid_out(&self) -> bool {
+        (0..self.leaderboard.len())
+            .all(|rank| self.leaderboard[rank].paid || self.reward_of(rank) == 0)
+    }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::error::BPSError;
+
+/// Authorizes an ephemeral keypair to act for a player, so mobile clients don't prompt the wallet
+/// on every step. Closing the account revokes it.
+#[account]
+pub struct SessionToken {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub session_signer: Pubkey,
+    pub valid_until: i64,
+    pub max_stake: u64,
+    pub created_at: i64,
+}
+
+impl SessionToken {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // authority
+        32 + // session_signer
+        8 + // valid_until
+        8 + // max_stake
+        8 // created_at
+    }
+
+    pub fn new(
+        bump: u8,
+        authority: Pubkey,
+        session_signer: Pubkey,
+        valid_until: i64,
+        max_stake: u64,
+        created_at: i64,
+    ) -> Self {
+        Self {
+            bump,
+            authority,
+            session_signer,
+            valid_until,
+            max_stake,
+            created_at,
+        }
+    }
+
+    /// Returns the player a signer acts for, which is the session authority when a token is given.
+    pub fn resolve_player(
+        session_token: Option<&SessionToken>,
+        signer: Pubkey,
+        now: i64,
+        stake: u64,
+    ) -> Result<Pubkey> {
+        match session_token {
+            Some(session_token) => {
+                require_keys_eq!(
+                    session_token.session_signer,
+                    signer,
+                    BPSError::InvalidPlayer
+                );
+                require!(now < session_token.valid_until, BPSError::SessionExpired);
+                require!(
+                    stake <= session_token.max_stake,
+                    BPSError::SessionStakeExceeded
+                );
+                Ok(session_token.authority)
+            }
+            None => Ok(signer),
+        }
+    }
+}
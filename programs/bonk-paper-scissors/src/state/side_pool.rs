@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::SIDE_POOL, state::GameState};
+
+/// Pari-mutuel pool where spectators wager on the outcome of a `Game`.
+#[account]
+pub struct SidePool {
+    pub bump: u8,
+    pub game: Pubkey,
+    // Seeds the pool with the game, guards against the game PDA being closed and created again
+    // under the same id.
+    pub game_created_at: i64,
+    pub mint: Pubkey,
+    pub vault_address: Pubkey,
+    pub betting_closes_at: i64,
+    pub total_first_player_won: u64,
+    pub total_second_player_won: u64,
+    pub total_draw: u64,
+}
+
+impl SidePool {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // game
+        8 + // game_created_at
+        32 + // mint
+        32 + // vault_address
+        8 + // betting_closes_at
+        8 + // total_first_player_won
+        8 + // total_second_player_won
+        8 // total_draw
+    }
+
+    pub fn new(
+        bump: u8,
+        game: Pubkey,
+        game_created_at: i64,
+        mint: Pubkey,
+        vault_address: Pubkey,
+        betting_closes_at: i64,
+    ) -> Self {
+        Self {
+            bump,
+            game,
+            game_created_at,
+            mint,
+            vault_address,
+            betting_closes_at,
+            total_first_player_won: 0,
+            total_second_player_won: 0,
+            total_draw: 0,
+        }
+    }
+
+    /// Address of the pool opened on the game at `game` created at `game_created_at`, which
+    /// seeds the bets placed in it.
+    pub fn address(game: &Pubkey, game_created_at: i64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                SIDE_POOL.as_ref(),
+                game.as_ref(),
+                game_created_at.to_le_bytes().as_ref(),
+            ],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total_first_player_won + self.total_second_player_won + self.total_draw
+    }
+
+    pub fn total_for(&self, outcome: &GameState) -> u64 {
+        match outcome {
+            GameState::FirstPlayerWon => self.total_first_player_won,
+            GameState::SecondPlayerWon => self.total_second_player_won,
+            GameState::Draw => self.total_draw,
+            _ => 0,
+        }
+    }
+
+    pub fn add_bet(&mut self, outcome: &GameState, amount: u64) {
+        match outcome {
+            GameState::FirstPlayerWon => self.total_first_player_won += amount,
+            GameState::SecondPlayerWon => self.total_second_player_won += amount,
+            GameState::Draw => self.total_draw += amount,
+            _ => {}
+        }
+    }
+
+    /// Share of the whole pool owed to a winning bet of `amount` on `outcome`.
+    pub fn payout_for(&self, outcome: &GameState, amount: u64) -> u64 {
+        let winning_total = self.total_for(outcome);
+        if winning_total == 0 {
+            return 0;
+        }
+        (amount as u128 * self.total() as u128 / winning_total as u128) as u64
+    }
+}
+
+#[account]
+pub struct SideBet {
+    pub bump: u8,
+    pub side_pool: Pubkey,
+    pub bettor: Pubkey,
+    pub outcome: GameState,
+    pub amount: u64,
+}
+
+impl SideBet {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // side_pool
+        32 + // bettor
+        1 + // outcome
+        8 // amount
+    }
+
+    pub fn new(
+        bump: u8,
+        side_pool: Pubkey,
+        bettor: Pubkey,
+        outcome: GameState,
+        amount: u64,
+    ) -> Self {
+        Self {
+            bump,
+            side_pool,
+            bettor,
+            outcome,
+            amount,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Holds the lamports both players staked on a native SOL game, on top of its own rent.
+#[account]
+pub struct SolVault {
+    pub bump: u8,
+    pub game: Pubkey,
+}
+
+impl SolVault {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 // game
+    }
+
+    pub fn new(bump: u8, game: Pubkey) -> Self {
+        Self { bump, game }
+    }
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::error::BPSError;
+
+pub const MAX_STAKE_TIERS: usize = 8;
+
+/// Stakes games of `mint` can be created with, set by the admin, who allows a mint by creating
+/// them. When `tiers` isn't empty only those stakes are accepted, so lobbies can list fixed buckets.
+#[account]
+pub struct StakeLimits {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub min_stake: u64,
+    pub max_stake: u64,
+    // Ascending, each within `min_stake..=max_stake`.
+    pub tiers: Vec<u64>,
+}
+
+impl StakeLimits {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // mint
+        8 + // min_stake
+        8 + // max_stake
+        4 + MAX_STAKE_TIERS * 8 // tiers
+    }
+
+    pub fn new(bump: u8, mint: Pubkey, min_stake: u64, max_stake: u64, tiers: Vec<u64>) -> Self {
+        Self {
+            bump,
+            mint,
+            min_stake,
+            max_stake,
+            tiers,
+        }
+    }
+
+    pub fn is_valid(min_stake: u64, max_stake: u64, tiers: &[u64]) -> bool {
+        0 < min_stake
+            && min_stake <= max_stake
+            && tiers.len() <= MAX_STAKE_TIERS
+            && tiers.windows(2).all(|pair| pair[0] < pair[1])
+            && tiers
+                .iter()
+                .all(|tier| (min_stake..=max_stake).contains(tier))
+    }
+
+    pub fn check(&self, amount: u64) -> Result<()> {
+        require_gte!(amount, self.min_stake, BPSError::StakeBelowMinimum);
+        require_gte!(self.max_stake, amount, BPSError::StakeAboveMaximum);
+        require!(
+            self.tiers.is_empty() || self.tiers.contains(&amount),
+            BPSError::StakeNotInTiers
+        );
+        Ok(())
+    }
+
+    /// Checks `amount` against the limits at `stake_limits`, the PDA of the game's mint. Only mints
+    /// the admin set limits for can be staked, any other fails with `MintNotAllowed`.
+    pub fn enforce(stake_limits: &AccountInfo, amount: u64) -> Result<()> {
+        require!(
+            stake_limits.owner == &crate::ID && !stake_limits.data_is_empty(),
+            BPSError::MintNotAllowed
+        );
+        StakeLimits::try_deserialize(&mut &stake_limits.try_borrow_data()?[..])?.check(amount)
+    }
+}
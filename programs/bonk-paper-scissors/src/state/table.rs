@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Choice, MAX_GAME_ID_LEN};
+
+pub const MIN_SEATS: u8 = 3;
+pub const MAX_SEATS: u8 = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TableState {
+    Open,
+    Committing,
+    Revealing,
+    Settled,
+    Claimed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Seat {
+    pub player: Pubkey,
+    pub escrow_address: Pubkey,
+    // Both are cleared at the start of every round.
+    pub hash: Option<[u8; 32]>,
+    pub choice: Option<Choice>,
+    // The seat forfeits if it hasn't committed, or revealed, by then.
+    pub deadline: i64,
+    pub eliminated: bool,
+}
+
+impl Seat {
+    pub fn size() -> usize {
+        32 + // player
+        32 + // escrow_address
+        1 + 32 + // hash
+        1 + 1 + // choice
+        8 + // deadline
+        1 // eliminated
+    }
+
+    fn has_acted(&self, state: TableState) -> bool {
+        match state {
+            TableState::Committing => self.hash.is_some(),
+            TableState::Revealing => self.choice.is_some(),
+            _ => true,
+        }
+    }
+}
+
+/// Elimination rounds between `MIN_SEATS` and `MAX_SEATS` players, each escrowing `stake`. Every
+/// round the seats whose choice is beaten are eliminated, a round with one or all three choices
+/// is replayed. Survivors split the pot once one is left or `max_rounds` were played.
+#[account]
+pub struct Table {
+    pub bump: u8,
+    pub table_id: String,
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub stake: u64,
+    pub max_seats: u8,
+    pub max_rounds: u8,
+    pub round: u8,
+    pub state: TableState,
+    pub created_at: i64,
+    pub settled_at: Option<i64>,
+    pub payout_per_survivor: u64,
+    pub amount_burned: u64,
+    pub seats: Vec<Seat>,
+}
+
+impl Table {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        4 + MAX_GAME_ID_LEN + // table_id
+        32 + // creator
+        32 + // mint
+        8 + // stake
+        1 + // max_seats
+        1 + // max_rounds
+        1 + // round
+        1 + // state
+        8 + // created_at
+        1 + 8 + // settled_at
+        8 + // payout_per_survivor
+        8 + // amount_burned
+        4 + MAX_SEATS as usize * Seat::size() // seats
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        bump: u8,
+        table_id: String,
+        creator: Pubkey,
+        mint: Pubkey,
+        stake: u64,
+        max_seats: u8,
+        max_rounds: u8,
+        created_at: i64,
+    ) -> Self {
+        Self {
+            bump,
+            table_id,
+            creator,
+            mint,
+            stake,
+            max_seats,
+            max_rounds,
+            round: 0,
+            state: TableState::Open,
+            created_at,
+            settled_at: None,
+            payout_per_survivor: 0,
+            amount_burned: 0,
+            seats: vec![],
+        }
+    }
+
+    pub fn seat_index(&self, player: &Pubkey) -> Option<usize> {
+        self.seats.iter().position(|seat| seat.player == *player)
+    }
+
+    pub fn add_seat(&mut self, player: Pubkey, escrow_address: Pubkey) {
+        self.seats.push(Seat {
+            player,
+            escrow_address,
+            hash: None,
+            choice: None,
+            deadline: 0,
+            eliminated: false,
+        });
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.seats.len() == self.max_seats as usize
+    }
+
+    pub fn survivors(&self) -> impl Iterator<Item = &Seat> {
+        self.seats.iter().filter(|seat| !seat.eliminated)
+    }
+
+    fn restart_timers(&mut self, now: i64, time_for_penalization: i64) {
+        for seat in self.seats.iter_mut().filter(|seat| !seat.eliminated) {
+            seat.deadline = now + time_for_penalization;
+        }
+    }
+
+    pub fn start_round(&mut self, now: i64, time_for_penalization: i64) {
+        self.round += 1;
+        self.state = TableState::Committing;
+        for seat in self.seats.iter_mut() {
+            seat.hash = None;
+            seat.choice = None;
+        }
+        self.restart_timers(now, time_for_penalization);
+    }
+
+    /// Moves on once every surviving seat acted in the current phase.
+    pub fn advance(&mut self, now: i64, time_for_penalization: i64) {
+        let state = self.state;
+        if self.survivors().count() <= 1 {
+            self.settle(now);
+        } else if self.survivors().all(|seat| seat.has_acted(state)) {
+            match state {
+                TableState::Committing => {
+                    self.state = TableState::Revealing;
+                    self.restart_timers(now, time_for_penalization);
+                }
+                TableState::Revealing => self.resolve_round(now, time_for_penalization),
+                _ => {}
+            }
+        }
+    }
+
+    fn resolve_round(&mut self, now: i64, time_for_penalization: i64) {
+        let choices: Vec<Choice> = self.survivors().filter_map(|seat| seat.choice).collect();
+        let beaten = choices.iter().find(|choice| {
+            choices.iter().any(|other| other.beats(choice))
+                && !choices.iter().any(|other| choice.beats(other))
+        });
+        if let Some(beaten) = beaten.copied() {
+            for seat in self.seats.iter_mut() {
+                if seat.choice == Some(beaten) {
+                    seat.eliminated = true;
+                }
+            }
+        }
+        if self.survivors().count() == 1 || self.round >= self.max_rounds {
+            self.settle(now);
+        } else {
+            self.start_round(now, time_for_penalization);
+        }
+    }
+
+    /// Eliminates the seats that missed their deadline, returning how many. When every surviving
+    /// seat missed it, they all stay survivors and the table settles.
+    pub fn forfeit_expired_seats(&mut self, now: i64, time_for_penalization: i64) -> usize {
+        let state = self.state;
+        let expired = |seat: &Seat| !seat.has_acted(state) && seat.deadline < now;
+        let forfeits = self.survivors().filter(|seat| expired(seat)).count();
+        if forfeits == 0 {
+            return 0;
+        }
+        if forfeits == self.survivors().count() {
+            self.settle(now);
+        } else {
+            for seat in self.seats.iter_mut().filter(|seat| !seat.eliminated) {
+                if expired(seat) {
+                    seat.eliminated = true;
+                }
+            }
+            self.advance(now, time_for_penalization);
+        }
+        forfeits
+    }
+
+    fn settle(&mut self, now: i64) {
+        self.state = TableState::Settled;
+        self.settled_at = Some(now);
+    }
+}
@@ -0,0 +1,273 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MAX_GAME_ID_LEN;
+
+pub const MIN_ENTRANTS: u8 = 2;
+pub const MAX_ENTRANTS: u8 = 32;
+pub const MAX_PRIZE_PLACES: usize = 8;
+pub const PRIZE_SPLIT_TOTAL: u16 = 10_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TournamentState {
+    Registering,
+    Running,
+    Finished,
+    // Too few entrants registered, every entry fee is refunded.
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Entrant {
+    pub player: Pubkey,
+    // Round the entrant lost in, 0 while still in the bracket.
+    pub eliminated_in: u8,
+    pub paid: bool,
+}
+
+impl Entrant {
+    pub fn size() -> usize {
+        32 + // player
+        1 + // eliminated_in
+        1 // paid
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Match {
+    pub home: Pubkey,
+    pub away: Pubkey,
+    // Created by `home`, cleared after a draw so the match is replayed.
+    pub game: Option<Pubkey>,
+    pub winner: Option<Pubkey>,
+    // A player who hasn't shown up by then forfeits.
+    pub deadline: i64,
+}
+
+impl Match {
+    pub fn size() -> usize {
+        32 + // home
+        32 + // away
+        1 + 32 + // game
+        1 + 32 + // winner
+        8 // deadline
+    }
+}
+
+/// Single elimination bracket between up to `MAX_ENTRANTS` players, each paying `entry_fee` into
+/// the prize pool. Every round the remaining entrants are paired in registration order, the last
+/// one gets a bye when they are odd. `prize_split` holds the share of the pool, in basis points,
+/// of every place starting with the winner.
+#[account]
+pub struct Tournament {
+    pub bump: u8,
+    pub tournament_id: String,
+    pub organizer: Pubkey,
+    pub mint: Pubkey,
+    pub entry_fee: u64,
+    pub max_entrants: u8,
+    pub registration_ends_at: i64,
+    pub prize_split: Vec<u16>,
+    pub state: TournamentState,
+    pub round: u8,
+    pub prize_pool: u64,
+    pub entrants: Vec<Entrant>,
+    // Current round only.
+    pub matches: Vec<Match>,
+}
+
+impl Tournament {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        4 + MAX_GAME_ID_LEN + // tournament_id
+        32 + // organizer
+        32 + // mint
+        8 + // entry_fee
+        1 + // max_entrants
+        8 + // registration_ends_at
+        4 + MAX_PRIZE_PLACES * 2 + // prize_split
+        1 + // state
+        1 + // round
+        8 + // prize_pool
+        4 + MAX_ENTRANTS as usize * Entrant::size() + // entrants
+        4 + MAX_ENTRANTS as usize / 2 * Match::size() // matches
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        bump: u8,
+        tournament_id: String,
+        organizer: Pubkey,
+        mint: Pubkey,
+        entry_fee: u64,
+        max_entrants: u8,
+        registration_ends_at: i64,
+        prize_split: Vec<u16>,
+    ) -> Self {
+        Self {
+            bump,
+            tournament_id,
+            organizer,
+            mint,
+            entry_fee,
+            max_entrants,
+            registration_ends_at,
+            prize_split,
+            state: TournamentState::Registering,
+            round: 0,
+            prize_pool: 0,
+            entrants: vec![],
+            matches: vec![],
+        }
+    }
+
+    pub fn entrant_index(&self, player: &Pubkey) -> Option<usize> {
+        self.entrants
+            .iter()
+            .position(|entrant| entrant.player == *player)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.entrants.len() == self.max_entrants as usize
+    }
+
+    pub fn register(&mut self, player: Pubkey) {
+        self.entrants.push(Entrant {
+            player,
+            eliminated_in: 0,
+            paid: false,
+        });
+        self.prize_pool += self.entry_fee;
+    }
+
+    /// Starts the bracket, or cancels the tournament when there is nobody to play against.
+    pub fn start(&mut self, now: i64, time_for_penalization: i64) {
+        if self.entrants.len() < MIN_ENTRANTS as usize {
+            self.state = TournamentState::Cancelled;
+        } else {
+            self.state = TournamentState::Running;
+            self.pair_round(now, time_for_penalization);
+        }
+    }
+
+    fn pair_round(&mut self, now: i64, time_for_penalization: i64) {
+        let remaining: Vec<Pubkey> = self
+            .entrants
+            .iter()
+            .filter(|entrant| entrant.eliminated_in == 0)
+            .map(|entrant| entrant.player)
+            .collect();
+        self.round += 1;
+        self.matches = remaining
+            .chunks_exact(2)
+            .map(|pair| Match {
+                home: pair[0],
+                away: pair[1],
+                game: None,
+                winner: None,
+                deadline: now + time_for_penalization,
+            })
+            .collect();
+    }
+
+    pub fn match_index(&self, game: &Pubkey) -> Option<usize> {
+        self.matches
+            .iter()
+            .position(|bracket_match| bracket_match.game == Some(*game))
+    }
+
+    /// A drawn match is replayed with a new game and a new deadline.
+    pub fn replay_match(&mut self, index: usize, now: i64, time_for_penalization: i64) {
+        let bracket_match = &mut self.matches[index];
+        bracket_match.game = None;
+        bracket_match.deadline = now + time_for_penalization;
+    }
+
+    /// Advances `winner`, starting the next round once every match of this one is decided.
+    pub fn record_winner(
+        &mut self,
+        index: usize,
+        winner: Pubkey,
+        now: i64,
+        time_for_penalization: i64,
+    ) {
+        let bracket_match = &mut self.matches[index];
+        let loser = if winner == bracket_match.home {
+            bracket_match.away
+        } else {
+            bracket_match.home
+        };
+        bracket_match.winner = Some(winner);
+        let round = self.round;
+        if let Some(entrant) = self
+            .entrants
+            .iter_mut()
+            .find(|entrant| entrant.player == loser)
+        {
+            entrant.eliminated_in = round;
+        }
+
+        if self
+            .matches
+            .iter()
+            .all(|bracket_match| bracket_match.winner.is_some())
+        {
+            let remaining = self
+                .entrants
+                .iter()
+                .filter(|entrant| entrant.eliminated_in == 0)
+                .count();
+            if remaining == 1 {
+                self.state = TournamentState::Finished;
+                self.matches.clear();
+            } else {
+                self.pair_round(now, time_for_penalization);
+            }
+        }
+    }
+
+    /// What the entrant at `index` is owed, the entry fee when the tournament was cancelled.
+    /// Entrants knocked out in the same round split the places they cover, and places nobody
+    /// could reach go to the winner.
+    pub fn prize_of(&self, index: usize) -> u64 {
+        if self.state == TournamentState::Cancelled {
+            return self.entry_fee;
+        }
+        let eliminated_in = self.entrants[index].eliminated_in;
+        // Entrants still in the bracket are ranked as if knocked out in a round after the last.
+        let rank = |entrant: &Entrant| match entrant.eliminated_in {
+            0 => u8::MAX,
+            round => round,
+        };
+        let own_rank = rank(&self.entrants[index]);
+        let ahead = self
+            .entrants
+            .iter()
+            .filter(|entrant| rank(entrant) > own_rank)
+            .count();
+        let tied = self
+            .entrants
+            .iter()
+            .filter(|entrant| entrant.eliminated_in == eliminated_in)
+            .count();
+        let places = |from: usize, to: usize| -> u64 {
+            self.prize_split
+                .iter()
+                .take(to)
+                .skip(from)
+                .map(|&basis_points| basis_points as u64)
+                .sum()
+        };
+        let mut basis_points = places(ahead, ahead + tied);
+        if eliminated_in == 0 {
+            basis_points += places(self.entrants.len(), MAX_PRIZE_PLACES);
+        }
+        (self.prize_pool as u128 * basis_points as u128 / tied as u128 / PRIZE_SPLIT_TOTAL as u128)
+            as u64
+    }
+
+    /// Every entrant with a prize was paid, what is left in the escrow is rounding dust.
+    pub fn is_paid_out(&self) -> bool {
+        (0..self.entrants.len()).all(|index| self.entrants[index].paid || self.prize_of(index) == 0)
+    }
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{FeeVault, GameV3};
+
+/// Moves lamports out of an account this program owns, which needs no system program CPI.
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_lamports = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
+
+/// Pays out the fees `game` holds, to each player whose fee is refunded and to the fee vault
+/// otherwise, and refunds the relayer fees nobody earned. Nothing is moved for games that don't
+/// hold their fees.
+pub fn release_fees<'info>(
+    game: &mut GameV3,
+    game_info: &AccountInfo<'info>,
+    refunds: (bool, bool),
+    first_player: &AccountInfo<'info>,
+    second_player: Option<&AccountInfo<'info>>,
+    fee_vault: &mut Account<'info, FeeVault>,
+) -> Result<()> {
+    let (first_player_fee, second_player_fee) = game.take_escrowed_fees();
+    for (fee, refund, player) in [
+        (first_player_fee, refunds.0, Some(first_player)),
+        (second_player_fee, refunds.1, second_player),
+    ] {
+        if fee == 0 {
+            continue;
+        }
+        match player {
+            Some(player) if refund => transfer_lamports(game_info, player, fee)?,
+            _ => {
+                transfer_lamports(game_info, &fee_vault.to_account_info(), fee)?;
+                fee_vault.collect(fee);
+            }
+        }
+    }
+    refund_relayer_fees(game, game_info, first_player, second_player)
+}
+
+/// Gives both players back everything `game` holds for them, for games that end through no doing
+/// of theirs, like an admin unwind.
+pub fn refund_fees<'info>(
+    game: &mut GameV3,
+    game_info: &AccountInfo<'info>,
+    first_player: &AccountInfo<'info>,
+    second_player: &AccountInfo<'info>,
+) -> Result<()> {
+    let (first_player_fee, second_player_fee) = game.take_escrowed_fees();
+    transfer_lamports(game_info, first_player, first_player_fee)?;
+    transfer_lamports(game_info, second_player, second_player_fee)?;
+    refund_relayer_fees(game, game_info, first_player, Some(second_player))
+}
+
+/// Gives each player back the relayer fee they deposited, when no relayer revealed for them.
+pub fn refund_relayer_fees<'info>(
+    game: &mut GameV3,
+    game_info: &AccountInfo<'info>,
+    first_player: &AccountInfo<'info>,
+    second_player: Option<&AccountInfo<'info>>,
+) -> Result<()> {
+    for player in [Some(first_player), second_player].into_iter().flatten() {
+        let relayer_fee = game.take_relayer_fee(player.key());
+        if relayer_fee > 0 {
+            transfer_lamports(game_info, player, relayer_fee)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use solana_program::pubkey;
+
+use crate::{constants::METADATA, error::BPSError};
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+// `Key::MetadataV1` of the token metadata program.
+const METADATA_V1: u8 = 4;
+// address + verified + share
+const CREATOR_LEN: usize = 34;
+
+/// Cursor over Borsh encoded account data.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            return err!(BPSError::InvalidMetadata);
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        Pubkey::try_from(self.take(32)?).map_err(|_| error!(BPSError::InvalidMetadata))
+    }
+
+    fn skip_string(&mut self) -> Result<()> {
+        let len = self.u32()? as usize;
+        self.take(len)?;
+        Ok(())
+    }
+
+    fn skip_option(&mut self, len: usize) -> Result<()> {
+        if self.u8()? == 1 {
+            self.take(len)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collection `mint` is a verified member of, read from its Metaplex metadata account without
+/// depending on the metadata crate. Fields after `collection` are never read, so newer metadata
+/// versions parse the same.
+pub fn verified_collection(metadata: &AccountInfo, mint: &Pubkey) -> Result<Option<Pubkey>> {
+    let (metadata_address, _) = Pubkey::find_program_address(
+        &[
+            METADATA.as_ref(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    );
+    require!(
+        metadata.owner == &TOKEN_METADATA_PROGRAM_ID && metadata.key() == metadata_address,
+        BPSError::InvalidMetadata
+    );
+
+    let data = metadata.try_borrow_data()?;
+    let mut reader = Reader { data: &data };
+    require!(reader.u8()? == METADATA_V1, BPSError::InvalidMetadata);
+    reader.pubkey()?; // update_authority
+    require!(reader.pubkey()? == *mint, BPSError::InvalidMetadata);
+    reader.skip_string()?; // name
+    reader.skip_string()?; // symbol
+    reader.skip_string()?; // uri
+    reader.take(2)?; // seller_fee_basis_points
+    if reader.u8()? == 1 {
+        let creators = reader.u32()? as usize;
+        reader.take(creators * CREATOR_LEN)?;
+    }
+    reader.take(2)?; // primary_sale_happened, is_mutable
+    reader.skip_option(1)?; // edition_nonce
+    reader.skip_option(1)?; // token_standard
+    if reader.u8()? != 1 {
+        return Ok(None);
+    }
+    let verified = reader.u8()? == 1;
+    let collection = reader.pubkey()?;
+    Ok(verified.then_some(collection))
+}
@@ -0,0 +1,11 @@
+pub mod lamports;
+pub mod metadata;
+pub mod nft;
+pub mod seeds;
+pub mod transfer_fee;
+
+pub use lamports::*;
+pub use metadata::*;
+pub use nft::*;
+pub use seeds::*;
+pub use transfer_fee::*;
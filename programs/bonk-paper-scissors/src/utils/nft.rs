@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, Create},
+    token_interface::{close_account, transfer_checked, CloseAccount, TransferChecked},
+};
+
+/// Accounts every payout out of an NFT game escrow needs.
+pub struct NftRelease<'a, 'info> {
+    pub game: AccountInfo<'info>,
+    pub game_signer: &'a [&'a [&'a [u8]]],
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'a, 'info> NftRelease<'a, 'info> {
+    /// Sends the NFT held by `escrow` to the associated token account of `owner`, which the
+    /// payer creates when missing, then closes the escrow into `rent_receiver`. The associated
+    /// token program rejects any other `destination`.
+    pub fn release(
+        &self,
+        escrow: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        destination: &AccountInfo<'info>,
+        owner: &AccountInfo<'info>,
+        rent_receiver: &AccountInfo<'info>,
+    ) -> Result<()> {
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.clone(),
+            Create {
+                payer: self.payer.clone(),
+                associated_token: destination.clone(),
+                authority: owner.clone(),
+                mint: mint.clone(),
+                system_program: self.system_program.clone(),
+                token_program: self.token_program.clone(),
+            },
+        ))?;
+        transfer_checked(
+            CpiContext::new(
+                self.token_program.clone(),
+                TransferChecked {
+                    from: escrow.clone(),
+                    mint: mint.clone(),
+                    to: destination.clone(),
+                    authority: self.game.clone(),
+                },
+            )
+            .with_signer(self.game_signer),
+            1,
+            0,
+        )?;
+        close_account(
+            CpiContext::new(
+                self.token_program.clone(),
+                CloseAccount {
+                    account: escrow.clone(),
+                    destination: rent_receiver.clone(),
+                    authority: self.game.clone(),
+                },
+            )
+            .with_signer(self.game_signer),
+        )
+    }
+}
@@ -0,0 +1,33 @@
+use anchor_lang::{prelude::*, ZeroCopy};
+
+/// Key of an optional account that seeds another one. Anchor derives the address with the
+/// account still in an `Option` and signs its creation with it unwrapped, a missing account
+/// gives the default key so the seeds constraint fails instead of panicking.
+pub trait SeedKey {
+    fn seed_key(&self) -> Pubkey;
+}
+
+impl<T: SeedKey> SeedKey for Option<T> {
+    fn seed_key(&self) -> Pubkey {
+        self.as_ref()
+            .map_or_else(Pubkey::default, SeedKey::seed_key)
+    }
+}
+
+impl<T: SeedKey> SeedKey for Box<T> {
+    fn seed_key(&self) -> Pubkey {
+        self.as_ref().seed_key()
+    }
+}
+
+impl<'info, T: AccountSerialize + AccountDeserialize + Clone> SeedKey for Account<'info, T> {
+    fn seed_key(&self) -> Pubkey {
+        self.key()
+    }
+}
+
+impl<'info, T: ZeroCopy + Owner> SeedKey for AccountLoader<'info, T> {
+    fn seed_key(&self) -> Pubkey {
+        self.key()
+    }
+}
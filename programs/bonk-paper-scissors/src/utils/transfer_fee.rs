@@ -0,0 +1,109 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{
+            instruction::harvest_withheld_tokens_to_mint, TransferFeeConfig, MAX_FEE_BASIS_POINTS,
+        },
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::Mint,
+};
+
+use crate::error::BPSError;
+
+/// Transfer fee settings of a Token-2022 mint, `None` for SPL Token mints and mints without fees.
+fn transfer_fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Fails unless the mint's only Token-2022 extension, if any, is `TransferFeeConfig`. Hooks,
+/// delegates, non-transferable or interest-bearing mints would break the escrow accounting.
+pub fn require_supported_mint(mint: &AccountInfo) -> Result<()> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(());
+    }
+    let data = mint.try_borrow_data()?;
+    let extension_types = StateWithExtensions::<Mint>::unpack(&data)?.get_extension_types()?;
+    require!(
+        extension_types
+            .iter()
+            .all(|extension_type| *extension_type == ExtensionType::TransferFeeConfig),
+        BPSError::UnsupportedMintExtension
+    );
+    Ok(())
+}
+
+/// Fee the mint withholds from a transfer of `amount` this epoch.
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| error!(BPSError::TransferFeeOverflow)),
+        None => Ok(0),
+    }
+}
+
+/// Amount to send so exactly `net_amount` arrives once the mint withheld its fee.
+// `div_ceil` isn't stable on the SBF toolchain yet.
+#[allow(clippy::manual_div_ceil)]
+pub fn gross_amount_for(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    let config = match transfer_fee_config(mint)? {
+        Some(config) => config,
+        None => return Ok(net_amount),
+    };
+    let epoch = Clock::get()?.epoch;
+    let overflow = || error!(BPSError::TransferFeeOverflow);
+    let received = |gross_amount: u64| -> Result<u64> {
+        config
+            .calculate_epoch_fee(epoch, gross_amount)
+            .map(|fee| gross_amount - fee)
+            .ok_or_else(overflow)
+    };
+
+    let fee = config.get_epoch_fee(epoch);
+    let basis_points = u16::from(fee.transfer_fee_basis_points) as u128;
+    let capped = net_amount as u128 + u64::from(fee.maximum_fee) as u128;
+    // The fee is rounded up, so the estimate can be off by one in either direction.
+    let estimate = if basis_points < MAX_FEE_BASIS_POINTS as u128 {
+        let remaining = MAX_FEE_BASIS_POINTS as u128 - basis_points;
+        ((net_amount as u128 * MAX_FEE_BASIS_POINTS as u128 + remaining - 1) / remaining)
+            .min(capped)
+    } else {
+        capped
+    };
+    let mut gross_amount = u64::try_from(estimate).map_err(|_| overflow())?;
+    while received(gross_amount)? < net_amount {
+        gross_amount = gross_amount.checked_add(1).ok_or_else(overflow)?;
+    }
+    while gross_amount > net_amount && received(gross_amount - 1)? >= net_amount {
+        gross_amount -= 1;
+    }
+    Ok(gross_amount)
+}
+
+/// Moves the fees withheld on `token_accounts` to the mint, Token-2022 won't close an account
+/// that still holds withheld fees.
+pub fn harvest_withheld_fees<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if transfer_fee_config(mint)?.is_none() {
+        return Ok(());
+    }
+    let sources: Vec<&Pubkey> = token_accounts.iter().map(|account| account.key).collect();
+    let mut account_infos = vec![mint.clone()];
+    account_infos.extend_from_slice(token_accounts);
+    account_infos.push(token_program.clone());
+    invoke(
+        &harvest_withheld_tokens_to_mint(token_program.key, mint.key, &sources)?,
+        &account_infos,
+    )?;
+    Ok(())
+}
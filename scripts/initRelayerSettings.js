@@ -1,4 +1,4 @@
-const anchor = require("@project-serum/anchor");
+const anchor = require("@coral-xyz/anchor");
 const { TextEncoder } = require("util");
 const fs = require("fs/promises");
 const IDL = require("../target/idl/bonk_paper_scissors.json");
@@ -12,9 +12,9 @@ const PROGRAM_ID_PROD = new anchor.web3.PublicKey(
 
 const encode = (str) => new TextEncoder().encode(str);
 const b = (input) => encode(input.join(""));
-const getBPSSettingsPDA = () =>
+const getRelayerSettingsPDA = () =>
   anchor.web3.PublicKey.findProgramAddressSync(
-    [b`bps_settings_v2`],
+    [b`relayer_settings`],
     PROGRAM_ID_PROD
   );
 
@@ -38,14 +38,13 @@ const getBPSSettingsPDA = () =>
   const provider = new anchor.AnchorProvider(connection, wallet, {});
   const program = new anchor.Program(IDL, PROGRAM_ID_PROD, provider);
 
-  const [bpsSettingsPDA] = getBPSSettingsPDA();
+  const [relayerSettingsPDA] = getRelayerSettingsPDA();
   const txId = await program.methods
-    .initBpsSettingsV2(
-      new anchor.BN(60 * 60 * 24 * 7), // 7 Days
-      new anchor.BN(0.025 * anchor.web3.LAMPORTS_PER_SOL) // 0.025 SOL
+    .initRelayerSettings(
+      new anchor.BN(0.001 * anchor.web3.LAMPORTS_PER_SOL) // 0.001 SOL
     )
     .accountsStrict({
-      bpsSettingsV2: bpsSettingsPDA,
+      relayerSettings: relayerSettingsPDA,
       signer: program.provider.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
     })
@@ -1,4 +1,4 @@
-const anchor = require("@project-serum/anchor");
+const anchor = require("@coral-xyz/anchor");
 const { TextEncoder } = require("util");
 const fs = require("fs/promises");
 const IDL = require("../target/idl/bonk_paper_scissors.json");
@@ -17,7 +17,7 @@ const encode = (str) => new TextEncoder().encode(str);
 const b = (input) => encode(input.join(""));
 const getBPSSettingsPDA = () =>
   anchor.web3.PublicKey.findProgramAddressSync(
-    [b`bps_settings_v2`],
+    [b`bps_settings_v3`],
     PROGRAM_ID
   );
 
@@ -43,12 +43,12 @@ const getBPSSettingsPDA = () =>
 
   const [bpsSettingsPDA] = getBPSSettingsPDA();
   const txId = await program.methods
-    .updateBpsSettingsV2(
+    .updateBpsSettingsV3(
       new anchor.BN(60 * 60 * 24 * 7), // 7 Days
       new anchor.BN(0.025 * anchor.web3.LAMPORTS_PER_SOL) // 0.025 SOL
     )
     .accountsStrict({
-      bpsSettingsV2: bpsSettingsPDA,
+      bpsSettingsV3: bpsSettingsPDA,
       signer: program.provider.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
     })
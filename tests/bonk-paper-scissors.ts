@@ -1,8 +1,12 @@
 import * as SPL from "@solana/spl-token";
-import * as anchor from "@project-serum/anchor";
-import { Program } from "@project-serum/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
 import { TextEncoder } from "util";
 import { randomBytes, createHash } from "crypto";
+import { execFileSync } from "child_process";
+import { readFileSync, writeFileSync } from "fs";
+import { tmpdir } from "os";
+import { join } from "path";
 
 import { BonkPaperScissors } from "../target/types/bonk_paper_scissors";
 
@@ -11,10 +15,26 @@ const b = (input: TemplateStringsArray) => encode(input.join(""));
 
 const GAME_ID = "testgame";
 const SECOND_GAME_ID = "secondgame";
+const REMATCH_GAME_ID = "rematchgame";
 const BPS_TREASURY = "bpstzWLPDetyjiD33HPGGE96MzkhEA7dhRFzhc8Ay5R";
 const BPS_TREASURY_PUBKEY = new anchor.web3.PublicKey(BPS_TREASURY);
 const SEVEN_DAYS_BN = new anchor.BN(7 * 24 * 60 * 60 * 1000); // 7 Days
 const PLAYER_FEE_LAMPORTS = new anchor.BN(0.025 * anchor.web3.LAMPORTS_PER_SOL); // 0.025 SOL
+const RELAYER_FEE_LAMPORTS = new anchor.BN(0.001 * anchor.web3.LAMPORTS_PER_SOL); // 0.001 SOL
+const MAX_OPEN_GAMES = 10;
+// `BpsSettingsV3` flags.
+const REFUND_ON_CANCEL = 1 << 0;
+const REFUND_ON_FORFEIT = 1 << 1;
+
+// GameV3 stores enums as their index and optional fields behind presence flags.
+const GAME_STATE_STARTED_AND_WAITING_FOR_REVEAL = 1;
+const GAME_STATE_FIRST_PLAYER_WON = 2;
+const CHOICE_BONK = 0;
+const CHOICE_PAPER = 1;
+const CHOICE_SCISSORS = 2;
+const FIRST_PLAYER_REVEALED = 1 << 1;
+const HAS_PREVIOUS_GAME = 1 << 4;
+const GAME_STATE_OFFSET = 426;
 
 const generateSalt = () => {
   const result = Uint8Array.from(randomBytes(32));
@@ -22,7 +42,7 @@ const generateSalt = () => {
   return result;
 };
 
-const generateHash = (salt: number[], move: 1 | 2 | 3) => {
+const generateHash = (salt: number[], move: number) => {
   const hash = createHash("sha256");
   hash.update(Uint8Array.from([move]));
   hash.update(Uint8Array.from(salt));
@@ -57,8 +77,201 @@ const getEscrowPDA = (
   );
 };
 
+const getQueueTicketPDA = (
+  mint: anchor.web3.PublicKey,
+  amount: anchor.BN,
+  player: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      b`queue_ticket`,
+      mint.toBytes(),
+      amount.toArrayLike(Buffer, "le", 8),
+      player.toBytes(),
+    ],
+    programId
+  );
+};
+
+const getQueueTicketEscrowPDA = (
+  queueTicketPDA: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [queueTicketPDA.toBytes(), b`escrow`, b`first_player`],
+    programId
+  );
+};
+
+const getSidePoolPDA = (
+  gamePDA: anchor.web3.PublicKey,
+  gameCreatedAt: anchor.BN,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      b`side_pool`,
+      gamePDA.toBytes(),
+      gameCreatedAt.toArrayLike(Buffer, "le", 8),
+    ],
+    programId
+  );
+};
+
+const getSidePoolVaultPDA = (
+  sidePoolPDA: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [sidePoolPDA.toBytes(), b`escrow`],
+    programId
+  );
+};
+
+const getSideBetPDA = (
+  sidePoolPDA: anchor.web3.PublicKey,
+  bettor: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`side_bet`, sidePoolPDA.toBytes(), bettor.toBytes()],
+    programId
+  );
+};
+
+// Bet of `bettor` in the side pool of the game as it is now.
+const getGameSideBetPDA = async (
+  program: Program<BonkPaperScissors>,
+  gamePDA: anchor.web3.PublicKey,
+  bettor: anchor.web3.PublicKey
+) => {
+  const game = await program.account.gameV3.fetch(gamePDA);
+  const [sidePoolPDA] = getSidePoolPDA(
+    gamePDA,
+    game.createdAt,
+    program.programId
+  );
+  return getSideBetPDA(sidePoolPDA, bettor, program.programId);
+};
+
+const getSessionTokenPDA = (
+  authority: anchor.web3.PublicKey,
+  sessionSigner: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`session_token`, authority.toBytes(), sessionSigner.toBytes()],
+    programId
+  );
+};
+
+const getSolVaultPDA = (
+  gamePDA: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`sol_vault`, gamePDA.toBytes()],
+    programId
+  );
+};
+
+const getAllowedCollectionPDA = (
+  collection: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`allowed_collection`, collection.toBytes()],
+    programId
+  );
+};
+
+const getTablePDA = (
+  creator: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey,
+  tableId: string
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`table`, creator.toBytes(), encode(tableId)],
+    programId
+  );
+};
+
+const getTableEscrowPDA = (
+  tablePDA: anchor.web3.PublicKey,
+  player: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [tablePDA.toBytes(), b`escrow`, player.toBytes()],
+    programId
+  );
+};
+
+const getTournamentPDA = (
+  organizer: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey,
+  tournamentId: string
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`tournament`, organizer.toBytes(), encode(tournamentId)],
+    programId
+  );
+};
+
+const getRulesetPDA = (
+  programId: anchor.web3.PublicKey,
+  rulesetId: string
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`ruleset`, encode(rulesetId)],
+    programId
+  );
+};
+
+const getStakeLimitsPDA = (
+  programId: anchor.web3.PublicKey,
+  mint: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`stake_limits`, mint.toBytes()],
+    programId
+  );
+};
+
+const getOpenGamesPDA = (
+  programId: anchor.web3.PublicKey,
+  player: anchor.web3.PublicKey
+) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`open_games`, player.toBytes()],
+    programId
+  );
+};
+
+const getFeeVaultPDA = (programId: anchor.web3.PublicKey) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`fee_vault`],
+    programId
+  );
+};
+
+const getRevenueSplitPDA = (programId: anchor.web3.PublicKey) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`revenue_split`],
+    programId
+  );
+};
+
+const getSeasonPDA = (programId: anchor.web3.PublicKey, seasonId: string) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`season`, encode(seasonId)],
+    programId
+  );
+};
+
 /**
- * @deprecated - Use getBPSSettingsPDAV2 instead
+ * @deprecated - Use getBPSSettingsPDAV3 instead
  */
 const getBPSSettingsPDA = (programId: anchor.web3.PublicKey) => {
   return anchor.web3.PublicKey.findProgramAddressSync(
@@ -67,6 +280,9 @@ const getBPSSettingsPDA = (programId: anchor.web3.PublicKey) => {
   );
 };
 
+/**
+ * @deprecated - Use getBPSSettingsPDAV3 instead
+ */
 const getBPSSettingsPDAV2 = (programId: anchor.web3.PublicKey) => {
   return anchor.web3.PublicKey.findProgramAddressSync(
     [b`bps_settings_v2`],
@@ -74,6 +290,44 @@ const getBPSSettingsPDAV2 = (programId: anchor.web3.PublicKey) => {
   );
 };
 
+const getBPSSettingsPDAV3 = (programId: anchor.web3.PublicKey) => {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [b`bps_settings_v3`],
+    programId
+  );
+};
+
+// Changes some of the V3 settings and keeps the others.
+const updateBpsSettings = async (
+  program: Program<BonkPaperScissors>,
+  changes: {
+    timeForPenalization?: anchor.BN;
+    relayerFeeLamports?: anchor.BN;
+    maxOpenGames?: number;
+    flags?: number;
+  }
+) => {
+  const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+  const settings = await program.account.bpsSettingsV3.fetch(bpsSettingsPDA);
+  return program.methods
+    .updateBpsSettingsV3(
+      changes.timeForPenalization ?? settings.timers.timeForPenalization,
+      settings.fees.playerFeeLamports,
+      settings.fees.payoutBasisPoints,
+      changes.relayerFeeLamports ?? settings.fees.relayerFeeLamports,
+      changes.maxOpenGames ?? settings.limits.maxOpenGames,
+      changes.flags === undefined
+        ? settings.flags
+        : new anchor.BN(changes.flags)
+    )
+    .accountsStrict({
+      bpsSettingsV3: bpsSettingsPDA,
+      signer: program.provider.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+};
+
 const createAndFundAccounts = async (program: Program<BonkPaperScissors>) => {
   const tokenCreator = anchor.web3.Keypair.generate();
   const playerOne = anchor.web3.Keypair.generate();
@@ -102,6 +356,29 @@ const createAndFundAccounts = async (program: Program<BonkPaperScissors>) => {
   };
 };
 
+const U64_MAX = new anchor.BN(2).pow(new anchor.BN(64)).subn(1);
+
+/**
+ * Lets games stake `mint`, the admin allows a mint by setting its stake limits.
+ */
+const allowMint = async (
+  program: Program<BonkPaperScissors>,
+  mint: anchor.web3.PublicKey
+) => {
+  await program.methods
+    .initStakeLimits(new anchor.BN(1), U64_MAX, [])
+    .accountsStrict({
+      stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+      mint,
+      signer: program.provider.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+};
+
+/**
+ * Creates a mint the admin allows with any stake above 0.
+ */
 const initializeMint = async (
   program: Program<BonkPaperScissors>,
   tokenCreator: anchor.web3.Keypair
@@ -113,21 +390,76 @@ const initializeMint = async (
     null,
     0
   );
+  await allowMint(program, mint);
   return mint;
 };
 
+/**
+ * Creates an allowed Token-2022 mint that withholds `basisPoints` of every
+ * transfer.
+ */
+const initializeTransferFeeMint = async (
+  program: Program<BonkPaperScissors>,
+  tokenCreator: anchor.web3.Keypair,
+  basisPoints: number,
+  maximumFee: number
+) => {
+  const mint = anchor.web3.Keypair.generate();
+  const mintLen = SPL.getMintLen([SPL.ExtensionType.TransferFeeConfig]);
+  const lamports =
+    await program.provider.connection.getMinimumBalanceForRentExemption(
+      mintLen
+    );
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: tokenCreator.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space: mintLen,
+      lamports,
+      programId: SPL.TOKEN_2022_PROGRAM_ID,
+    }),
+    SPL.createInitializeTransferFeeConfigInstruction(
+      mint.publicKey,
+      tokenCreator.publicKey,
+      tokenCreator.publicKey,
+      basisPoints,
+      BigInt(maximumFee),
+      SPL.TOKEN_2022_PROGRAM_ID
+    ),
+    SPL.createInitializeMintInstruction(
+      mint.publicKey,
+      0,
+      tokenCreator.publicKey,
+      null,
+      SPL.TOKEN_2022_PROGRAM_ID
+    )
+  );
+  await anchor.web3.sendAndConfirmTransaction(
+    program.provider.connection,
+    tx,
+    [tokenCreator, mint]
+  );
+  await allowMint(program, mint.publicKey);
+  return mint.publicKey;
+};
+
 const mintTo = async (
   program: Program<BonkPaperScissors>,
   tokenCreator: anchor.web3.Keypair,
   target: anchor.web3.PublicKey,
   mint: anchor.web3.PublicKey,
-  amount: number
+  amount: number,
+  tokenProgram: anchor.web3.PublicKey = SPL.TOKEN_PROGRAM_ID
 ) => {
   const { address } = await SPL.getOrCreateAssociatedTokenAccount(
     program.provider.connection,
     tokenCreator,
     mint,
-    target
+    target,
+    false,
+    undefined,
+    undefined,
+    tokenProgram
   );
   const result = await SPL.mintTo(
     program.provider.connection,
@@ -135,7 +467,10 @@ const mintTo = async (
     mint,
     address,
     tokenCreator,
-    amount
+    amount,
+    [],
+    undefined,
+    tokenProgram
   );
   return {
     ata: address,
@@ -143,17 +478,212 @@ const mintTo = async (
   };
 };
 
+/**
+ * Funds two players, creates a game and joins it, leaving it waiting for reveals.
+ */
+const startGame = async (
+  program: Program<BonkPaperScissors>,
+  gameId: string,
+  playerOneMove: number,
+  playerTwoMove: number,
+  ruleset: anchor.web3.PublicKey | null = null,
+  // Both players opt in to relayed reveals.
+  useRelayer = false
+) => {
+  const { tokenCreator, playerOne, playerTwo } = await createAndFundAccounts(
+    program
+  );
+  const mint = await initializeMint(program, tokenCreator);
+  const { ata: ataOne } = await mintTo(
+    program,
+    tokenCreator,
+    playerOne.publicKey,
+    mint,
+    10_000
+  );
+  const { ata: ataTwo } = await mintTo(
+    program,
+    tokenCreator,
+    playerTwo.publicKey,
+    mint,
+    10_000
+  );
+  const [gamePDA] = getGamePDA(playerOne.publicKey, program.programId, gameId);
+  const [escrowOne] = getEscrowPDA("first", gamePDA, program.programId);
+  const [escrowTwo] = getEscrowPDA("second", gamePDA, program.programId);
+  const playerOneSalt = [...generateSalt()];
+  const playerTwoSalt = [...generateSalt()];
+  const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+
+  await program.methods
+    .firstPlayerMove(
+      gameId,
+      new anchor.BN(1_000),
+      [...generateHash(playerOneSalt, playerOneMove)],
+      useRelayer
+    )
+    .accountsStrict({
+      game: gamePDA,
+      firstPlayer: playerOne.publicKey,
+      firstPlayerEscrow: escrowOne,
+      firstPlayerTokenAccount: ataOne,
+      mint: mint,
+      stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+      bpsSettingsV3: bpsSettingsPDA,
+      firstPlayerOpenGames: getOpenGamesPDA(
+        program.programId,
+        playerOne.publicKey
+      )[0],
+      ruleset,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: SPL.TOKEN_PROGRAM_ID,
+    })
+    .signers([playerOne])
+    .rpc();
+  await program.methods
+    .secondPlayerMove(
+      [...generateHash(playerTwoSalt, playerTwoMove)],
+      useRelayer
+    )
+    .accountsStrict({
+      game: gamePDA,
+      secondPlayer: playerTwo.publicKey,
+      secondPlayerEscrow: escrowTwo,
+      secondPlayerTokenAccount: ataTwo,
+      secondPlayerSideBet: (
+        await getGameSideBetPDA(program, gamePDA, playerTwo.publicKey)
+      )[0],
+      mint: mint,
+      bpsSettingsV3: bpsSettingsPDA,
+      secondPlayerOpenGames: getOpenGamesPDA(
+        program.programId,
+        playerTwo.publicKey
+      )[0],
+      systemProgram: anchor.web3.SystemProgram.programId,
+      associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: SPL.TOKEN_PROGRAM_ID,
+    })
+    .signers([playerTwo])
+    .rpc();
+
+  return {
+    tokenCreator,
+    playerOne,
+    playerTwo,
+    mint,
+    ataOne,
+    ataTwo,
+    gamePDA,
+    escrowOne,
+    escrowTwo,
+    playerOneSalt,
+    playerTwoSalt,
+  };
+};
+
 describe("bonk-paper-scissors: init bps settings", async () => {
   // Configure the client to use the local cluster.
   anchor.setProvider(anchor.AnchorProvider.env());
   const program = anchor.workspace
     .BonkPaperScissors as Program<BonkPaperScissors>;
-  it("init_bps_settings", async () => {
-    const [bpsSettingsPDA] = getBPSSettingsPDAV2(program.programId);
+  it("update_bps_settings_v2 is removed", async () => {
+    try {
+      await program.methods
+        .updateBpsSettingsV2(SEVEN_DAYS_BN, PLAYER_FEE_LAMPORTS)
+        .accountsStrict({
+          bpsSettingsV2: getBPSSettingsPDAV2(program.programId)[0],
+          signer: program.provider.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      throw new Error("Should have failed");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InstructionRemoved") {
+        throw error;
+      }
+    }
+  });
+  it("migrate_settings_v2_to_v3", async () => {
+    const [bpsSettingsV2PDA] = getBPSSettingsPDAV2(program.programId);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const settingsV2 = await program.account.bpsSettingsV2.fetch(
+      bpsSettingsV2PDA
+    );
     const txId = await program.methods
-      .initBpsSettingsV2(SEVEN_DAYS_BN, PLAYER_FEE_LAMPORTS)
+      .migrateSettingsV2ToV3()
       .accountsStrict({
-        bpsSettingsV2: bpsSettingsPDA,
+        bpsSettingsV2: bpsSettingsV2PDA,
+        bpsSettingsV3: bpsSettingsPDA,
+        signer: program.provider.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("txId:", txId);
+    const settings = await program.account.bpsSettingsV3.fetch(
+      bpsSettingsPDA
+    );
+    if (
+      settings.version !== 3 ||
+      !settings.authority.equals(settingsV2.authority) ||
+      !settings.timers.timeForPenalization.eq(
+        settingsV2.timeForPenalization
+      ) ||
+      !settings.fees.playerFeeLamports.eq(settingsV2.playerFeeLamports) ||
+      settings.fees.payoutBasisPoints !== 9_000 ||
+      !settings.fees.relayerFeeLamports.isZero() ||
+      settings.limits.maxOpenGames !== MAX_OPEN_GAMES ||
+      !settings.flags.eqn(REFUND_ON_CANCEL | REFUND_ON_FORFEIT)
+    ) {
+      throw new Error("Settings should carry over to V3");
+    }
+    const closed = await program.provider.connection.getAccountInfo(
+      bpsSettingsV2PDA
+    );
+    if (closed !== null) {
+      throw new Error("V2 settings should be closed");
+    }
+  });
+  it("won't take a penalization time over a u32", async () => {
+    try {
+      await updateBpsSettings(program, {
+        timeForPenalization: new anchor.BN(2).pow(new anchor.BN(32)),
+      });
+      throw new Error("Penalization time should fit in a u32");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InvalidTimeForPenalization") {
+        throw error;
+      }
+    }
+  });
+  it("won't take an undefined settings flag", async () => {
+    try {
+      await updateBpsSettings(program, { flags: 1 << 2 });
+      throw new Error("Undefined flags should be rejected");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "UnknownSettingsFlags") {
+        throw error;
+      }
+    }
+  });
+  it("update_bps_settings_v3", async () => {
+    const txId = await updateBpsSettings(program, {
+      relayerFeeLamports: RELAYER_FEE_LAMPORTS,
+    });
+    console.log("txId:", txId);
+    const settings = await program.account.bpsSettingsV3.fetch(
+      getBPSSettingsPDAV3(program.programId)[0]
+    );
+    if (!settings.fees.relayerFeeLamports.eq(RELAYER_FEE_LAMPORTS)) {
+      throw new Error("Relayer fee should be updated");
+    }
+  });
+  it("init_fee_vault", async () => {
+    const [feeVaultPDA] = getFeeVaultPDA(program.programId);
+    const txId = await program.methods
+      .initFeeVault(BPS_TREASURY_PUBKEY)
+      .accountsStrict({
+        feeVault: feeVaultPDA,
         signer: program.provider.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -229,17 +759,22 @@ describe("bonk-paper-scissors: happy-path", async () => {
     const hash = generateHash([...salt], 1);
     playerOneHash = [...hash];
 
-    const [bpsSettingsPDA] = getBPSSettingsPDAV2(program.programId);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
     const tx = await program.methods
-      .firstPlayerMove(GAME_ID, new anchor.BN(1_000), playerOneHash)
+      .firstPlayerMove(GAME_ID, new anchor.BN(1_000), playerOneHash, false)
       .accountsStrict({
         game: gamePDA,
         firstPlayer: playerOne.publicKey,
         firstPlayerEscrow: escrowOne,
         firstPlayerTokenAccount: ataOne,
         mint: mint,
-        bpsSettingsV2: bpsSettingsPDA,
-        bpsTreasury: BPS_TREASURY_PUBKEY,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        ruleset: null,
         systemProgram: anchor.web3.SystemProgram.programId,
         associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: SPL.TOKEN_PROGRAM_ID,
@@ -247,7 +782,7 @@ describe("bonk-paper-scissors: happy-path", async () => {
       .signers([playerOne])
       .rpc();
 
-    const result = await program.account.game.fetchNullable(gamePDA);
+    const result = await program.account.gameV3.fetchNullable(gamePDA);
     if (result === null) {
       throw new Error("Game account not found");
     }
@@ -265,17 +800,23 @@ describe("bonk-paper-scissors: happy-path", async () => {
     playerTwoSalt = [...salt];
     const hash = generateHash([...salt], 3);
     playerTwoHash = [...hash];
-    const [bpsSettingsPDA] = getBPSSettingsPDAV2(program.programId);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
     const txId = await program.methods
-      .secondPlayerMove(playerTwoHash)
+      .secondPlayerMove(playerTwoHash, false)
       .accountsStrict({
         game: gamePDA,
         secondPlayer: playerTwo.publicKey,
         secondPlayerEscrow: escrowTwo,
         secondPlayerTokenAccount: ataTwo,
         mint: mint,
-        bpsSettingsV2: bpsSettingsPDA,
-        bpsTreasury: BPS_TREASURY_PUBKEY,
+        secondPlayerSideBet: (
+          await getGameSideBetPDA(program, gamePDA, playerTwo.publicKey)
+        )[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
         systemProgram: anchor.web3.SystemProgram.programId,
         associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: SPL.TOKEN_PROGRAM_ID,
@@ -285,40 +826,122 @@ describe("bonk-paper-scissors: happy-path", async () => {
     console.log("txid: ", txId);
   });
 
+  it("settings snapshot", async () => {
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const settings = await program.account.bpsSettingsV3.fetch(
+      bpsSettingsPDA
+    );
+    const game = await program.account.gameV3.fetch(gamePDA);
+    // Updating the settings from here on doesn't change this game.
+    if (
+      game.payoutBasisPoints !== settings.fees.payoutBasisPoints ||
+      game.timeForPenalization !==
+        settings.timers.timeForPenalization.toNumber() ||
+      !game.firstPlayerFeeLamports.eq(settings.fees.playerFeeLamports) ||
+      !game.secondPlayerFeeLamports.eq(settings.fees.playerFeeLamports)
+    ) {
+      throw new Error("Game should snapshot the settings");
+    }
+  });
+
   it("reveal (both)", async () => {
     const txId = await program.methods
-      .reveal({ bonk: {} }, playerOneSalt)
+      .reveal(CHOICE_BONK, playerOneSalt)
       .accountsStrict({
         game: gamePDA,
         player: playerOne.publicKey,
+        sessionToken: null,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([playerOne])
       .rpc();
     const txId2 = await program.methods
-      .reveal({ scissors: {} }, playerTwoSalt)
+      .reveal(CHOICE_SCISSORS, playerTwoSalt)
       .accountsStrict({
         game: gamePDA,
         player: playerTwo.publicKey,
+        sessionToken: null,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([playerTwo])
       .rpc();
     console.log("txid: ", txId);
     console.log("txid: ", txId2);
-    const result = await program.account.game.fetchNullable(gamePDA);
+    const result = await program.account.gameV3.fetchNullable(gamePDA);
     if (result === null) {
       throw new Error("Game account not found");
     }
     // console.log("result: ", result);
   });
 
+  it("audit_game", async () => {
+    const audit = await program.methods
+      .auditGame()
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayerEscrow: escrowOne,
+        secondPlayerEscrow: escrowTwo,
+      })
+      .view();
+    if (!audit.gameState.startedAndWaitingForReveal) {
+      throw new Error("Game should be waiting for reveals");
+    }
+    for (const escrow of audit.escrows) {
+      if (
+        escrow.expected.toNumber() !== 1_000 ||
+        !escrow.actual.eq(escrow.expected)
+      ) {
+        throw new Error(
+          `Escrow ${escrow.address} holds ${escrow.actual}, expected 1000`
+        );
+      }
+    }
+  });
+
+  it("get_game_status", async () => {
+    const status = await program.methods
+      .getGameStatus()
+      .accountsStrict({
+        game: gamePDA,
+        bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+      })
+      .view();
+    if (!status.claimable || !status.outcome?.firstPlayerWon) {
+      throw new Error("Game should be claimable by the first player");
+    }
+    // Both players revealed, nobody can forfeit or expire the game anymore.
+    if (status.forfeitsAt !== null || status.expiresAt !== null) {
+      throw new Error("Game shouldn't have deadlines left");
+    }
+  });
+
+  it("preview_settlement", async () => {
+    const preview = await program.methods
+      .previewSettlement()
+      .accountsStrict({
+        game: gamePDA,
+        bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+        mint,
+      })
+      .view();
+    if (
+      !preview.winner?.equals(playerOne.publicKey) ||
+      preview.firstPlayerPayout.toNumber() !== 1_800 ||
+      !preview.secondPlayerPayout.isZero() ||
+      preview.amountBurned.toNumber() !== 200
+    ) {
+      throw new Error(`Unexpected settlement ${JSON.stringify(preview)}`);
+    }
+  });
+
   it("claim", async () => {
-    const [bpsSettingsPDA] = getBPSSettingsPDAV2(program.programId);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
     const tx = await program.methods
       .claim()
       .accountsStrict({
-        bpsSettingsV2: bpsSettingsPDA,
+        bpsSettingsV3: bpsSettingsPDA,
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        season: null,
         firstPlayer: playerOne.publicKey,
         firstPlayerEscrow: escrowOne,
         firstPlayerTokenAccount: ataOne,
@@ -328,6 +951,16 @@ describe("bonk-paper-scissors: happy-path", async () => {
         secondPlayerTokenAccount: ataTwo,
 
         game: gamePDA,
+
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
         mint: mint,
         payer: tokenCreator.publicKey,
 
@@ -343,15 +976,123 @@ describe("bonk-paper-scissors: happy-path", async () => {
 
     console.log("txid: ", txId);
     // Fetch the account to see the new state.
-    const result = await program.account.game.fetchNullable(gamePDA);
+    const result = await program.account.gameV3.fetchNullable(gamePDA);
     if (result === null) {
       throw new Error("Game account not found");
     }
   });
-});
-
-describe("bonk-paper-scissors: cancelled", () => {
-  anchor.setProvider(anchor.AnchorProvider.env());
+
+  it("audit_game (settled)", async () => {
+    const audit = await program.methods
+      .auditGame()
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayerEscrow: escrowOne,
+        secondPlayerEscrow: escrowTwo,
+      })
+      .view();
+    // The claim emptied and closed both escrows.
+    for (const escrow of audit.escrows) {
+      if (!escrow.expected.isZero() || !escrow.actual.isZero()) {
+        throw new Error(`Escrow ${escrow.address} holds ${escrow.actual}`);
+      }
+    }
+  });
+
+  it("propose_rematch", async () => {
+    const [rematchPDA] = getGamePDA(
+      playerTwo.publicKey,
+      program.programId,
+      REMATCH_GAME_ID
+    );
+    const [rematchEscrow] = getEscrowPDA(
+      "first",
+      rematchPDA,
+      program.programId
+    );
+    const hash = generateHash([...generateSalt()], 2);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const txId = await program.methods
+      .proposeRematch(REMATCH_GAME_ID, [...hash])
+      .accountsStrict({
+        previousGame: gamePDA,
+        game: rematchPDA,
+        firstPlayerEscrow: rematchEscrow,
+        firstPlayerTokenAccount: ataTwo,
+        mint: mint,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        firstPlayer: playerTwo.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerTwo])
+      .rpc();
+    const result = await program.account.gameV3.fetchNullable(rematchPDA);
+    if (
+      result === null ||
+      !(result.flags & HAS_PREVIOUS_GAME) ||
+      !result.previousGame.equals(gamePDA)
+    ) {
+      throw new Error("Rematch should point to the previous game");
+    }
+    console.log("txid: ", txId);
+  });
+
+  it("accept_rematch", async () => {
+    const [rematchPDA] = getGamePDA(
+      playerTwo.publicKey,
+      program.programId,
+      REMATCH_GAME_ID
+    );
+    const [rematchEscrow] = getEscrowPDA(
+      "second",
+      rematchPDA,
+      program.programId
+    );
+    const hash = generateHash([...generateSalt()], 3);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const txId = await program.methods
+      .acceptRematch([...hash])
+      .accountsStrict({
+        game: rematchPDA,
+        previousGame: gamePDA,
+        secondPlayerEscrow: rematchEscrow,
+        secondPlayerTokenAccount: ataOne,
+        mint: mint,
+        secondPlayer: playerOne.publicKey,
+        secondPlayerSideBet: (
+          await getGameSideBetPDA(program, rematchPDA, playerOne.publicKey)
+        )[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+    const result = await program.account.gameV3.fetchNullable(rematchPDA);
+    if (
+      result === null ||
+      result.gameState !== GAME_STATE_STARTED_AND_WAITING_FOR_REVEAL
+    ) {
+      throw new Error("Rematch should be started");
+    }
+    console.log("txid: ", txId);
+  });
+});
+
+describe("bonk-paper-scissors: cancelled", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
 
   const program = anchor.workspace
     .BonkPaperScissors as Program<BonkPaperScissors>;
@@ -400,24 +1141,34 @@ describe("bonk-paper-scissors: cancelled", () => {
     const hash = generateHash([...salt], 1);
     playerOneHash = [...hash];
 
-    const [bpsSettingsPDA] = getBPSSettingsPDAV2(program.programId);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
     const txId = await program.methods
-      .firstPlayerMove(SECOND_GAME_ID, new anchor.BN(1_000), playerOneHash)
+      .firstPlayerMove(
+        SECOND_GAME_ID,
+        new anchor.BN(1_000),
+        playerOneHash,
+        false
+      )
       .accountsStrict({
         game: gamePDA,
         firstPlayer: playerOne.publicKey,
         firstPlayerEscrow: escrowOne,
         firstPlayerTokenAccount: ataOne,
         mint: mint,
-        bpsSettingsV2: bpsSettingsPDA,
-        bpsTreasury: BPS_TREASURY_PUBKEY,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        ruleset: null,
         systemProgram: anchor.web3.SystemProgram.programId,
         associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: SPL.TOKEN_PROGRAM_ID,
       })
       .signers([playerOne])
       .rpc();
-    const result = await program.account.game.fetchNullable(gamePDA);
+    const result = await program.account.gameV3.fetchNullable(gamePDA);
     if (result === null) {
       throw new Error("Game account not found");
     }
@@ -429,15 +1180,22 @@ describe("bonk-paper-scissors: cancelled", () => {
       .cancelGame()
       .accountsStrict({
         game: gamePDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+        feeVault: getFeeVaultPDA(program.programId)[0],
         firstPlayer: playerOne.publicKey,
         firstPlayerEscrow: escrowOne,
         firstPlayerTokenAccount: ataOne,
+        mint: mint,
         systemProgram: anchor.web3.SystemProgram.programId,
         tokenProgram: SPL.TOKEN_PROGRAM_ID,
       })
       .signers([playerOne])
       .rpc();
-    const result = await program.account.game.fetchNullable(gamePDA);
+    const result = await program.account.gameV3.fetchNullable(gamePDA);
     if (result) {
       throw new Error("Game account should not exist");
     }
@@ -495,24 +1253,34 @@ describe("bonk-paper-scissors: safety", () => {
     const hash = generateHash([...salt], 1);
     playerOneHash = [...hash];
 
-    const [bpsSettingsPDA] = getBPSSettingsPDAV2(program.programId);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
     const txId = await program.methods
-      .firstPlayerMove(SECOND_GAME_ID, new anchor.BN(1_000), playerOneHash)
+      .firstPlayerMove(
+        SECOND_GAME_ID,
+        new anchor.BN(1_000),
+        playerOneHash,
+        false
+      )
       .accountsStrict({
         game: gamePDA,
         firstPlayer: playerOne.publicKey,
         firstPlayerEscrow: escrowOne,
         firstPlayerTokenAccount: ataOne,
         mint: mint,
-        bpsSettingsV2: bpsSettingsPDA,
-        bpsTreasury: BPS_TREASURY_PUBKEY,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        ruleset: null,
         systemProgram: anchor.web3.SystemProgram.programId,
         associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: SPL.TOKEN_PROGRAM_ID,
       })
       .signers([playerOne])
       .rpc();
-    const result = await program.account.game.fetchNullable(gamePDA);
+    const result = await program.account.gameV3.fetchNullable(gamePDA);
     if (result === null) {
       throw new Error("Game account not found");
     }
@@ -539,3 +1307,2496 @@ describe("bonk-paper-scissors: safety", () => {
     }
   });
 });
+
+describe("bonk-paper-scissors: matchmaking queue", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const QUEUE_GAME_ID = "queuegame";
+  const STAKE = new anchor.BN(1_000);
+
+  let tokenCreator: anchor.web3.Keypair;
+  let playerOne: anchor.web3.Keypair;
+  let playerTwo: anchor.web3.Keypair;
+  let mint: anchor.web3.PublicKey;
+  let ataOne: anchor.web3.PublicKey;
+  let ataTwo: anchor.web3.PublicKey;
+  let queueTicketPDA: anchor.web3.PublicKey;
+  let queueTicketEscrow: anchor.web3.PublicKey;
+
+  it("join_queue (enqueue)", async () => {
+    // #region ----- SETUP -----
+    const results = await createAndFundAccounts(program);
+    tokenCreator = results.tokenCreator;
+    playerOne = results.playerOne;
+    playerTwo = results.playerTwo;
+
+    mint = await initializeMint(program, tokenCreator);
+    ataOne = (
+      await mintTo(program, tokenCreator, playerOne.publicKey, mint, 10_000)
+    ).ata;
+    ataTwo = (
+      await mintTo(program, tokenCreator, playerTwo.publicKey, mint, 10_000)
+    ).ata;
+    // #endregion ----- SETUP ----- END
+
+    [queueTicketPDA] = getQueueTicketPDA(
+      mint,
+      STAKE,
+      playerOne.publicKey,
+      program.programId
+    );
+    [queueTicketEscrow] = getQueueTicketEscrowPDA(
+      queueTicketPDA,
+      program.programId
+    );
+
+    const hash = generateHash([...generateSalt()], 1);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const txId = await program.methods
+      .joinQueue(QUEUE_GAME_ID, STAKE, [...hash])
+      .accountsStrict({
+        player: playerOne.publicKey,
+        playerTokenAccount: ataOne,
+        mint: mint,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        queueTicket: queueTicketPDA,
+        queueTicketEscrow: queueTicketEscrow,
+        queuedTicket: null,
+        queuedTicketEscrow: null,
+        queuedPlayer: null,
+        game: null,
+        firstPlayerEscrow: null,
+        secondPlayerEscrow: null,
+        firstPlayerOpenGames: null,
+        secondPlayerOpenGames: null,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+
+    const ticket = await program.account.queueTicket.fetchNullable(
+      queueTicketPDA
+    );
+    if (ticket === null) {
+      throw new Error("Queue ticket not found");
+    }
+    console.log("txid: ", txId);
+  });
+
+  it("join_queue (match)", async () => {
+    const [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      QUEUE_GAME_ID
+    );
+    const [escrowOne] = getEscrowPDA("first", gamePDA, program.programId);
+    const [escrowTwo] = getEscrowPDA("second", gamePDA, program.programId);
+
+    const hash = generateHash([...generateSalt()], 2);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const txId = await program.methods
+      .joinQueue(QUEUE_GAME_ID, STAKE, [...hash])
+      .accountsStrict({
+        player: playerTwo.publicKey,
+        playerTokenAccount: ataTwo,
+        mint: mint,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        queueTicket: null,
+        queueTicketEscrow: null,
+        queuedTicket: queueTicketPDA,
+        queuedTicketEscrow: queueTicketEscrow,
+        queuedPlayer: playerOne.publicKey,
+        game: gamePDA,
+        firstPlayerEscrow: escrowOne,
+        secondPlayerEscrow: escrowTwo,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerTwo])
+      .rpc();
+
+    const game = await program.account.gameV3.fetchNullable(gamePDA);
+    if (game === null) {
+      throw new Error("Game account not found");
+    }
+    if (game.gameState !== GAME_STATE_STARTED_AND_WAITING_FOR_REVEAL) {
+      throw new Error("Game should be started");
+    }
+    if (game.openGameSlots !== 3) {
+      throw new Error("Both players should hold a slot in the game");
+    }
+    // The game holds both fees, the queued player's came from its ticket.
+    const gameInfo = await program.provider.connection.getAccountInfo(gamePDA);
+    const gameRent =
+      await program.provider.connection.getMinimumBalanceForRentExemption(
+        gameInfo.data.length
+      );
+    if (
+      gameInfo.lamports - gameRent !== 2 * PLAYER_FEE_LAMPORTS.toNumber() ||
+      !game.firstPlayerFeeLamports.eq(PLAYER_FEE_LAMPORTS)
+    ) {
+      throw new Error("The game should hold both fees");
+    }
+    const startedGames = await program.account.gameV3.all([
+      {
+        memcmp: {
+          offset: GAME_STATE_OFFSET,
+          bytes: anchor.utils.bytes.bs58.encode([
+            GAME_STATE_STARTED_AND_WAITING_FOR_REVEAL,
+          ]),
+        },
+      },
+    ]);
+    if (!startedGames.some(({ publicKey }) => publicKey.equals(gamePDA))) {
+      throw new Error("Game state should be at its documented offset");
+    }
+    const ticket = await program.account.queueTicket.fetchNullable(
+      queueTicketPDA
+    );
+    if (ticket) {
+      throw new Error("Queue ticket should be closed");
+    }
+    console.log("txid: ", txId);
+  });
+
+  it("leave_queue refunds the fee", async () => {
+    const connection = program.provider.connection;
+    const hash = generateHash([...generateSalt()], 1);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    await program.methods
+      .joinQueue(QUEUE_GAME_ID, STAKE, [...hash])
+      .accountsStrict({
+        player: playerOne.publicKey,
+        playerTokenAccount: ataOne,
+        mint: mint,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        queueTicket: queueTicketPDA,
+        queueTicketEscrow: queueTicketEscrow,
+        queuedTicket: null,
+        queuedTicketEscrow: null,
+        queuedPlayer: null,
+        game: null,
+        firstPlayerEscrow: null,
+        secondPlayerEscrow: null,
+        firstPlayerOpenGames: null,
+        secondPlayerOpenGames: null,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+
+    const settings = await program.account.bpsSettingsV3.fetch(
+      bpsSettingsPDA
+    );
+    const ticketInfo = await connection.getAccountInfo(queueTicketPDA);
+    const escrowInfo = await connection.getAccountInfo(queueTicketEscrow);
+    const ticketRent = await connection.getMinimumBalanceForRentExemption(
+      ticketInfo.data.length
+    );
+    if (
+      ticketInfo.lamports - ticketRent !==
+      settings.fees.playerFeeLamports.toNumber()
+    ) {
+      throw new Error("Queue ticket should hold the fee");
+    }
+
+    const balanceBefore = await connection.getBalance(
+      playerOne.publicKey,
+      "confirmed"
+    );
+    const txId = await program.methods
+      .leaveQueue()
+      .accountsStrict({
+        queueTicket: queueTicketPDA,
+        queueTicketEscrow: queueTicketEscrow,
+        playerTokenAccount: ataOne,
+        mint: mint,
+        player: playerOne.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc({ commitment: "confirmed" });
+    const balanceAfter = await connection.getBalance(
+      playerOne.publicKey,
+      "confirmed"
+    );
+    const txFee = 5_000;
+    // Rent, fee and escrow rent come back, minus the transaction fee.
+    if (
+      balanceAfter - balanceBefore !==
+      ticketInfo.lamports + escrowInfo.lamports - txFee
+    ) {
+      throw new Error("Leaving the queue should refund the fee");
+    }
+    console.log("txid: ", txId);
+  });
+});
+
+describe("bonk-paper-scissors: side bets", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const SIDE_BET_GAME_ID = "sidebetgame";
+
+  let tokenCreator: anchor.web3.Keypair;
+  let playerOne: anchor.web3.Keypair;
+  let playerTwo: anchor.web3.Keypair;
+  let mint: anchor.web3.PublicKey;
+  let ataOne: anchor.web3.PublicKey;
+  let ataTwo: anchor.web3.PublicKey;
+  let ataSpectator: anchor.web3.PublicKey;
+  let gamePDA: anchor.web3.PublicKey;
+  let sidePoolPDA: anchor.web3.PublicKey;
+  let sidePoolVault: anchor.web3.PublicKey;
+
+  it("stage: first_player_move", async () => {
+    // #region ----- SETUP -----
+    const results = await createAndFundAccounts(program);
+    tokenCreator = results.tokenCreator;
+    playerOne = results.playerOne;
+    playerTwo = results.playerTwo;
+
+    mint = await initializeMint(program, tokenCreator);
+    ataOne = (
+      await mintTo(program, tokenCreator, playerOne.publicKey, mint, 10_000)
+    ).ata;
+    ataTwo = (
+      await mintTo(program, tokenCreator, playerTwo.publicKey, mint, 10_000)
+    ).ata;
+    ataSpectator = (
+      await mintTo(program, tokenCreator, tokenCreator.publicKey, mint, 10_000)
+    ).ata;
+    // #endregion ----- SETUP ----- END
+
+    [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      SIDE_BET_GAME_ID
+    );
+    const [escrowOne] = getEscrowPDA("first", gamePDA, program.programId);
+    const hash = generateHash([...generateSalt()], 1);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    await program.methods
+      .firstPlayerMove(
+        SIDE_BET_GAME_ID,
+        new anchor.BN(1_000),
+        [...hash],
+        false
+      )
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayer: playerOne.publicKey,
+        firstPlayerEscrow: escrowOne,
+        firstPlayerTokenAccount: ataOne,
+        mint: mint,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        ruleset: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+      })
+      .signers([playerOne])
+      .rpc();
+  });
+
+  it("init_side_pool", async () => {
+    const game = await program.account.gameV3.fetch(gamePDA);
+    [sidePoolPDA] = getSidePoolPDA(gamePDA, game.createdAt, program.programId);
+    [sidePoolVault] = getSidePoolVaultPDA(sidePoolPDA, program.programId);
+    const txId = await program.methods
+      .initSidePool()
+      .accountsStrict({
+        game: gamePDA,
+        sidePool: sidePoolPDA,
+        sidePoolVault: sidePoolVault,
+        mint: mint,
+        bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+        payer: tokenCreator.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([tokenCreator])
+      .rpc();
+    // Betting stays open until the game could be expired.
+    const sidePool = await program.account.sidePool.fetch(sidePoolPDA);
+    const expiresAt = game.createdAt.addn(game.timeForPenalization);
+    if (!sidePool.bettingClosesAt.eq(expiresAt)) {
+      throw new Error("Betting should close when the game can be expired");
+    }
+    console.log("txid: ", txId);
+  });
+
+  it("place_side_bet", async () => {
+    const [sideBetPDA] = getSideBetPDA(
+      sidePoolPDA,
+      tokenCreator.publicKey,
+      program.programId
+    );
+    const txId = await program.methods
+      .placeSideBet({ firstPlayerWon: {} }, new anchor.BN(500))
+      .accountsStrict({
+        game: gamePDA,
+        sidePool: sidePoolPDA,
+        sidePoolVault: sidePoolVault,
+        sideBet: sideBetPDA,
+        bettorTokenAccount: ataSpectator,
+        mint: mint,
+        bettor: tokenCreator.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([tokenCreator])
+      .rpc();
+    const sidePool = await program.account.sidePool.fetch(sidePoolPDA);
+    if (!sidePool.totalFirstPlayerWon.eq(new anchor.BN(500))) {
+      throw new Error("Side bet should be added to the pool");
+    }
+    console.log("txid: ", txId);
+  });
+
+  it("won't allow players to bet on their own game", async () => {
+    const [sideBetPDA] = getSideBetPDA(
+      sidePoolPDA,
+      playerOne.publicKey,
+      program.programId
+    );
+    try {
+      await program.methods
+        .placeSideBet({ secondPlayerWon: {} }, new anchor.BN(500))
+        .accountsStrict({
+          game: gamePDA,
+          sidePool: sidePoolPDA,
+          sidePoolVault: sidePoolVault,
+          sideBet: sideBetPDA,
+          bettorTokenAccount: ataOne,
+          mint: mint,
+          bettor: playerOne.publicKey,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([playerOne])
+        .rpc();
+      throw new Error("Should not be able to bet on own game");
+    } catch (error) {
+      if (error.message === "Should not be able to bet on own game") {
+        throw error;
+      }
+    }
+  });
+});
+
+describe("bonk-paper-scissors: session keys", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const SESSION_GAME_ID = "sessiongame";
+  const sessionSigner = anchor.web3.Keypair.generate();
+
+  let game: Awaited<ReturnType<typeof startGame>>;
+  let sessionTokenPDA: anchor.web3.PublicKey;
+
+  it("create_session", async () => {
+    game = await startGame(program, SESSION_GAME_ID, 1, 3);
+    [sessionTokenPDA] = getSessionTokenPDA(
+      game.playerOne.publicKey,
+      sessionSigner.publicKey,
+      program.programId
+    );
+    const now = Math.floor(Date.now() / 1000);
+    const txId = await program.methods
+      .createSession(
+        sessionSigner.publicKey,
+        new anchor.BN(now + 60 * 60),
+        new anchor.BN(1_000),
+        new anchor.BN(0.01 * anchor.web3.LAMPORTS_PER_SOL)
+      )
+      .accountsStrict({
+        sessionToken: sessionTokenPDA,
+        sessionSigner: sessionSigner.publicKey,
+        authority: game.playerOne.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([game.playerOne])
+      .rpc();
+    console.log("txid: ", txId);
+  });
+
+  it("reveal with the session signer", async () => {
+    const txId = await program.methods
+      .reveal(CHOICE_BONK, game.playerOneSalt)
+      .accountsStrict({
+        game: game.gamePDA,
+        player: sessionSigner.publicKey,
+        sessionToken: sessionTokenPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([sessionSigner])
+      .rpc();
+    const result = await program.account.gameV3.fetch(game.gamePDA);
+    if (
+      !(result.flags & FIRST_PLAYER_REVEALED) ||
+      result.firstPlayerChoice !== CHOICE_BONK
+    ) {
+      throw new Error("Session signer should reveal for the player");
+    }
+    console.log("txid: ", txId);
+  });
+
+  it("revoke_session", async () => {
+    const txId = await program.methods
+      .revokeSession()
+      .accountsStrict({
+        sessionToken: sessionTokenPDA,
+        authority: game.playerOne.publicKey,
+        signer: game.playerOne.publicKey,
+      })
+      .signers([game.playerOne])
+      .rpc();
+    const result = await program.account.sessionToken.fetchNullable(
+      sessionTokenPDA
+    );
+    if (result) {
+      throw new Error("Session token should be closed");
+    }
+    console.log("txid: ", txId);
+  });
+});
+
+describe("bonk-paper-scissors: relayed reveals", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const RELAYED_GAME_ID = "relayedgame";
+
+  let game: Awaited<ReturnType<typeof startGame>>;
+
+  it("deposits the relayer fee when players opt in", async () => {
+    game = await startGame(
+      program,
+      RELAYED_GAME_ID,
+      CHOICE_BONK,
+      CHOICE_SCISSORS,
+      null,
+      true
+    );
+    const result = await program.account.gameV3.fetch(game.gamePDA);
+    if (
+      !result.firstPlayerRelayerFeeLamports.eq(RELAYER_FEE_LAMPORTS) ||
+      !result.secondPlayerRelayerFeeLamports.eq(RELAYER_FEE_LAMPORTS)
+    ) {
+      throw new Error("Game should hold both relayer fees");
+    }
+  });
+
+  it("reveal_for", async () => {
+    const relayer = game.tokenCreator;
+    const connection = program.provider.connection;
+    const balanceBefore = await connection.getBalance(relayer.publicKey);
+    // Another payer covers the transaction fee, so the balance only moves by
+    // the relayer fee.
+    const txId = await program.methods
+      .revealFor(game.playerOne.publicKey, CHOICE_BONK, game.playerOneSalt)
+      .accountsStrict({
+        game: game.gamePDA,
+        relayer: relayer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([relayer])
+      .rpc();
+    const result = await program.account.gameV3.fetch(game.gamePDA);
+    if (
+      !(result.flags & FIRST_PLAYER_REVEALED) ||
+      result.firstPlayerChoice !== CHOICE_BONK
+    ) {
+      throw new Error("Relayer should reveal for the player");
+    }
+    const balanceAfter = await connection.getBalance(relayer.publicKey);
+    if (balanceAfter - balanceBefore !== RELAYER_FEE_LAMPORTS.toNumber()) {
+      throw new Error("Relayer should be paid the relayer fee");
+    }
+    console.log("txid: ", txId);
+  });
+
+  it("refunds the relayer fee on a self-reveal", async () => {
+    const connection = program.provider.connection;
+    const balanceBefore = await connection.getBalance(game.playerTwo.publicKey);
+    await program.methods
+      .reveal(CHOICE_SCISSORS, game.playerTwoSalt)
+      .accountsStrict({
+        game: game.gamePDA,
+        player: game.playerTwo.publicKey,
+        sessionToken: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([game.playerTwo])
+      .rpc();
+    const balanceAfter = await connection.getBalance(game.playerTwo.publicKey);
+    if (balanceAfter - balanceBefore !== RELAYER_FEE_LAMPORTS.toNumber()) {
+      throw new Error("Player should get the relayer fee back");
+    }
+  });
+});
+
+describe("bonk-paper-scissors: keeper", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const KEEPER_GAME_ID = "keepergame";
+
+  it("bps-keeper claims revealed games", async () => {
+    const game = await startGame(program, KEEPER_GAME_ID, 1, 3);
+    await program.methods
+      .reveal(CHOICE_BONK, game.playerOneSalt)
+      .accountsStrict({
+        game: game.gamePDA,
+        player: game.playerOne.publicKey,
+        sessionToken: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([game.playerOne])
+      .rpc();
+    await program.methods
+      .reveal(CHOICE_SCISSORS, game.playerTwoSalt)
+      .accountsStrict({
+        game: game.gamePDA,
+        player: game.playerTwo.publicKey,
+        sessionToken: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([game.playerTwo])
+      .rpc();
+
+    // The token creator cranks the claim and keeps the escrow rent.
+    const keypairPath = join(tmpdir(), `bps-keeper-${Date.now()}.json`);
+    writeFileSync(
+      keypairPath,
+      JSON.stringify([...game.tokenCreator.secretKey])
+    );
+    execFileSync(
+      "cargo",
+      [
+        "run",
+        "-q",
+        "-p",
+        "bps-keeper",
+        "--",
+        "--once",
+        "--rpc",
+        program.provider.connection.rpcEndpoint,
+        "--keypair",
+        keypairPath,
+      ],
+      { stdio: "inherit" }
+    );
+
+    const result = await program.account.gameV3.fetch(game.gamePDA);
+    if (result.gameState === GAME_STATE_STARTED_AND_WAITING_FOR_REVEAL) {
+      throw new Error("Keeper should claim the game");
+    }
+    const escrow = await program.provider.connection.getAccountInfo(
+      game.escrowOne
+    );
+    if (escrow) {
+      throw new Error("Escrow should be closed");
+    }
+  });
+});
+
+describe("bonk-paper-scissors: token-2022 transfer fees", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const FEE_GAME_ID = "feegame";
+  const TRANSFER_FEE_BASIS_POINTS = 100; // 1%
+
+  it("escrows the same amount for both players and closes the escrows", async () => {
+    const { tokenCreator, playerOne, playerTwo } = await createAndFundAccounts(
+      program
+    );
+    const mint = await initializeTransferFeeMint(
+      program,
+      tokenCreator,
+      TRANSFER_FEE_BASIS_POINTS,
+      1_000_000
+    );
+    const { ata: ataOne } = await mintTo(
+      program,
+      tokenCreator,
+      playerOne.publicKey,
+      mint,
+      10_000,
+      SPL.TOKEN_2022_PROGRAM_ID
+    );
+    const { ata: ataTwo } = await mintTo(
+      program,
+      tokenCreator,
+      playerTwo.publicKey,
+      mint,
+      10_000,
+      SPL.TOKEN_2022_PROGRAM_ID
+    );
+    const [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      FEE_GAME_ID
+    );
+    const [escrowOne] = getEscrowPDA("first", gamePDA, program.programId);
+    const [escrowTwo] = getEscrowPDA("second", gamePDA, program.programId);
+    const playerOneSalt = [...generateSalt()];
+    const playerTwoSalt = [...generateSalt()];
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+
+    await program.methods
+      .firstPlayerMove(
+        FEE_GAME_ID,
+        new anchor.BN(1_000),
+        [...generateHash(playerOneSalt, 1)],
+        false
+      )
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayer: playerOne.publicKey,
+        firstPlayerEscrow: escrowOne,
+        firstPlayerTokenAccount: ataOne,
+        mint: mint,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        ruleset: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([playerOne])
+      .rpc();
+    await program.methods
+      .secondPlayerMove([...generateHash(playerTwoSalt, 3)], false)
+      .accountsStrict({
+        game: gamePDA,
+        secondPlayer: playerTwo.publicKey,
+        secondPlayerEscrow: escrowTwo,
+        secondPlayerTokenAccount: ataTwo,
+        secondPlayerSideBet: (
+          await getGameSideBetPDA(program, gamePDA, playerTwo.publicKey)
+        )[0],
+        mint: mint,
+        bpsSettingsV3: bpsSettingsPDA,
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        systemProgram: anchor.web3.SystemProgram.programId,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([playerTwo])
+      .rpc();
+
+    // 1% of the first player's 1_000 was withheld, the second player covered the fee on top.
+    const game = await program.account.gameV3.fetch(gamePDA);
+    if (!game.amountToMatch.eq(new anchor.BN(990))) {
+      throw new Error("Stake should be what reached the escrow");
+    }
+    for (const escrow of [escrowOne, escrowTwo]) {
+      const account = await SPL.getAccount(
+        program.provider.connection,
+        escrow,
+        undefined,
+        SPL.TOKEN_2022_PROGRAM_ID
+      );
+      if (account.amount !== BigInt(990)) {
+        throw new Error("Both escrows should hold the stake");
+      }
+    }
+
+    for (const [player, choice, salt] of [
+      [playerOne, CHOICE_BONK, playerOneSalt],
+      [playerTwo, CHOICE_SCISSORS, playerTwoSalt],
+    ] as const) {
+      await program.methods
+        .reveal(choice, salt)
+        .accountsStrict({
+          game: gamePDA,
+          player: player.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+    }
+    await program.methods
+      .claim()
+      .accountsStrict({
+        bpsSettingsV3: bpsSettingsPDA,
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        season: null,
+        firstPlayer: playerOne.publicKey,
+        firstPlayerEscrow: escrowOne,
+        firstPlayerTokenAccount: ataOne,
+        secondPlayer: playerTwo.publicKey,
+        secondPlayerEscrow: escrowTwo,
+        secondPlayerTokenAccount: ataTwo,
+        game: gamePDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        mint: mint,
+        payer: tokenCreator.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([tokenCreator])
+      .rpc();
+
+    // The withheld fees were harvested to the mint so the escrows could be closed.
+    const escrow = await program.provider.connection.getAccountInfo(escrowOne);
+    if (escrow) {
+      throw new Error("Escrow should be closed");
+    }
+  });
+
+  it("won't allow mints with other extensions", async () => {
+    const { tokenCreator } = await createAndFundAccounts(program);
+    const mint = anchor.web3.Keypair.generate();
+    const mintLen = SPL.getMintLen([SPL.ExtensionType.MintCloseAuthority]);
+    const lamports =
+      await program.provider.connection.getMinimumBalanceForRentExemption(
+        mintLen
+      );
+    const tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: tokenCreator.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space: mintLen,
+        lamports,
+        programId: SPL.TOKEN_2022_PROGRAM_ID,
+      }),
+      SPL.createInitializeMintCloseAuthorityInstruction(
+        mint.publicKey,
+        tokenCreator.publicKey,
+        SPL.TOKEN_2022_PROGRAM_ID
+      ),
+      SPL.createInitializeMintInstruction(
+        mint.publicKey,
+        0,
+        tokenCreator.publicKey,
+        null,
+        SPL.TOKEN_2022_PROGRAM_ID
+      )
+    );
+    await anchor.web3.sendAndConfirmTransaction(
+      program.provider.connection,
+      tx,
+      [tokenCreator, mint]
+    );
+
+    try {
+      await allowMint(program, mint.publicKey);
+      throw new Error("Admin should not be able to allow the mint");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "UnsupportedMintExtension") {
+        throw error;
+      }
+    }
+  });
+});
+
+describe("bonk-paper-scissors: native SOL", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const SOL_STAKE = new anchor.BN(0.1 * anchor.web3.LAMPORTS_PER_SOL);
+
+  // Native SOL stakes are limited by the limits of the native mint.
+  before(async () => {
+    await allowMint(program, SPL.NATIVE_MINT);
+  });
+
+  const firstPlayerMoveSol = async (
+    playerOne: anchor.web3.Keypair,
+    gameId: string,
+    salt: number[],
+    amount = SOL_STAKE
+  ) => {
+    const [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      gameId
+    );
+    const [solVaultPDA] = getSolVaultPDA(gamePDA, program.programId);
+    await program.methods
+      .firstPlayerMoveSol(gameId, amount, [...generateHash(salt, 1)])
+      .accountsStrict({
+        game: gamePDA,
+        solVault: solVaultPDA,
+        stakeLimits: getStakeLimitsPDA(program.programId, SPL.NATIVE_MINT)[0],
+        bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        ruleset: null,
+        firstPlayer: playerOne.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+    return { gamePDA, solVaultPDA };
+  };
+
+  // Settled by the first test, player two won't rematch it.
+  let settled: {
+    tokenCreator: anchor.web3.Keypair;
+    playerTwo: anchor.web3.Keypair;
+    gamePDA: anchor.web3.PublicKey;
+  };
+
+  it("pays the winner and sends the fee to the fee vault", async () => {
+    const { tokenCreator, playerOne, playerTwo } = await createAndFundAccounts(
+      program
+    );
+    const playerOneSalt = [...generateSalt()];
+    const playerTwoSalt = [...generateSalt()];
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const { gamePDA, solVaultPDA } = await firstPlayerMoveSol(
+      playerOne,
+      "solgame",
+      playerOneSalt
+    );
+    await program.methods
+      .secondPlayerMoveSol([...generateHash(playerTwoSalt, 3)])
+      .accountsStrict({
+        game: gamePDA,
+        solVault: solVaultPDA,
+        secondPlayer: playerTwo.publicKey,
+        secondPlayerSideBet: (
+          await getGameSideBetPDA(program, gamePDA, playerTwo.publicKey)
+        )[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerTwo])
+      .rpc();
+
+    for (const [player, choice, salt] of [
+      [playerOne, CHOICE_BONK, playerOneSalt],
+      [playerTwo, CHOICE_SCISSORS, playerTwoSalt],
+    ] as const) {
+      await program.methods
+        .reveal(choice, salt)
+        .accountsStrict({
+          game: gamePDA,
+          player: player.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+    }
+
+    const connection = program.provider.connection;
+    const [feeVaultPDA] = getFeeVaultPDA(program.programId);
+    const vaultBefore = await connection.getBalance(feeVaultPDA);
+    const playerOneBefore = await connection.getBalance(playerOne.publicKey);
+    await program.methods
+      .claimSol()
+      .accountsStrict({
+        game: gamePDA,
+        solVault: solVaultPDA,
+        bpsSettingsV3: bpsSettingsPDA,
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        firstPlayer: playerOne.publicKey,
+        secondPlayer: playerTwo.publicKey,
+        payer: tokenCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([tokenCreator])
+      .rpc();
+
+    const fee = SOL_STAKE.divn(10).toNumber();
+    // Along with both players' fees, which the game held until now.
+    const playerFees = PLAYER_FEE_LAMPORTS.toNumber() * 2;
+    const vaultAfter = await connection.getBalance(feeVaultPDA);
+    if (vaultAfter - vaultBefore !== fee * 2 + playerFees) {
+      throw new Error("Fee vault should get 10% of each stake and the fees");
+    }
+    const playerOneAfter = await connection.getBalance(playerOne.publicKey);
+    const winnings = (SOL_STAKE.toNumber() - fee) * 2;
+    if (playerOneAfter - playerOneBefore !== winnings) {
+      throw new Error("Winner should get 90% of both stakes");
+    }
+    const game = await program.account.gameV3.fetch(gamePDA);
+    if (game.gameState !== GAME_STATE_FIRST_PLAYER_WON) {
+      throw new Error("First player should have won");
+    }
+    if (await connection.getAccountInfo(solVaultPDA)) {
+      throw new Error("Vault should be closed");
+    }
+    settled = { tokenCreator, playerTwo, gamePDA };
+  });
+
+  it("won't rematch a native SOL game", async () => {
+    const { tokenCreator, playerTwo, gamePDA } = settled;
+    const mint = await initializeMint(program, tokenCreator);
+    const { ata } = await mintTo(
+      program,
+      tokenCreator,
+      playerTwo.publicKey,
+      mint,
+      10_000
+    );
+    const [rematchPDA] = getGamePDA(
+      playerTwo.publicKey,
+      program.programId,
+      "solrematch"
+    );
+    try {
+      await program.methods
+        .proposeRematch("solrematch", [...generateHash([...generateSalt()], 1)])
+        .accountsStrict({
+          previousGame: gamePDA,
+          game: rematchPDA,
+          firstPlayerEscrow: getEscrowPDA(
+            "first",
+            rematchPDA,
+            program.programId
+          )[0],
+          firstPlayerTokenAccount: ata,
+          mint: mint,
+          stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+          bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+          firstPlayerOpenGames: getOpenGamesPDA(
+            program.programId,
+            playerTwo.publicKey
+          )[0],
+          firstPlayer: playerTwo.publicKey,
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([playerTwo])
+        .rpc();
+      throw new Error("Should not rematch a native SOL game");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "RematchNotSupported") {
+        throw error;
+      }
+    }
+  });
+
+  it("won't create a game without a stake", async () => {
+    const { playerOne } = await createAndFundAccounts(program);
+    try {
+      await firstPlayerMoveSol(
+        playerOne,
+        "solnostake",
+        [...generateSalt()],
+        new anchor.BN(0)
+      );
+      throw new Error("Should not create a SOL game without a stake");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "StakeBelowMinimum") {
+        throw error;
+      }
+    }
+  });
+
+  it("returns the stake when the game is cancelled", async () => {
+    const { playerOne } = await createAndFundAccounts(program);
+    const { gamePDA, solVaultPDA } = await firstPlayerMoveSol(
+      playerOne,
+      "solcancel",
+      [...generateSalt()]
+    );
+    await program.methods
+      .cancelSolGame()
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        solVault: solVaultPDA,
+        bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        firstPlayer: playerOne.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+
+    const connection = program.provider.connection;
+    for (const account of [gamePDA, solVaultPDA]) {
+      if (await connection.getAccountInfo(account)) {
+        throw new Error("Game and vault should be closed");
+      }
+    }
+  });
+});
+
+describe("bonk-paper-scissors: NFT wagers", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  // Loaded by the validator from `tests/fixtures`, see Anchor.toml.
+  const COLLECTION = new anchor.web3.PublicKey(
+    "FY4r1Zb3Eo8EfiAZ7RQjF8LLq9A6Kiz9n3kMybJ11LAL"
+  );
+  const NFT_ONE = new anchor.web3.PublicKey(
+    "GQFjroRubu7wJqNAGtvpoMfjwpETtigSECM8E3z7gkBe"
+  );
+  const NFT_TWO = new anchor.web3.PublicKey(
+    "CBf7zsBJcCmjrd6NrhQgMx2qwydPE1Uo8WBMaBQ2oUam"
+  );
+  const NFT_UNVERIFIED = new anchor.web3.PublicKey(
+    "24zDHe6i9cmtTHPosg9vFpGScrZeaX1ATDkqrdqYJxe2"
+  );
+  const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey(
+    "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+  );
+  const getMetadataPDA = (mint: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [b`metadata`, TOKEN_METADATA_PROGRAM_ID.toBytes(), mint.toBytes()],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0];
+  const ata = (owner: anchor.web3.PublicKey, mint: anchor.web3.PublicKey) =>
+    SPL.getAssociatedTokenAddressSync(mint, owner);
+
+  const nftAuthority = anchor.web3.Keypair.fromSecretKey(
+    Uint8Array.from(
+      JSON.parse(
+        readFileSync(join(__dirname, "fixtures/nft-authority.json"), "utf8")
+      )
+    )
+  );
+  const [allowedCollectionPDA] = getAllowedCollectionPDA(
+    COLLECTION,
+    program.programId
+  );
+  const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+
+  let playerOne: anchor.web3.Keypair;
+  let playerTwo: anchor.web3.Keypair;
+
+  const firstPlayerMoveNft = (
+    gameId: string,
+    mint: anchor.web3.PublicKey,
+    salt: number[]
+  ) => {
+    const [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      gameId
+    );
+    return program.methods
+      .firstPlayerMoveNft(gameId, [...generateHash(salt, 1)])
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayerEscrow: getEscrowPDA("first", gamePDA, program.programId)[0],
+        firstPlayerTokenAccount: ata(playerOne.publicKey, mint),
+        mint,
+        metadata: getMetadataPDA(mint),
+        allowedCollection: allowedCollectionPDA,
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        ruleset: null,
+        firstPlayer: playerOne.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne]);
+  };
+
+  it("allow_collection", async () => {
+    const accounts = await createAndFundAccounts(program);
+    playerOne = accounts.playerOne;
+    playerTwo = accounts.playerTwo;
+    const tx = await program.provider.connection.requestAirdrop(
+      nftAuthority.publicKey,
+      1_000_000_000
+    );
+    await program.provider.connection.confirmTransaction(tx);
+    await mintTo(program, nftAuthority, playerOne.publicKey, NFT_ONE, 1);
+    await mintTo(program, nftAuthority, playerTwo.publicKey, NFT_TWO, 1);
+    await mintTo(program, nftAuthority, playerTwo.publicKey, NFT_UNVERIFIED, 1);
+
+    await program.methods
+      .allowCollection(COLLECTION)
+      .accountsStrict({
+        allowedCollection: allowedCollectionPDA,
+        signer: program.provider.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("rejects NFTs that aren't verified in the collection", async () => {
+    const playerOneSalt = [...generateSalt()];
+    await firstPlayerMoveNft("nftunverified", NFT_ONE, playerOneSalt).rpc();
+    const [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      "nftunverified"
+    );
+    try {
+      await program.methods
+        .secondPlayerMoveNft([...generateHash([...generateSalt()], 2)])
+        .accountsStrict({
+          game: gamePDA,
+          secondPlayerEscrow: getEscrowPDA(
+            "second",
+            gamePDA,
+            program.programId
+          )[0],
+          secondPlayerTokenAccount: ata(playerTwo.publicKey, NFT_UNVERIFIED),
+          mint: NFT_UNVERIFIED,
+          metadata: getMetadataPDA(NFT_UNVERIFIED),
+          secondPlayer: playerTwo.publicKey,
+          secondPlayerSideBet: (
+            await getGameSideBetPDA(program, gamePDA, playerTwo.publicKey)
+          )[0],
+          bpsSettingsV3: bpsSettingsPDA,
+          secondPlayerOpenGames: getOpenGamesPDA(
+            program.programId,
+            playerTwo.publicKey
+          )[0],
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([playerTwo])
+        .rpc();
+      throw new Error("Unverified NFT should be rejected");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "CollectionMismatch") {
+        throw error;
+      }
+    }
+
+    // Cancelling returns the NFT to the first player.
+    await program.methods
+      .cancelNftGame()
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        firstPlayerEscrow: getEscrowPDA("first", gamePDA, program.programId)[0],
+        firstPlayerTokenAccount: ata(playerOne.publicKey, NFT_ONE),
+        mint: NFT_ONE,
+        bpsSettingsV3: getBPSSettingsPDAV3(program.programId)[0],
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        firstPlayer: playerOne.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+    const account = await SPL.getAccount(
+      program.provider.connection,
+      ata(playerOne.publicKey, NFT_ONE)
+    );
+    if (account.amount !== BigInt(1)) {
+      throw new Error("NFT should be back with the first player");
+    }
+  });
+
+  it("gives both NFTs to the winner", async () => {
+    const playerOneSalt = [...generateSalt()];
+    const playerTwoSalt = [...generateSalt()];
+    await firstPlayerMoveNft("nftgame", NFT_ONE, playerOneSalt).rpc();
+    const [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      "nftgame"
+    );
+    const [escrowOne] = getEscrowPDA("first", gamePDA, program.programId);
+    const [escrowTwo] = getEscrowPDA("second", gamePDA, program.programId);
+    await program.methods
+      .secondPlayerMoveNft([...generateHash(playerTwoSalt, 3)])
+      .accountsStrict({
+        game: gamePDA,
+        secondPlayerEscrow: escrowTwo,
+        secondPlayerTokenAccount: ata(playerTwo.publicKey, NFT_TWO),
+        mint: NFT_TWO,
+        metadata: getMetadataPDA(NFT_TWO),
+        secondPlayer: playerTwo.publicKey,
+        secondPlayerSideBet: (
+          await getGameSideBetPDA(program, gamePDA, playerTwo.publicKey)
+        )[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerTwo])
+      .rpc();
+
+    for (const [player, choice, salt] of [
+      [playerOne, CHOICE_BONK, playerOneSalt],
+      [playerTwo, CHOICE_SCISSORS, playerTwoSalt],
+    ] as const) {
+      await program.methods
+        .reveal(choice, salt)
+        .accountsStrict({
+          game: gamePDA,
+          player: player.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+    }
+    await program.methods
+      .claimNft()
+      .accountsStrict({
+        game: gamePDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerOne.publicKey
+        )[0],
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          playerTwo.publicKey
+        )[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        firstPlayerEscrow: escrowOne,
+        firstPlayerMint: NFT_ONE,
+        firstPlayerTokenAccount: ata(playerOne.publicKey, NFT_ONE),
+        firstPlayerPrizeTokenAccount: ata(playerOne.publicKey, NFT_TWO),
+        secondPlayerEscrow: escrowTwo,
+        secondPlayerMint: NFT_TWO,
+        secondPlayerTokenAccount: ata(playerTwo.publicKey, NFT_TWO),
+        secondPlayerPrizeTokenAccount: ata(playerTwo.publicKey, NFT_ONE),
+        firstPlayer: playerOne.publicKey,
+        secondPlayer: playerTwo.publicKey,
+        payer: nftAuthority.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([nftAuthority])
+      .rpc();
+
+    // The prize account didn't exist, the claim created it.
+    for (const mint of [NFT_ONE, NFT_TWO]) {
+      const account = await SPL.getAccount(
+        program.provider.connection,
+        ata(playerOne.publicKey, mint)
+      );
+      if (account.amount !== BigInt(1)) {
+        throw new Error("Winner should hold both NFTs");
+      }
+    }
+    for (const escrow of [escrowOne, escrowTwo]) {
+      if (await program.provider.connection.getAccountInfo(escrow)) {
+        throw new Error("Escrows should be closed");
+      }
+    }
+  });
+});
+
+describe("bonk-paper-scissors: elimination tables", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const TABLE_STAKE = new anchor.BN(1_000);
+
+  it("splits the pot between the players who weren't beaten", async () => {
+    const { tokenCreator, playerOne, playerTwo } = await createAndFundAccounts(
+      program
+    );
+    const playerThree = anchor.web3.Keypair.generate();
+    const connection = program.provider.connection;
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(playerThree.publicKey, 1_000_000_000)
+    );
+    const players = [playerOne, playerTwo, playerThree];
+    const mint = await initializeMint(program, tokenCreator);
+    const atas: anchor.web3.PublicKey[] = [];
+    for (const player of players) {
+      atas.push(
+        (await mintTo(program, tokenCreator, player.publicKey, mint, 10_000))
+          .ata
+      );
+    }
+    const [tablePDA] = getTablePDA(
+      playerOne.publicKey,
+      program.programId,
+      "table"
+    );
+    const escrows = players.map(
+      (player) =>
+        getTableEscrowPDA(tablePDA, player.publicKey, program.programId)[0]
+    );
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+
+    const [twoSeatsPDA] = getTablePDA(
+      playerOne.publicKey,
+      program.programId,
+      "twoseats"
+    );
+    try {
+      await program.methods
+        .createTable("twoseats", 2, 1, TABLE_STAKE)
+        .accountsStrict({
+          table: twoSeatsPDA,
+          creatorEscrow: getTableEscrowPDA(
+            twoSeatsPDA,
+            playerOne.publicKey,
+            program.programId
+          )[0],
+          creatorTokenAccount: atas[0],
+          mint,
+          stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+          bpsSettingsV3: bpsSettingsPDA,
+          feeVault: getFeeVaultPDA(program.programId)[0],
+          creator: playerOne.publicKey,
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([playerOne])
+        .rpc();
+      throw new Error("Two seats should be rejected");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InvalidSeatCount") {
+        throw error;
+      }
+    }
+
+    await program.methods
+      .createTable("table", 3, 1, TABLE_STAKE)
+      .accountsStrict({
+        table: tablePDA,
+        creatorEscrow: escrows[0],
+        creatorTokenAccount: atas[0],
+        mint,
+        stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+        bpsSettingsV3: bpsSettingsPDA,
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        creator: playerOne.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+    for (const i of [1, 2]) {
+      await program.methods
+        .joinTable()
+        .accountsStrict({
+          table: tablePDA,
+          playerEscrow: escrows[i],
+          playerTokenAccount: atas[i],
+          mint,
+          bpsSettingsV3: bpsSettingsPDA,
+          feeVault: getFeeVaultPDA(program.programId)[0],
+          player: players[i].publicKey,
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([players[i]])
+        .rpc();
+    }
+    let table = await program.account.table.fetch(tablePDA);
+    if (!("committing" in table.state) || table.round !== 1) {
+      throw new Error("A full table should start its first round");
+    }
+
+    // Two bonks beat the scissors.
+    const moves = [
+      { choice: { bonk: {} }, move: 1, salt: [...generateSalt()] },
+      { choice: { bonk: {} }, move: 1, salt: [...generateSalt()] },
+      { choice: { scissors: {} }, move: 3, salt: [...generateSalt()] },
+    ] as const;
+    for (const [i, { move, salt }] of moves.entries()) {
+      await program.methods
+        .commitTableMove([...generateHash(salt, move)])
+        .accountsStrict({
+          table: tablePDA,
+          bpsSettingsV3: bpsSettingsPDA,
+          player: players[i].publicKey,
+        })
+        .signers([players[i]])
+        .rpc();
+    }
+    for (const [i, { choice, salt }] of moves.entries()) {
+      await program.methods
+        .revealTableMove(choice, salt)
+        .accountsStrict({
+          table: tablePDA,
+          bpsSettingsV3: bpsSettingsPDA,
+          player: players[i].publicKey,
+        })
+        .signers([players[i]])
+        .rpc();
+    }
+    table = await program.account.table.fetch(tablePDA);
+    if (!("settled" in table.state) || !table.seats[2].eliminated) {
+      throw new Error("The scissors should be eliminated");
+    }
+
+    await program.methods
+      .claimTable()
+      .accountsStrict({
+        table: tablePDA,
+        mint,
+        payer: tokenCreator.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        [...escrows, atas[0], atas[1]].map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .signers([tokenCreator])
+      .rpc();
+
+    table = await program.account.table.fetch(tablePDA);
+    const share = (TABLE_STAKE.toNumber() * 3 * 9) / 10 / 2;
+    if (
+      !("claimed" in table.state) ||
+      table.payoutPerSurvivor.toNumber() !== share
+    ) {
+      throw new Error("Survivors should split 90% of the pot");
+    }
+    const winnerBalance = 10_000 - TABLE_STAKE.toNumber() + share;
+    for (const [i, balance] of [
+      winnerBalance,
+      winnerBalance,
+      9_000,
+    ].entries()) {
+      const account = await SPL.getAccount(connection, atas[i]);
+      if (account.amount !== BigInt(balance)) {
+        throw new Error(`Player ${i + 1} has the wrong balance`);
+      }
+    }
+    for (const escrow of escrows) {
+      if (await connection.getAccountInfo(escrow)) {
+        throw new Error("Escrows should be closed");
+      }
+    }
+  });
+});
+
+describe("bonk-paper-scissors: tournaments", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const ENTRY_FEE = new anchor.BN(1_000);
+
+  it("pays the prize pool to the winner of the bracket", async () => {
+    const { tokenCreator, playerOne, playerTwo } = await createAndFundAccounts(
+      program
+    );
+    const connection = program.provider.connection;
+    const mint = await initializeMint(program, tokenCreator);
+    const players = [playerOne, playerTwo];
+    const atas: anchor.web3.PublicKey[] = [];
+    for (const player of players) {
+      atas.push(
+        (await mintTo(program, tokenCreator, player.publicKey, mint, 10_000))
+          .ata
+      );
+    }
+    const [tournamentPDA] = getTournamentPDA(
+      tokenCreator.publicKey,
+      program.programId,
+      "tournament"
+    );
+    const [tournamentEscrow] = anchor.web3.PublicKey.findProgramAddressSync(
+      [tournamentPDA.toBytes(), b`escrow`],
+      program.programId
+    );
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+
+    const registrationEndsAt = new anchor.BN(
+      Math.floor(Date.now() / 1000) + 600
+    );
+    await program.methods
+      .createTournament("tournament", ENTRY_FEE, 2, registrationEndsAt, [
+        10_000,
+      ])
+      .accountsStrict({
+        tournament: tournamentPDA,
+        tournamentEscrow,
+        mint,
+        organizer: tokenCreator.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([tokenCreator])
+      .rpc();
+    for (const [i, player] of players.entries()) {
+      await program.methods
+        .registerForTournament()
+        .accountsStrict({
+          tournament: tournamentPDA,
+          tournamentEscrow,
+          playerTokenAccount: atas[i],
+          mint,
+          bpsSettingsV3: bpsSettingsPDA,
+          feeVault: getFeeVaultPDA(program.programId)[0],
+          player: player.publicKey,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+    }
+    // Full, so it starts before registration ends.
+    await program.methods
+      .startTournament()
+      .accountsStrict({
+        tournament: tournamentPDA,
+        bpsSettingsV3: bpsSettingsPDA,
+      })
+      .rpc();
+
+    const [gamePDA] = getGamePDA(
+      playerOne.publicKey,
+      program.programId,
+      "tournamentfinal"
+    );
+    const playerOneSalt = [...generateSalt()];
+    const playerTwoSalt = [...generateSalt()];
+    await program.methods
+      .firstPlayerMoveTournament("tournamentfinal", 0, [
+        ...generateHash(playerOneSalt, 1),
+      ])
+      .accountsStrict({
+        game: gamePDA,
+        tournament: tournamentPDA,
+        firstPlayer: playerOne.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([playerOne])
+      .rpc();
+    await program.methods
+      .secondPlayerMoveTournament([...generateHash(playerTwoSalt, 3)])
+      .accountsStrict({
+        game: gamePDA,
+        tournament: tournamentPDA,
+        secondPlayer: playerTwo.publicKey,
+        secondPlayerSideBet: (
+          await getGameSideBetPDA(program, gamePDA, playerTwo.publicKey)
+        )[0],
+      })
+      .signers([playerTwo])
+      .rpc();
+    for (const [player, choice, salt] of [
+      [playerOne, CHOICE_BONK, playerOneSalt],
+      [playerTwo, CHOICE_SCISSORS, playerTwoSalt],
+    ] as const) {
+      await program.methods
+        .reveal(choice, salt)
+        .accountsStrict({
+          game: gamePDA,
+          player: player.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+    }
+    await program.methods
+      .claimTournamentMatch()
+      .accountsStrict({
+        game: gamePDA,
+        tournament: tournamentPDA,
+        bpsSettingsV3: bpsSettingsPDA,
+      })
+      .rpc();
+    const tournament = await program.account.tournament.fetch(tournamentPDA);
+    if (!("finished" in tournament.state)) {
+      throw new Error("Tournament should be finished");
+    }
+
+    await program.methods
+      .claimTournamentPrize()
+      .accountsStrict({
+        tournament: tournamentPDA,
+        tournamentEscrow,
+        entrantTokenAccount: atas[0],
+        entrant: playerOne.publicKey,
+        mint,
+        organizer: tokenCreator.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const account = await SPL.getAccount(connection, atas[0]);
+    if (account.amount !== BigInt(11_000)) {
+      throw new Error("Winner should get the whole prize pool");
+    }
+    if (await connection.getAccountInfo(tournamentEscrow)) {
+      throw new Error("Escrow should be closed after the last prize");
+    }
+  });
+});
+
+describe("bonk-paper-scissors: seasons", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const SEASON_GAME_ID = "seasongame";
+  const REWARDS = 5_000;
+
+  it("pays the reward pool to the top of the leaderboard", async () => {
+    const game = await startGame(program, SEASON_GAME_ID, 1, 3);
+    const connection = program.provider.connection;
+    const [seasonPDA] = getSeasonPDA(program.programId, "season");
+    const [seasonVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [seasonPDA.toBytes(), b`escrow`],
+      program.programId
+    );
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createSeason(
+        "season",
+        new anchor.BN(now - 60),
+        new anchor.BN(now + 10),
+        new anchor.BN(100),
+        [7_000, 3_000]
+      )
+      .accountsStrict({
+        season: seasonPDA,
+        seasonVault,
+        mint: game.mint,
+        signer: program.provider.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const { ata: funderAta } = await mintTo(
+      program,
+      game.tokenCreator,
+      game.tokenCreator.publicKey,
+      game.mint,
+      REWARDS
+    );
+    await program.methods
+      .fundSeason(new anchor.BN(REWARDS))
+      .accountsStrict({
+        season: seasonPDA,
+        seasonVault,
+        funderTokenAccount: funderAta,
+        mint: game.mint,
+        funder: game.tokenCreator.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+      })
+      .signers([game.tokenCreator])
+      .rpc();
+
+    for (const [player, choice, salt] of [
+      [game.playerOne, CHOICE_BONK, game.playerOneSalt],
+      [game.playerTwo, CHOICE_SCISSORS, game.playerTwoSalt],
+    ] as const) {
+      await program.methods
+        .reveal(choice, salt)
+        .accountsStrict({
+          game: game.gamePDA,
+          player: player.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+    }
+    await program.methods
+      .claim()
+      .accountsStrict({
+        bpsSettingsV3: bpsSettingsPDA,
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        season: seasonPDA,
+        firstPlayer: game.playerOne.publicKey,
+        firstPlayerEscrow: game.escrowOne,
+        firstPlayerTokenAccount: game.ataOne,
+        secondPlayer: game.playerTwo.publicKey,
+        secondPlayerEscrow: game.escrowTwo,
+        secondPlayerTokenAccount: game.ataTwo,
+        game: game.gamePDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          game.playerOne.publicKey
+        )[0],
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          game.playerTwo.publicKey
+        )[0],
+        mint: game.mint,
+        payer: game.tokenCreator.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([game.tokenCreator])
+      .rpc();
+    let season = await program.account.season.fetch(seasonPDA);
+    if (
+      season.leaderboard.length !== 1 ||
+      !season.leaderboard[0].player.equals(game.playerOne.publicKey) ||
+      season.leaderboard[0].points.toNumber() !== 110
+    ) {
+      throw new Error("Winner should get 100 points plus 1 per 100 staked");
+    }
+
+    // The season can only be closed once it is over.
+    await new Promise((resolve) => setTimeout(resolve, 12_000));
+    await program.methods
+      .closeSeason()
+      .accountsStrict({
+        season: seasonPDA,
+        seasonVault,
+        refundTokenAccount: null,
+        mint: game.mint,
+        signer: program.provider.publicKey,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    season = await program.account.season.fetch(seasonPDA);
+    if (season.rewardPool.toNumber() !== REWARDS) {
+      throw new Error("Reward pool should be the vault balance");
+    }
+
+    const before = await SPL.getAccount(connection, game.ataOne);
+    await program.methods
+      .claimSeasonReward()
+      .accountsStrict({
+        season: seasonPDA,
+        seasonVault,
+        playerTokenAccount: game.ataOne,
+        player: game.playerOne.publicKey,
+        mint: game.mint,
+        bpsTreasury: BPS_TREASURY_PUBKEY,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+      })
+      .signers([game.playerOne])
+      .rpc();
+    const after = await SPL.getAccount(connection, game.ataOne);
+    // Nobody reached the second place, its share goes to the first.
+    if (after.amount - before.amount !== BigInt(REWARDS)) {
+      throw new Error("The only ranked player should get the whole pool");
+    }
+    if (await connection.getAccountInfo(seasonVault)) {
+      throw new Error("Vault should be closed after the last reward");
+    }
+  });
+});
+
+describe("bonk-paper-scissors: rulesets", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  const CHOICE_SPOCK = 4;
+  // Bit j of row i is set when choice i beats choice j.
+  const LIZARD_SPOCK_BEATS = [0b01100, 0b10001, 0b01010, 0b10010, 0b00101];
+  const [rulesetPDA] = getRulesetPDA(program.programId, "lizardspock");
+
+  it("create_ruleset", async () => {
+    try {
+      await program.methods
+        // Bonk and Paper beating each other.
+        .createRuleset("broken", 2, Buffer.from([0b10, 0b01]))
+        .accountsStrict({
+          ruleset: getRulesetPDA(program.programId, "broken")[0],
+          signer: program.provider.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      throw new Error("Inconsistent ruleset should be rejected");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InvalidRuleset") {
+        throw error;
+      }
+    }
+
+    await program.methods
+      .createRuleset("lizardspock", 5, Buffer.from(LIZARD_SPOCK_BEATS))
+      .accountsStrict({
+        ruleset: rulesetPDA,
+        signer: program.provider.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("settles games with the ruleset they were created with", async () => {
+    const game = await startGame(
+      program,
+      "spockgame",
+      CHOICE_SPOCK,
+      CHOICE_SCISSORS,
+      rulesetPDA
+    );
+    for (const [player, choice, salt] of [
+      [game.playerOne, CHOICE_SPOCK, game.playerOneSalt],
+      [game.playerTwo, CHOICE_SCISSORS, game.playerTwoSalt],
+    ] as const) {
+      await program.methods
+        .reveal(choice, salt)
+        .accountsStrict({
+          game: game.gamePDA,
+          player: player.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+    }
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    await program.methods
+      .claim()
+      .accountsStrict({
+        bpsSettingsV3: bpsSettingsPDA,
+        feeVault: getFeeVaultPDA(program.programId)[0],
+        season: null,
+        firstPlayer: game.playerOne.publicKey,
+        firstPlayerEscrow: game.escrowOne,
+        firstPlayerTokenAccount: game.ataOne,
+        secondPlayer: game.playerTwo.publicKey,
+        secondPlayerEscrow: game.escrowTwo,
+        secondPlayerTokenAccount: game.ataTwo,
+        game: game.gamePDA,
+        firstPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          game.playerOne.publicKey
+        )[0],
+        secondPlayerOpenGames: getOpenGamesPDA(
+          program.programId,
+          game.playerTwo.publicKey
+        )[0],
+        mint: game.mint,
+        payer: game.tokenCreator.publicKey,
+        associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([game.tokenCreator])
+      .rpc();
+    const result = await program.account.gameV3.fetch(game.gamePDA);
+    if (result.gameState !== GAME_STATE_FIRST_PLAYER_WON) {
+      throw new Error("Spock should smash Scissors");
+    }
+  });
+
+  it("rejects choices the ruleset doesn't have", async () => {
+    const game = await startGame(
+      program,
+      "classicspock",
+      CHOICE_SPOCK,
+      CHOICE_PAPER
+    );
+    try {
+      await program.methods
+        .reveal(CHOICE_SPOCK, game.playerOneSalt)
+        .accountsStrict({
+          game: game.gamePDA,
+          player: game.playerOne.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([game.playerOne])
+        .rpc();
+      throw new Error("Spock isn't a Bonk-Paper-Scissors choice");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InvalidChoice") {
+        throw error;
+      }
+    }
+  });
+});
+
+describe("bonk-paper-scissors: stake limits", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  it("only accepts the tiers the admin set for the mint", async () => {
+    const { tokenCreator, playerOne } = await createAndFundAccounts(program);
+    const limitedMint = await initializeMint(program, tokenCreator);
+    const openMint = await initializeMint(program, tokenCreator);
+    const unlistedMint = await SPL.createMint(
+      program.provider.connection,
+      tokenCreator,
+      tokenCreator.publicKey,
+      null,
+      0
+    );
+    for (const mint of [limitedMint, openMint, unlistedMint]) {
+      await mintTo(program, tokenCreator, playerOne.publicKey, mint, 10_000);
+    }
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const firstPlayerMove = (
+      gameId: string,
+      mint: anchor.web3.PublicKey,
+      amount: number
+    ) => {
+      const [gamePDA] = getGamePDA(
+        playerOne.publicKey,
+        program.programId,
+        gameId
+      );
+      return program.methods
+        .firstPlayerMove(
+          gameId,
+          new anchor.BN(amount),
+          [...generateHash([...generateSalt()], CHOICE_BONK)],
+          false
+        )
+        .accountsStrict({
+          game: gamePDA,
+          firstPlayer: playerOne.publicKey,
+          firstPlayerEscrow: getEscrowPDA(
+            "first",
+            gamePDA,
+            program.programId
+          )[0],
+          firstPlayerTokenAccount: SPL.getAssociatedTokenAddressSync(
+            mint,
+            playerOne.publicKey
+          ),
+          mint,
+          stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+          bpsSettingsV3: bpsSettingsPDA,
+          firstPlayerOpenGames: getOpenGamesPDA(
+            program.programId,
+            playerOne.publicKey
+          )[0],
+          ruleset: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+        })
+        .signers([playerOne]);
+    };
+
+    await program.methods
+      .updateStakeLimits(new anchor.BN(500), new anchor.BN(5_000), [
+        new anchor.BN(500),
+        new anchor.BN(1_000),
+        new anchor.BN(5_000),
+      ])
+      .accountsStrict({
+        stakeLimits: getStakeLimitsPDA(program.programId, limitedMint)[0],
+        signer: program.provider.publicKey,
+      })
+      .rpc();
+
+    for (const [gameId, mint, amount, code] of [
+      ["stakedust", limitedMint, 100, "StakeBelowMinimum"],
+      ["stakewhale", limitedMint, 6_000, "StakeAboveMaximum"],
+      ["staketier", limitedMint, 2_000, "StakeNotInTiers"],
+      ["stakezero", openMint, 0, "StakeBelowMinimum"],
+      ["stakeunlisted", unlistedMint, 1_000, "MintNotAllowed"],
+    ] as const) {
+      try {
+        await firstPlayerMove(gameId, mint, amount).rpc();
+        throw new Error(`A stake of ${amount} should be rejected`);
+      } catch (error) {
+        if (error.error?.errorCode?.code !== code) {
+          throw error;
+        }
+      }
+    }
+    await firstPlayerMove("stakebucket", limitedMint, 1_000).rpc();
+    // The limits `allowMint` sets take any stake above 0.
+    await firstPlayerMove("stakeopen", openMint, 1_234).rpc();
+  });
+});
+
+describe("bonk-paper-scissors: open games", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+
+  it("frees a slot once a game is over", async () => {
+    const { tokenCreator, playerOne } = await createAndFundAccounts(program);
+    const mint = await initializeMint(program, tokenCreator);
+    await mintTo(program, tokenCreator, playerOne.publicKey, mint, 10_000);
+    const ata = SPL.getAssociatedTokenAddressSync(mint, playerOne.publicKey);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const [openGamesPDA] = getOpenGamesPDA(
+      program.programId,
+      playerOne.publicKey
+    );
+    const accounts = (gameId: string) => {
+      const [gamePDA] = getGamePDA(
+        playerOne.publicKey,
+        program.programId,
+        gameId
+      );
+      return {
+        game: gamePDA,
+        firstPlayer: playerOne.publicKey,
+        firstPlayerEscrow: getEscrowPDA("first", gamePDA, program.programId)[0],
+        firstPlayerTokenAccount: ata,
+        mint,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+      };
+    };
+    const firstPlayerMove = (gameId: string) =>
+      program.methods
+        .firstPlayerMove(
+          gameId,
+          new anchor.BN(1_000),
+          [...generateHash([...generateSalt()], CHOICE_BONK)],
+          false
+        )
+        .accountsStrict({
+          ...accounts(gameId),
+          stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+          bpsSettingsV3: bpsSettingsPDA,
+          firstPlayerOpenGames: openGamesPDA,
+          ruleset: null,
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([playerOne])
+        .rpc();
+
+    await updateBpsSettings(program, { maxOpenGames: 1 });
+    try {
+      await firstPlayerMove("opengameone");
+      try {
+        await firstPlayerMove("opengametwo");
+        throw new Error("A second open game should be rejected");
+      } catch (error) {
+        if (error.error?.errorCode?.code !== "TooManyOpenGames") {
+          throw error;
+        }
+      }
+
+      await program.methods
+        .cancelGame()
+        .accountsStrict({
+          ...accounts("opengameone"),
+          bpsSettingsV3: bpsSettingsPDA,
+          feeVault: getFeeVaultPDA(program.programId)[0],
+          firstPlayerOpenGames: openGamesPDA,
+        })
+        .signers([playerOne])
+        .rpc();
+      await firstPlayerMove("opengametwo");
+      const openGames = await program.account.openGames.fetch(openGamesPDA);
+      if (openGames.count !== 1) {
+        throw new Error(`Expected 1 open game, got ${openGames.count}`);
+      }
+    } finally {
+      await updateBpsSettings(program, { maxOpenGames: MAX_OPEN_GAMES });
+    }
+  });
+});
+
+describe("bonk-paper-scissors: fee refunds", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+  const connection = program.provider.connection;
+
+  const updateFeeRefunds = (refundOnCancel: boolean, refundOnForfeit = true) =>
+    updateBpsSettings(program, {
+      flags:
+        (refundOnCancel ? REFUND_ON_CANCEL : 0) |
+        (refundOnForfeit ? REFUND_ON_FORFEIT : 0),
+    });
+
+  it("holds the fee until the game is cancelled", async () => {
+    const { tokenCreator, playerOne } = await createAndFundAccounts(program);
+    const mint = await initializeMint(program, tokenCreator);
+    await mintTo(program, tokenCreator, playerOne.publicKey, mint, 10_000);
+    const ata = SPL.getAssociatedTokenAddressSync(mint, playerOne.publicKey);
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const [openGamesPDA] = getOpenGamesPDA(
+      program.programId,
+      playerOne.publicKey
+    );
+    const accounts = (gameId: string) => {
+      const [gamePDA] = getGamePDA(
+        playerOne.publicKey,
+        program.programId,
+        gameId
+      );
+      return {
+        game: gamePDA,
+        firstPlayer: playerOne.publicKey,
+        firstPlayerEscrow: getEscrowPDA("first", gamePDA, program.programId)[0],
+        firstPlayerTokenAccount: ata,
+        mint,
+        bpsSettingsV3: bpsSettingsPDA,
+        firstPlayerOpenGames: openGamesPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: SPL.TOKEN_PROGRAM_ID,
+      };
+    };
+    // Returns what the fee vault got out of the cancelled game.
+    const createAndCancel = async (gameId: string) => {
+      await program.methods
+        .firstPlayerMove(
+          gameId,
+          new anchor.BN(1_000),
+          [...generateHash([...generateSalt()], CHOICE_BONK)],
+          false
+        )
+        .accountsStrict({
+          ...accounts(gameId),
+          stakeLimits: getStakeLimitsPDA(program.programId, mint)[0],
+          ruleset: null,
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([playerOne])
+        .rpc();
+      const game = await connection.getAccountInfo(accounts(gameId).game);
+      const rent = await connection.getMinimumBalanceForRentExemption(
+        game.data.length
+      );
+      if (game.lamports - rent !== PLAYER_FEE_LAMPORTS.toNumber()) {
+        throw new Error("The game should hold the fee");
+      }
+
+      const [feeVaultPDA] = getFeeVaultPDA(program.programId);
+      const vaultBefore = await connection.getBalance(feeVaultPDA);
+      await program.methods
+        .cancelGame()
+        .accountsStrict({
+          ...accounts(gameId),
+          feeVault: feeVaultPDA,
+        })
+        .signers([playerOne])
+        .rpc();
+      const vaultAfter = await connection.getBalance(feeVaultPDA);
+      return vaultAfter - vaultBefore;
+    };
+
+    try {
+      await updateFeeRefunds(true);
+      if ((await createAndCancel("feerefunded")) !== 0) {
+        throw new Error("The fee should be refunded");
+      }
+      await updateFeeRefunds(false);
+      const kept = await createAndCancel("feekept");
+      if (kept !== PLAYER_FEE_LAMPORTS.toNumber()) {
+        throw new Error(`The fee vault should keep the fee, got ${kept}`);
+      }
+    } finally {
+      await updateFeeRefunds(true);
+    }
+  });
+
+  it("holds the fee until the opponent forfeits", async () => {
+    const [bpsSettingsPDA] = getBPSSettingsPDAV3(program.programId);
+    const [feeVaultPDA] = getFeeVaultPDA(program.programId);
+    const settings = await program.account.bpsSettingsV3.fetch(
+      bpsSettingsPDA
+    );
+    // Returns what player one and the fee vault got out of the fees once
+    // player two forfeited.
+    const claimForfeit = async (gameId: string) => {
+      const game = await startGame(program, gameId, CHOICE_BONK, CHOICE_PAPER);
+      await program.methods
+        .reveal(CHOICE_BONK, game.playerOneSalt)
+        .accountsStrict({
+          game: game.gamePDA,
+          player: game.playerOne.publicKey,
+          sessionToken: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([game.playerOne])
+        .rpc();
+      // Player two forfeits once the one second penalization time is over.
+      await new Promise((resolve) => setTimeout(resolve, 3_000));
+
+      const playerBefore = await connection.getBalance(
+        game.playerOne.publicKey
+      );
+      const vaultBefore = await connection.getBalance(feeVaultPDA);
+      await program.methods
+        .claim()
+        .accountsStrict({
+          game: game.gamePDA,
+          firstPlayerOpenGames: getOpenGamesPDA(
+            program.programId,
+            game.playerOne.publicKey
+          )[0],
+          secondPlayerOpenGames: getOpenGamesPDA(
+            program.programId,
+            game.playerTwo.publicKey
+          )[0],
+          bpsSettingsV3: bpsSettingsPDA,
+          feeVault: feeVaultPDA,
+          season: null,
+          firstPlayerEscrow: game.escrowOne,
+          firstPlayerTokenAccount: game.ataOne,
+          secondPlayerEscrow: game.escrowTwo,
+          secondPlayerTokenAccount: game.ataTwo,
+          mint: game.mint,
+          firstPlayer: game.playerOne.publicKey,
+          secondPlayer: game.playerTwo.publicKey,
+          payer: program.provider.publicKey,
+          associatedTokenProgram: SPL.ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: SPL.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      const playerAfter = await connection.getBalance(game.playerOne.publicKey);
+      const vaultAfter = await connection.getBalance(feeVaultPDA);
+      return {
+        refunded: playerAfter - playerBefore,
+        collected: vaultAfter - vaultBefore,
+      };
+    };
+
+    const fee = settings.fees.playerFeeLamports.toNumber();
+    try {
+      await updateBpsSettings(program, {
+        timeForPenalization: new anchor.BN(1),
+      });
+      await updateFeeRefunds(true, true);
+      const refunded = await claimForfeit("forfeitrefunded");
+      // The forfeiting player's fee still goes to the fee vault.
+      if (refunded.refunded !== fee || refunded.collected !== fee) {
+        throw new Error("The player who revealed should get their fee back");
+      }
+      await updateFeeRefunds(true, false);
+      const kept = await claimForfeit("forfeitkept");
+      if (kept.refunded !== 0 || kept.collected !== 2 * fee) {
+        throw new Error("The fee vault should keep both fees");
+      }
+    } finally {
+      await updateFeeRefunds(true);
+      await updateBpsSettings(program, {
+        timeForPenalization: settings.timers.timeForPenalization,
+      });
+    }
+  });
+});
+
+describe("bonk-paper-scissors: fee vault", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace
+    .BonkPaperScissors as Program<BonkPaperScissors>;
+  const connection = program.provider.connection;
+  const [feeVaultPDA] = getFeeVaultPDA(program.programId);
+  const [revenueSplitPDA] = getRevenueSplitPDA(program.programId);
+
+  const updateFeeVault = (destination: anchor.web3.PublicKey) =>
+    program.methods
+      .updateFeeVault(destination)
+      .accountsStrict({
+        feeVault: feeVaultPDA,
+        signer: program.provider.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  // Recipients are passed to share the sweep once the revenue split exists.
+  const sweepFees = (
+    destination: anchor.web3.PublicKey,
+    recipients: anchor.web3.PublicKey[] = []
+  ) =>
+    program.methods
+      .sweepFees()
+      .accountsStrict({
+        feeVault: feeVaultPDA,
+        destination,
+        revenueSplit: revenueSplitPDA,
+        signer: program.provider.publicKey,
+      })
+      .remainingAccounts(
+        recipients.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .rpc();
+
+  it("sweeps the collected fees to the destination", async () => {
+    // The games of the other suites left their fees in the vault.
+    const destination = anchor.web3.Keypair.generate().publicKey;
+    const vault = await connection.getAccountInfo(feeVaultPDA);
+    const rent = await connection.getMinimumBalanceForRentExemption(
+      vault.data.length
+    );
+    const before = await program.account.feeVault.fetch(feeVaultPDA);
+
+    try {
+      await updateFeeVault(destination);
+      await sweepFees(destination);
+
+      const swept = await connection.getBalance(destination);
+      if (swept !== vault.lamports - rent) {
+        throw new Error(`Everything above rent should be swept, got ${swept}`);
+      }
+      if ((await connection.getBalance(feeVaultPDA)) !== rent) {
+        throw new Error("The vault should be left with its rent");
+      }
+      const after = await program.account.feeVault.fetch(feeVaultPDA);
+      if (after.totalSwept.sub(before.totalSwept).toNumber() !== swept) {
+        throw new Error("The sweep should be added to the total");
+      }
+
+      try {
+        await sweepFees(destination);
+        throw new Error("Sweeping an empty vault should fail");
+      } catch (error) {
+        if (error.error?.errorCode?.code !== "NothingToSweep") {
+          throw error;
+        }
+      }
+    } finally {
+      await updateFeeVault(BPS_TREASURY_PUBKEY);
+    }
+  });
+
+  it("splits the sweep between the revenue recipients", async () => {
+    const dao = anchor.web3.Keypair.generate().publicKey;
+    const charity = anchor.web3.Keypair.generate().publicKey;
+    const initRevenueSplit = (daoBasisPoints: number) =>
+      program.methods
+        .initRevenueSplit([
+          { address: dao, basisPoints: daoBasisPoints },
+          { address: charity, basisPoints: 3_000 },
+        ])
+        .accountsStrict({
+          revenueSplit: revenueSplitPDA,
+          signer: program.provider.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    try {
+      await initRevenueSplit(6_000);
+      throw new Error("Weights below 10000 basis points should be rejected");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InvalidRevenueSplit") {
+        throw error;
+      }
+    }
+    await initRevenueSplit(7_000);
+
+    // An odd amount, the rounding dust goes to the destination.
+    const amount = anchor.web3.LAMPORTS_PER_SOL + 1;
+    await program.provider.sendAndConfirm!(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: program.provider.publicKey,
+          toPubkey: feeVaultPDA,
+          lamports: amount,
+        })
+      )
+    );
+
+    try {
+      await sweepFees(BPS_TREASURY_PUBKEY);
+      throw new Error("The split shouldn't be skipped once it exists");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InvalidRevenueRecipients") {
+        throw error;
+      }
+    }
+    try {
+      await sweepFees(BPS_TREASURY_PUBKEY, [dao]);
+      throw new Error("Every recipient should be required");
+    } catch (error) {
+      if (error.error?.errorCode?.code !== "InvalidRevenueRecipients") {
+        throw error;
+      }
+    }
+    await sweepFees(BPS_TREASURY_PUBKEY, [dao, charity]);
+
+    const daoShare = await connection.getBalance(dao);
+    const charityShare = await connection.getBalance(charity);
+    if (daoShare !== 700_000_000 || charityShare !== 300_000_000) {
+      throw new Error(`Wrong shares: ${daoShare} and ${charityShare}`);
+    }
+  });
+});